@@ -0,0 +1,306 @@
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+
+use super::episode::{
+    display_episode_label, entry_title_and_total, episode_progress_position,
+    format_last_seen_display,
+};
+use super::tui::TuiAction;
+use crate::db::{
+    Database, EntryKind, RATING_RANGE, SCHEMA_VERSION, SeenEntry, normalize_tags,
+    normalize_watched_episodes,
+};
+
+/// Columns of the shareable `csv` and `markdown` exports, in order.
+pub(crate) const TABLE_EXPORT_COLUMNS: [&str; 4] = ["Title", "Episode", "Progress", "Last Seen"];
+
+const BACKUP_FORMAT: &str = "anitrack-backup";
+const BACKUP_FORMAT_VERSION: u64 = 1;
+
+/// One shareable row per entry, matching `TABLE_EXPORT_COLUMNS`. Progress is `watched/total`
+/// when the total is known (from the title or the override) and `-` otherwise.
+fn table_export_row(item: &SeenEntry) -> [String; 4] {
+    let (title, total) = entry_title_and_total(item);
+    let progress = total
+        .and_then(|total| {
+            let watched = episode_progress_position(&item.last_episode, total, None)?;
+            Some(format!("{watched}/{total}"))
+        })
+        .unwrap_or_else(|| "-".to_string());
+    [
+        title,
+        display_episode_label(&item.last_episode),
+        progress,
+        format_last_seen_display(&item.last_seen_at),
+    ]
+}
+
+/// RFC 4180 CSV with a header row.
+pub(crate) fn export_csv(items: &[SeenEntry]) -> String {
+    let field = |value: &str| {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+    let mut out = TABLE_EXPORT_COLUMNS.join(",");
+    out.push_str("\r\n");
+    for item in items {
+        let row = table_export_row(item);
+        out.push_str(
+            &row.iter()
+                .map(|value| field(value))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// A GitHub-flavored Markdown table, ready to paste into a README or forum post.
+pub(crate) fn export_markdown(items: &[SeenEntry]) -> String {
+    let cell = |value: &str| {
+        value
+            .replace('\\', "\\\\")
+            .replace('|', "\\|")
+            .replace(['\r', '\n'], " ")
+    };
+    let mut out = format!(
+        "| {} |\n|{}\n",
+        TABLE_EXPORT_COLUMNS.join(" | "),
+        " --- |".repeat(TABLE_EXPORT_COLUMNS.len())
+    );
+    for item in items {
+        let row = table_export_row(item);
+        out.push_str(&format!(
+            "| {} |\n",
+            row.iter()
+                .map(|value| cell(value))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ));
+    }
+    out
+}
+
+/// Entries as an `ani-cli` history file (`ep<TAB>id<TAB>title` per line), least recently watched
+/// first so the last line is the latest show, as `ani-cli` appends it. Fields are written as
+/// stored, except that tabs and line breaks in a title become spaces to keep one line per show.
+pub(crate) fn export_history(items: &[SeenEntry]) -> String {
+    let mut ordered = items.iter().collect::<Vec<_>>();
+    ordered.sort_by(|a, b| a.last_seen_at.cmp(&b.last_seen_at));
+    ordered
+        .into_iter()
+        .map(|item| {
+            let title = item.title.replace(['\t', '\n', '\r'], " ");
+            format!("{}\t{}\t{}\n", item.last_episode, item.ani_id, title)
+        })
+        .collect()
+}
+
+/// Serializes every tracked entry into a versioned backup document. The header records the
+/// backup format version and the database schema version the entries were read from.
+pub(crate) fn export_backup(db: &Database) -> Result<String> {
+    let entries = db
+        .list_seen()?
+        .iter()
+        .map(|item| {
+            json!({
+                "ani_id": item.ani_id,
+                "title": item.title,
+                "last_episode": item.last_episode,
+                "last_seen_at": item.last_seen_at,
+                "first_seen_at": item.first_seen_at,
+                "intro_skip_seconds": item.intro_skip_seconds,
+                "tags": item.tags,
+                "kind": item.kind.as_str(),
+                "total_eps_override": item.total_eps_override,
+                "default_action": item.default_action,
+                "airing_status": item.airing_status,
+                "rating": item.rating,
+                "external_provider": item.external_provider,
+                "external_id": item.external_id,
+                "watched_episodes": item.watched_episodes,
+            })
+        })
+        .collect::<Vec<_>>();
+    let backup = json!({
+        "format": BACKUP_FORMAT,
+        "format_version": BACKUP_FORMAT_VERSION,
+        "schema_version": db.schema_version()?,
+        "entries": entries,
+    });
+    Ok(serde_json::to_string_pretty(&backup)?)
+}
+
+/// Parses a backup document, upgrading entries from older schemas: columns added after the
+/// backup's schema version take their migration defaults.
+pub(crate) fn parse_backup(raw: &str) -> Result<Vec<SeenEntry>> {
+    let backup: Value = serde_json::from_str(raw).context("backup is not valid JSON")?;
+    if backup.get("format").and_then(Value::as_str) != Some(BACKUP_FORMAT) {
+        bail!("not an anitrack backup (missing `\"format\": \"{BACKUP_FORMAT}\"` header)");
+    }
+    let format_version = backup
+        .get("format_version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| anyhow!("backup header is missing `format_version`"))?;
+    if format_version > BACKUP_FORMAT_VERSION {
+        bail!(
+            "backup format version {format_version} is newer than supported {BACKUP_FORMAT_VERSION}; upgrade anitrack to restore it"
+        );
+    }
+    let schema_version = backup
+        .get("schema_version")
+        .and_then(Value::as_i64)
+        .ok_or_else(|| anyhow!("backup header is missing `schema_version`"))?;
+    if schema_version > SCHEMA_VERSION {
+        bail!(
+            "backup was taken with database schema {schema_version}, newer than supported {SCHEMA_VERSION}; upgrade anitrack to restore it"
+        );
+    }
+
+    backup
+        .get("entries")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("backup is missing the `entries` array"))?
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| parse_backup_entry(entry).with_context(|| format!("entry {idx}")))
+        .collect()
+}
+
+fn parse_backup_entry(entry: &Value) -> Result<SeenEntry> {
+    let field = |name: &str| {
+        entry
+            .get(name)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("missing `{name}`"))
+    };
+    let intro_skip_seconds = match entry.get("intro_skip_seconds") {
+        None => 0,
+        Some(value) => value
+            .as_u64()
+            .and_then(|seconds| u32::try_from(seconds).ok())
+            .ok_or_else(|| anyhow!("invalid `intro_skip_seconds`"))?,
+    };
+    let tags = match entry.get("tags") {
+        None => Vec::new(),
+        Some(value) => normalize_tags(
+            value
+                .as_array()
+                .ok_or_else(|| anyhow!("invalid `tags`"))?
+                .iter()
+                .map(|tag| tag.as_str().ok_or_else(|| anyhow!("invalid `tags`")))
+                .collect::<Result<Vec<_>>>()?,
+        ),
+    };
+    let kind = match entry.get("kind") {
+        None => EntryKind::default(),
+        Some(value) => value
+            .as_str()
+            .and_then(EntryKind::parse)
+            .ok_or_else(|| anyhow!("invalid `kind`"))?,
+    };
+    let total_eps_override = match entry.get("total_eps_override") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            value
+                .as_u64()
+                .and_then(|total| u32::try_from(total).ok())
+                .ok_or_else(|| anyhow!("invalid `total_eps_override`"))?,
+        ),
+    };
+    let default_action = match entry.get("default_action") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            value
+                .as_str()
+                .and_then(TuiAction::parse)
+                .ok_or_else(|| anyhow!("invalid `default_action`"))?
+                .as_str()
+                .to_string(),
+        ),
+    };
+    let airing_status = match entry.get("airing_status") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            value
+                .as_str()
+                .ok_or_else(|| anyhow!("invalid `airing_status`"))?
+                .to_string(),
+        ),
+    };
+    let rating = match entry.get("rating") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            value
+                .as_u64()
+                .and_then(|rating| u8::try_from(rating).ok())
+                .filter(|rating| RATING_RANGE.contains(rating))
+                .ok_or_else(|| anyhow!("invalid `rating` (expected 1-10)"))?,
+        ),
+    };
+    let optional_text = |name: &str| match entry.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_str()
+            .map(|text| Some(text.to_string()))
+            .ok_or_else(|| anyhow!("invalid `{name}`")),
+    };
+    let external_provider = optional_text("external_provider")?;
+    let external_id = optional_text("external_id")?;
+    if external_provider.is_some() != external_id.is_some() {
+        bail!("`external_provider` and `external_id` must be set together");
+    }
+    let watched_episodes = match entry.get("watched_episodes") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(normalize_watched_episodes(
+            value
+                .as_array()
+                .ok_or_else(|| anyhow!("invalid `watched_episodes`"))?
+                .iter()
+                .map(|label| {
+                    label
+                        .as_str()
+                        .ok_or_else(|| anyhow!("invalid `watched_episodes`"))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        )),
+    };
+    let last_seen_at = field("last_seen_at")?;
+    // Backups from before first-seen times were recorded get the same backfill as the migration.
+    let first_seen_at = match entry.get("first_seen_at") {
+        None => last_seen_at.clone(),
+        Some(value) => value
+            .as_str()
+            .ok_or_else(|| anyhow!("invalid `first_seen_at`"))?
+            .to_string(),
+    };
+    Ok(SeenEntry {
+        ani_id: field("ani_id")?,
+        title: field("title")?,
+        last_episode: field("last_episode")?,
+        last_seen_at,
+        first_seen_at,
+        intro_skip_seconds,
+        tags,
+        kind,
+        total_eps_override,
+        default_action,
+        airing_status,
+        rating,
+        external_provider,
+        external_id,
+        watched_episodes,
+    })
+}
+
+/// Restores a backup into the database, replacing entries with the same `ani_id`.
+pub(crate) fn import_backup(db: &Database, raw: &str) -> Result<usize> {
+    let entries = parse_backup(raw)?;
+    db.restore_seen_entries(&entries)?;
+    Ok(entries.len())
+}
@@ -0,0 +1,77 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::cli::ColorChoice;
+
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Paint {
+    Bold,
+    Green,
+    Yellow,
+}
+
+impl Paint {
+    fn code(self) -> &'static str {
+        match self {
+            Self::Bold => "1",
+            Self::Green => "32",
+            Self::Yellow => "33",
+        }
+    }
+}
+
+/// `auto` colors only when stdout is a terminal and `NO_COLOR` is unset or empty.
+pub(crate) fn color_enabled(choice: ColorChoice, stdout_is_terminal: bool, no_color: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => stdout_is_terminal && !no_color,
+    }
+}
+
+/// Sets whether `paint` emits ANSI colors for the rest of the process.
+pub(crate) fn set_color_enabled(enabled: bool) {
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Wraps `text` in an ANSI color when colors are on. Pad fixed-width columns before painting so
+/// the escape codes never count toward the width.
+pub(crate) fn paint(text: &str, paint: Paint) -> String {
+    paint_if(COLOR_ENABLED.load(Ordering::Relaxed), text, paint)
+}
+
+pub(crate) fn paint_if(enabled: bool, text: &str, paint: Paint) -> String {
+    if enabled && !text.is_empty() {
+        format!("\x1b[{}m{text}\x1b[0m", paint.code())
+    } else {
+        text.to_string()
+    }
+}
+
+/// The `Warning:` label, yellow when colors are on.
+pub(crate) fn warning_label() -> String {
+    paint("Warning:", Paint::Yellow)
+}
+
+/// Colors a multi-line result message line by line: the `Recorded last seen` line green and
+/// `Warning:` labels yellow. Messages are built uncolored because the TUI shows them too.
+pub(crate) fn paint_message(message: &str) -> String {
+    paint_message_if(COLOR_ENABLED.load(Ordering::Relaxed), message)
+}
+
+pub(crate) fn paint_message_if(enabled: bool, message: &str) -> String {
+    message
+        .lines()
+        .map(|line| {
+            if line.starts_with("Recorded last seen:") {
+                paint_if(enabled, line, Paint::Green)
+            } else if let Some(rest) = line.strip_prefix("Warning:") {
+                format!("{}{rest}", paint_if(enabled, "Warning:", Paint::Yellow))
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
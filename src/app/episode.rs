@@ -1,11 +1,55 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::sync::atomic::{self, AtomicBool};
 use std::time::Duration;
 
+use anyhow::Result;
 use chrono::{DateTime, Local};
 use serde_json::Value;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+use crate::cli::EpisodeFormat;
+use crate::db::{Database, EntryKind, SeenEntry};
 use crate::http::get_text_with_retries;
 
+/// Words recognized after the count in a title's `(N episodes)` suffix, matched ignoring case,
+/// for ani-cli builds that translate it.
+const DEFAULT_EPISODE_SUFFIXES: [&str; 7] = [
+    "episodes",
+    "épisodes",
+    "episodios",
+    "episódios",
+    "episodi",
+    "folgen",
+    "afleveringen",
+];
+
+static EXTRA_EPISODE_SUFFIXES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Adds comma-separated words to recognize in the `(N episodes)` suffix, for the rest of the
+/// process.
+pub(crate) fn set_extra_episode_suffixes(raw: Option<&str>) {
+    let extra = raw
+        .unwrap_or_default()
+        .split(',')
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    if !extra.is_empty() {
+        let _ = EXTRA_EPISODE_SUFFIXES.set(extra);
+    }
+}
+
+fn is_episode_suffix(word: &str) -> bool {
+    let word = word.to_lowercase();
+    DEFAULT_EPISODE_SUFFIXES.contains(&word.as_str())
+        || EXTRA_EPISODE_SUFFIXES
+            .get()
+            .is_some_and(|extra| extra.contains(&word))
+}
+
 pub(crate) fn parse_title_and_total_eps(title: &str) -> (String, Option<u32>) {
     let trimmed = title.trim();
     let Some(open_idx) = trimmed.rfind('(') else {
@@ -15,17 +59,177 @@ pub(crate) fn parse_title_and_total_eps(title: &str) -> (String, Option<u32>) {
         return (trimmed.to_string(), None);
     }
     let inner = trimmed[open_idx + 1..trimmed.len() - 1].trim();
-    let Some(num_str) = inner.strip_suffix(" episodes") else {
+    let Some((num_str, word)) = inner.split_once(char::is_whitespace) else {
         return (trimmed.to_string(), None);
     };
-    let Ok(num) = num_str.trim().parse::<u32>() else {
+    if !is_episode_suffix(word.trim()) {
+        return (trimmed.to_string(), None);
+    }
+    let Ok(num) = num_str.parse::<u32>() else {
         return (trimmed.to_string(), None);
     };
     (trimmed[..open_idx].trim().to_string(), Some(num))
 }
 
+/// `parse_title_and_total_eps` for a tracked entry, falling back to the stored episode-count
+/// override when the title has no `(N episodes)` suffix.
+pub(crate) fn entry_title_and_total(item: &SeenEntry) -> (String, Option<u32>) {
+    let (title, total) = parse_title_and_total_eps(&item.title);
+    (title, total.or(item.total_eps_override))
+}
+
+static STORE_CLEAN_TITLES: AtomicBool = AtomicBool::new(false);
+
+/// Whether newly recorded titles drop their `(N episodes)` suffix, for the rest of the process.
+pub(crate) fn set_store_clean_titles(enabled: bool) {
+    STORE_CLEAN_TITLES.store(enabled, atomic::Ordering::Relaxed);
+}
+
+static TRACK_WATCHED_EPISODES: AtomicBool = AtomicBool::new(false);
+
+/// Whether successful playback also adds the episode to the entry's watched episodes, for the
+/// rest of the process.
+pub(crate) fn set_track_watched_episodes(enabled: bool) {
+    TRACK_WATCHED_EPISODES.store(enabled, atomic::Ordering::Relaxed);
+}
+
+/// Adds a just-played episode to the entry's watched episodes, if tracking them is enabled.
+pub(crate) fn record_watched_episode(db: &Database, ani_id: &str, episode: &str) -> Result<()> {
+    if TRACK_WATCHED_EPISODES.load(atomic::Ordering::Relaxed) {
+        db.add_watched_episode(ani_id, episode)?;
+    }
+    Ok(())
+}
+
+/// The `last_episode` to store after playing `played`. While watched episodes are tracked it stays
+/// the furthest episode watched, so going back to an earlier one only adds to the watched set.
+pub(crate) fn last_episode_after_play(stored: &str, played: &str) -> String {
+    if TRACK_WATCHED_EPISODES.load(atomic::Ordering::Relaxed)
+        && compare_episode_labels(stored, played) == Ordering::Greater
+    {
+        stored.to_string()
+    } else {
+        played.to_string()
+    }
+}
+
+/// The title and episode-count override to store for a title read from `ani-cli` history. Titles
+/// are kept verbatim unless clean titles are enabled.
+pub(crate) fn stored_title_and_total(raw_title: &str) -> (String, Option<u32>) {
+    if STORE_CLEAN_TITLES.load(atomic::Ordering::Relaxed) {
+        parse_title_and_total_eps(raw_title)
+    } else {
+        (raw_title.to_string(), None)
+    }
+}
+
+/// Display-only title casing for titles that arrive entirely lowercase or entirely uppercase.
+/// Mixed-case titles are returned unchanged, since their casing (and any acronyms) is deliberate.
+/// Short connecting words stay lowercase after the first word, and in all-caps titles, words with
+/// no vowels (`TV`, `BD`) or Roman numerals (`II`, `IV`) keep their capitals.
+pub(crate) fn title_case_display(title: &str) -> String {
+    const MINOR_WORDS: [&str; 12] = [
+        "a", "an", "and", "as", "at", "in", "of", "on", "or", "the", "to", "x",
+    ];
+    let has_lower = title.chars().any(char::is_lowercase);
+    let has_upper = title.chars().any(char::is_uppercase);
+    if has_lower == has_upper {
+        return title.to_string();
+    }
+
+    let mut out = String::with_capacity(title.len());
+    let mut first_word = true;
+    for (idx, word) in title.split(' ').enumerate() {
+        if idx > 0 {
+            out.push(' ');
+        }
+        if word.is_empty() {
+            continue;
+        }
+        let lower = word.to_lowercase();
+        let keep_caps = has_upper && (is_roman_numeral(word) || !has_vowel(&lower));
+        if !first_word && MINOR_WORDS.contains(&lower.as_str()) {
+            out.push_str(&lower);
+        } else if keep_caps {
+            out.push_str(word);
+        } else {
+            out.push_str(&capitalize_first_letter(&lower));
+        }
+        first_word = false;
+    }
+    out
+}
+
+/// Words with digits (`2nd`) or no letters at all count as having vowels so they are not kept
+/// in capitals as acronyms.
+fn has_vowel(word: &str) -> bool {
+    if word.chars().any(|ch| ch.is_ascii_digit()) {
+        return true;
+    }
+    let letters = word
+        .chars()
+        .filter(|ch| ch.is_alphabetic())
+        .collect::<Vec<_>>();
+    letters.is_empty() || letters.iter().any(|ch| "aeiouy".contains(*ch))
+}
+
+fn is_roman_numeral(word: &str) -> bool {
+    let letters = word.trim_matches(|ch: char| !ch.is_alphanumeric());
+    (2..=4).contains(&letters.len()) && letters.chars().all(|ch| "IVX".contains(ch))
+}
+
+fn capitalize_first_letter(word: &str) -> String {
+    match word.char_indices().find(|(_, ch)| ch.is_alphanumeric()) {
+        Some((idx, ch)) if ch.is_alphabetic() => {
+            let mut out = String::with_capacity(word.len());
+            out.push_str(&word[..idx]);
+            out.extend(ch.to_uppercase());
+            out.push_str(&word[idx + ch.len_utf8()..]);
+            out
+        }
+        _ => word.to_string(),
+    }
+}
+
+/// Numeric value of an episode label; a range label such as `1-2` counts as its first episode.
 pub(crate) fn parse_episode_f64(ep: &str) -> Option<f64> {
-    ep.trim().parse::<f64>().ok()
+    let ep = ep.trim();
+    ep.parse::<f64>()
+        .ok()
+        .or_else(|| episode_range_start(ep)?.parse::<f64>().ok())
+}
+
+/// The first episode of a combined-episode label such as `1-2`, which some sources use for
+/// double episodes. Anything else, including a negative number like `-1`, is not a range.
+fn episode_range_start(label: &str) -> Option<&str> {
+    let (start, end) = label.split_once('-')?;
+    let is_episode = |part: &str| {
+        part.parse::<f64>()
+            .is_ok_and(|value| value.is_finite() && value >= 0.0)
+    };
+    let (start, end) = (start.trim(), end.trim());
+    (is_episode(start) && is_episode(end)).then_some(start)
+}
+
+/// Display form of an episode label: strips zero padding from the integer part (`01` -> `1`,
+/// `013.5` -> `13.5`) and leaves anything non-numeric untouched. Stored labels are not changed.
+pub(crate) fn display_episode_label(label: &str) -> String {
+    let trimmed = label.trim();
+    let (int_part, frac_part) = match trimmed.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (trimmed, None),
+    };
+    let is_digits = |part: &str| !part.is_empty() && part.chars().all(|ch| ch.is_ascii_digit());
+    if !is_digits(int_part) || frac_part.is_some_and(|frac| !is_digits(frac)) {
+        return trimmed.to_string();
+    }
+
+    let unpadded = int_part.trim_start_matches('0');
+    let unpadded = if unpadded.is_empty() { "0" } else { unpadded };
+    match frac_part {
+        Some(frac) => format!("{unpadded}.{frac}"),
+        None => unpadded.to_string(),
+    }
 }
 
 pub(crate) fn episode_labels_match(a: &str, b: &str) -> bool {
@@ -56,6 +260,43 @@ pub(crate) fn parse_mode_episode_labels(raw: &str, mode: &str) -> Option<Vec<Str
     parse_mode_episode_labels_from_value(&value, mode)
 }
 
+/// Whether a show is still releasing episodes, from the metadata's `status` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AiringStatus {
+    Airing,
+    Finished,
+}
+
+impl AiringStatus {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Airing => "airing",
+            Self::Finished => "finished",
+        }
+    }
+
+    /// Reads both the stored values and the metadata's wording (`Releasing`, `Finished`, ...);
+    /// anything else, such as `Not Yet Released`, is unknown.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "airing" | "releasing" | "ongoing" | "currently airing" => Some(Self::Airing),
+            "finished" | "completed" | "finished airing" => Some(Self::Finished),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Airing => "Airing",
+            Self::Finished => "Finished",
+        }
+    }
+}
+
+pub(crate) fn parse_airing_status_from_value(value: &Value) -> Option<AiringStatus> {
+    AiringStatus::parse(value.pointer("/data/show/status")?.as_str()?)
+}
+
 fn parse_mode_episode_labels_from_value(value: &Value, mode: &str) -> Option<Vec<String>> {
     let items = value
         .pointer("/data/show/availableEpisodesDetail")?
@@ -105,6 +346,7 @@ pub(crate) fn choose_episode_labels_candidate(
 #[derive(Debug, Clone, Default)]
 pub(crate) struct EpisodeLabelFetchOutcome {
     pub(crate) episode_list: Option<Vec<String>>,
+    pub(crate) airing_status: Option<AiringStatus>,
     pub(crate) warnings: Vec<String>,
 }
 
@@ -112,7 +354,8 @@ pub(crate) fn fetch_episode_labels_with_diagnostics(
     ani_id: &str,
     total_hint: Option<u32>,
 ) -> EpisodeLabelFetchOutcome {
-    let query = "query ($showId: String!) { show( _id: $showId ) { _id availableEpisodesDetail }}";
+    let query =
+        "query ($showId: String!) { show( _id: $showId ) { _id availableEpisodesDetail status }}";
     let variables = format!("{{\"showId\":\"{ani_id}\"}}");
     let query_params = vec![
         ("variables".to_string(), variables),
@@ -132,23 +375,42 @@ pub(crate) fn fetch_episode_labels_with_diagnostics(
             let warning = format!("episode metadata request failed for {ani_id}: {err}");
             return EpisodeLabelFetchOutcome {
                 episode_list: None,
+                airing_status: None,
                 warnings: vec![warning],
             };
         }
     };
 
-    let parsed: Value = match serde_json::from_str(&raw) {
+    episode_labels_from_response(ani_id, &raw, total_hint)
+}
+
+/// Parses an episode metadata response. A payload cut off mid-document is reported as truncated
+/// rather than salvaged, since a partial episode list would shift ordinals and progress.
+pub(crate) fn episode_labels_from_response(
+    ani_id: &str,
+    raw: &str,
+    total_hint: Option<u32>,
+) -> EpisodeLabelFetchOutcome {
+    let parsed: Value = match serde_json::from_str(raw) {
         Ok(parsed) => parsed,
         Err(err) => {
+            let warning = if err.is_eof() {
+                format!(
+                    "episode metadata response for {ani_id} was truncated ({} bytes): {err}",
+                    raw.len()
+                )
+            } else {
+                format!("episode metadata response parse failed for {ani_id}: {err}")
+            };
             return EpisodeLabelFetchOutcome {
                 episode_list: None,
-                warnings: vec![format!(
-                    "episode metadata response parse failed for {ani_id}: {err}"
-                )],
+                airing_status: None,
+                warnings: vec![warning],
             };
         }
     };
 
+    let airing_status = parse_airing_status_from_value(&parsed);
     let mut candidates = Vec::new();
     if let Some(sub) = parse_mode_episode_labels_from_value(&parsed, "sub") {
         candidates.push(sub);
@@ -159,6 +421,7 @@ pub(crate) fn fetch_episode_labels_with_diagnostics(
     let Some(mut episodes) = choose_episode_labels_candidate(candidates, total_hint) else {
         return EpisodeLabelFetchOutcome {
             episode_list: None,
+            airing_status,
             warnings: vec![format!(
                 "episode metadata response for {ani_id} did not contain usable sub/dub episode labels"
             )],
@@ -167,6 +430,7 @@ pub(crate) fn fetch_episode_labels_with_diagnostics(
     episodes.sort_by(|left, right| compare_episode_labels(left, right));
     EpisodeLabelFetchOutcome {
         episode_list: Some(episodes),
+        airing_status,
         warnings: Vec::new(),
     }
 }
@@ -221,6 +485,52 @@ pub(crate) fn previous_target_episode(
     integer_episode_label(current.floor())
 }
 
+/// Which Previous steps ask for confirmation before playing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub(crate) enum PreviousConfirm {
+    /// Never ask.
+    Never,
+    /// Ask when stepping from a main episode back to episode 0.
+    #[default]
+    Zero,
+    /// Also ask when stepping from a main episode back to a special (a decimal or non-numeric
+    /// label).
+    Special,
+}
+
+impl PreviousConfirm {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "never" => Some(Self::Never),
+            "zero" => Some(Self::Zero),
+            "special" => Some(Self::Special),
+            _ => None,
+        }
+    }
+}
+
+/// Describes the Previous target when stepping from `last_episode` to `target` crosses from a main
+/// episode into episode 0 or a special and `level` asks to confirm that crossing. Steps between
+/// main episodes, and steps starting from a special, never need confirmation.
+pub(crate) fn previous_crossing_note(
+    last_episode: &str,
+    target: &str,
+    level: PreviousConfirm,
+) -> Option<String> {
+    let from_main = parse_episode_f64(last_episode)
+        .is_some_and(|value| value >= 1.0 && is_effective_integer(value));
+    if !from_main {
+        return None;
+    }
+    match parse_episode_f64(target) {
+        Some(0.0) => (level >= PreviousConfirm::Zero)
+            .then(|| format!("episode {} (prologue)", display_episode_label(target))),
+        Some(value) if is_effective_integer(value) => None,
+        _ => (level >= PreviousConfirm::Special)
+            .then(|| format!("episode {} (special)", display_episode_label(target))),
+    }
+}
+
 pub(crate) fn previous_seed_episode(
     last_episode: &str,
     episode_list: Option<&[String]>,
@@ -245,6 +555,24 @@ pub(crate) fn previous_seed_episode(
     }
 }
 
+/// Whether an entry is a movie: explicitly marked as one, or a single-episode title whose stored
+/// episode is 0 or 1.
+pub(crate) fn is_movie(item: &SeenEntry) -> bool {
+    item.kind == EntryKind::Movie
+        || (entry_title_and_total(item).1 == Some(1)
+            && matches!(item.last_episode.trim(), "0" | "1"))
+}
+
+/// `has_next_episode` for a tracked entry; movies never have a next episode.
+pub(crate) fn entry_has_next_episode(item: &SeenEntry, episode_list: Option<&[String]>) -> bool {
+    !is_movie(item)
+        && has_next_episode(
+            &item.last_episode,
+            entry_title_and_total(item).1,
+            episode_list,
+        )
+}
+
 pub(crate) fn has_next_episode(
     last_episode: &str,
     total_episodes: Option<u32>,
@@ -258,13 +586,98 @@ pub(crate) fn has_next_episode(
         return idx + 1 < episodes.len();
     }
 
-    if let (Some(total), Some(current)) = (total_episodes, parse_episode_u32(last_episode)) {
-        return current < total;
+    if episode_list.is_some() && first_episode_after(last_episode, episode_list).is_some() {
+        return true;
+    }
+
+    if let Some(total) = total_episodes {
+        // Without a list to check against, only a regular numbered episode can be compared with
+        // the total; decimal specials don't prove that a next episode exists. Episode 0 (e.g.
+        // after a restart) is always followed by episode 1.
+        return parse_episode_u32(last_episode).is_some_and(|current| current < total);
     }
 
     true
 }
 
+/// The label Next would play for a tracked entry, when it has a next episode: the following
+/// listed episode, or the next whole number without a list. `None` when there is no next
+/// episode or it cannot be named (e.g. after a special, without a list).
+pub(crate) fn next_episode_label(
+    item: &SeenEntry,
+    episode_list: Option<&[String]>,
+) -> Option<String> {
+    if !entry_has_next_episode(item, episode_list) {
+        return None;
+    }
+    // A list that ends before the stored episode cannot name what follows it.
+    if let Some(episodes) = episode_list
+        && episode_list_ends_before(&item.last_episode, episodes).is_none()
+    {
+        return match episodes
+            .iter()
+            .position(|episode| episode_labels_match(episode, &item.last_episode))
+        {
+            Some(idx) => episodes.get(idx + 1).cloned(),
+            None => first_episode_after(&item.last_episode, episode_list),
+        };
+    }
+    first_episode_after(&item.last_episode, None).or_else(|| {
+        let current = item.last_episode.trim().parse::<u32>().ok()?;
+        Some((current + 1).to_string())
+    })
+}
+
+/// Progress that makes the next Next start a show over: the episode before the first listed one
+/// (`0` for lists starting at 1, `-1` for lists starting at 0), or `0` without a list.
+pub(crate) fn restart_episode(episode_list: Option<&[String]>) -> String {
+    match episode_list
+        .and_then(|episodes| episodes.first())
+        .and_then(|first| first.trim().parse::<f64>().ok())
+    {
+        Some(first) if first.is_finite() && first < 1.0 => format!("{}", first.floor() as i64 - 1),
+        _ => "0".to_string(),
+    }
+}
+
+/// The episode Next has to play directly because progress sits before the first episode (after
+/// a restart): `ani-cli -c` can only continue from an episode in its list. Without a list, a
+/// whole-number episode below 1 is followed by the next whole number.
+pub(crate) fn first_episode_after(
+    last_episode: &str,
+    episode_list: Option<&[String]>,
+) -> Option<String> {
+    let last = last_episode.trim().parse::<f64>().ok()?;
+    match episode_list {
+        Some(episodes) => {
+            if episodes
+                .iter()
+                .any(|episode| episode_labels_match(episode, last_episode))
+            {
+                return None;
+            }
+            let first = episodes.first()?;
+            (first.trim().parse::<f64>().ok()? > last).then(|| first.clone())
+        }
+        None => (last < 1.0 && last.fract() == 0.0).then(|| format!("{}", last as i64 + 1)),
+    }
+}
+
+/// The highest numbered episode of a fetched list, when the stored episode is past it: metadata
+/// can come back truncated (e.g. listing only the subbed episodes of a show watched dubbed), and
+/// such a list cannot place the episode, so progress falls back to the episode number.
+pub(crate) fn episode_list_ends_before<'a>(
+    last_episode: &str,
+    episodes: &'a [String],
+) -> Option<&'a str> {
+    let last = parse_episode_f64(last_episode)?;
+    let (end, end_value) = episodes
+        .iter()
+        .filter_map(|episode| Some((episode, parse_episode_f64(episode)?)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    (end_value < last).then_some(end.as_str())
+}
+
 pub(crate) fn has_previous_episode(last_episode: &str, episode_list: Option<&[String]>) -> bool {
     previous_target_episode(last_episode, episode_list).is_some()
 }
@@ -291,17 +704,83 @@ pub(crate) fn episode_ordinal_from_list(last_episode: &str, episodes: &[String])
         .map(|idx| (idx + 1) as u32)
 }
 
+/// Lists at least this long get a label -> ordinal index, so progress lookups on long-running
+/// shows stay O(1) on every render.
+const EPISODE_INDEX_MIN_LEN: usize = 200;
+
+/// How a label is keyed in `EpisodeList`'s index: finite numbers by value (so `01` and `1` share a
+/// key, as in `episode_labels_match`), everything else by its trimmed text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum EpisodeKey {
+    Number(u64),
+    Text(String),
+}
+
+impl EpisodeKey {
+    fn of(label: &str) -> Self {
+        let label = label.trim();
+        match parse_episode_f64(label).filter(|value| value.is_finite()) {
+            // `+ 0.0` folds -0 into 0 so both share a key.
+            Some(value) => Self::Number((value + 0.0).to_bits()),
+            None => Self::Text(label.to_string()),
+        }
+    }
+}
+
+/// A show's episode labels, in order, with an index for ordinal lookups on long lists.
+#[derive(Debug, Clone)]
+pub(crate) struct EpisodeList {
+    labels: Vec<String>,
+    ordinals: Option<HashMap<EpisodeKey, u32>>,
+}
+
+impl EpisodeList {
+    pub(crate) fn new(labels: Vec<String>) -> Self {
+        let ordinals = (labels.len() >= EPISODE_INDEX_MIN_LEN).then(|| {
+            let mut ordinals = HashMap::with_capacity(labels.len());
+            for (idx, label) in labels.iter().enumerate() {
+                // The first occurrence wins, like the linear scan.
+                ordinals
+                    .entry(EpisodeKey::of(label))
+                    .or_insert((idx + 1) as u32);
+            }
+            ordinals
+        });
+        Self { labels, ordinals }
+    }
+
+    pub(crate) fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Same result as `episode_ordinal_from_list`. Numeric labels missing from the index still
+    /// fall back to the scan, which also matches values within its rounding tolerance.
+    pub(crate) fn ordinal_of(&self, last_episode: &str) -> Option<u32> {
+        let Some(ordinals) = &self.ordinals else {
+            return episode_ordinal_from_list(last_episode, &self.labels);
+        };
+        let key = EpisodeKey::of(last_episode);
+        match ordinals.get(&key) {
+            Some(ordinal) => Some(*ordinal),
+            None if matches!(key, EpisodeKey::Number(_)) => {
+                episode_ordinal_from_list(last_episode, &self.labels)
+            }
+            None => None,
+        }
+    }
+}
+
 pub(crate) fn episode_progress_position(
     last_episode: &str,
     total_episodes: u32,
-    episode_list: Option<&[String]>,
+    episode_list: Option<&EpisodeList>,
 ) -> Option<u32> {
     if total_episodes == 0 {
         return None;
     }
 
     if let Some(episodes) = episode_list
-        && let Some(ordinal) = episode_ordinal_from_list(last_episode, episodes)
+        && let Some(ordinal) = episodes.ordinal_of(last_episode)
     {
         return Some(ordinal.min(total_episodes));
     }
@@ -309,54 +788,288 @@ pub(crate) fn episode_progress_position(
     parse_episode_u32(last_episode).map(|current| current.min(total_episodes))
 }
 
+/// Renders `current` out of `total` in the chosen format. `fraction` is the share watched, used
+/// by the percentage presets; without it they fall back to the plain count.
+pub(crate) fn format_progress(
+    current: &str,
+    fraction: Option<f64>,
+    total: u32,
+    format: EpisodeFormat,
+) -> String {
+    let pct = fraction.map(|value| format!("{:.0}%", (value * 100.0).clamp(0.0, 100.0)));
+    match (format, pct) {
+        (EpisodeFormat::XOfY, _) | (EpisodeFormat::Percent, None) => {
+            format!("{current} of {total}")
+        }
+        (EpisodeFormat::Slash, _) | (EpisodeFormat::SlashPercent, None) => {
+            format!("{current}/{total}")
+        }
+        (EpisodeFormat::Percent, Some(pct)) => pct,
+        (EpisodeFormat::SlashPercent, Some(pct)) => format!("{current}/{total} ({pct})"),
+    }
+}
+
 pub(crate) fn format_episode_progress_text(
     last_episode: &str,
     total_episodes: u32,
-    episode_list: Option<&[String]>,
+    episode_list: Option<&EpisodeList>,
+    format: EpisodeFormat,
 ) -> String {
     match episode_progress_position(last_episode, total_episodes, episode_list) {
         Some(position) => {
+            let fraction = f64::from(position) / f64::from(total_episodes);
+            let text = format_progress(
+                &position.to_string(),
+                Some(fraction),
+                total_episodes,
+                format,
+            );
             if parse_episode_u32(last_episode) == Some(position) {
-                format!("{position} of {total_episodes}")
+                text
             } else {
-                format!("{position} of {total_episodes} (episode {last_episode})")
+                format!("{text} (episode {})", display_episode_label(last_episode))
             }
         }
-        None => format!("{last_episode} of {total_episodes}"),
+        None => {
+            // A non-numeric label missing from the episode list (e.g. "Special", "Movie") has no
+            // meaningful position, so show it verbatim instead of as a count.
+            let Some(episode) = parse_episode_f64(last_episode) else {
+                return format!("{} (special)", last_episode.trim());
+            };
+            let fraction = (total_episodes > 0).then(|| episode / f64::from(total_episodes));
+            format_progress(
+                &display_episode_label(last_episode),
+                fraction,
+                total_episodes,
+                format,
+            )
+        }
     }
 }
 
 pub(crate) fn build_progress_gauge(
     last_episode: &str,
     total_episodes: u32,
-    episode_list: Option<&[String]>,
+    episode_list: Option<&EpisodeList>,
 ) -> Option<(f64, String)> {
     let shown = episode_progress_position(last_episode, total_episodes, episode_list)?;
     let ratio = (shown as f64 / total_episodes as f64).clamp(0.0, 1.0);
     Some((ratio, format!("{shown}/{total_episodes}")))
 }
 
+/// Gauge for an entry tracking watched episodes: how many of `total_episodes` were played,
+/// wherever they fall, rather than how far along the furthest one is.
+pub(crate) fn build_coverage_gauge(
+    watched_episodes: &[String],
+    total_episodes: u32,
+) -> Option<(f64, String)> {
+    if total_episodes == 0 || watched_episodes.is_empty() {
+        return None;
+    }
+    let watched = u32::try_from(watched_episodes.len())
+        .unwrap_or(u32::MAX)
+        .min(total_episodes);
+    let ratio = (f64::from(watched) / f64::from(total_episodes)).clamp(0.0, 1.0);
+    Some((ratio, format!("{watched}/{total_episodes} watched")))
+}
+
+/// What a progress position was derived from, so the TUI can say how far to trust it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProgressSource {
+    /// The stored episode was found in the fetched episode list.
+    EpisodeList,
+    /// No list (or the episode is not in it), so the label was read as a number.
+    NumericEstimate,
+    /// Counted from the entry's watched episodes.
+    WatchedEpisodes,
+}
+
+impl ProgressSource {
+    /// Mirrors the lookup order of `episode_progress_position`.
+    pub(crate) fn of(last_episode: &str, episode_list: Option<&EpisodeList>) -> Self {
+        match episode_list.and_then(|episodes| episodes.ordinal_of(last_episode)) {
+            Some(_) => Self::EpisodeList,
+            None => Self::NumericEstimate,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::EpisodeList => "from episode list",
+            Self::NumericEstimate => "numeric estimate",
+            Self::WatchedEpisodes => "watched episodes",
+        }
+    }
+}
+
+/// Where `truncate` cuts over-long text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum EllipsisStyle {
+    /// Keep the start and append `...` (limit counted in characters).
+    #[default]
+    End,
+    /// Keep the start and the end around a `…`, so suffixes like `Part 2` stay visible (limit
+    /// counted in display columns, cutting only between graphemes).
+    Middle,
+}
+
+impl EllipsisStyle {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "end" => Some(Self::End),
+            "middle" => Some(Self::Middle),
+            _ => None,
+        }
+    }
+}
+
+static MIDDLE_ELLIPSIS: AtomicBool = AtomicBool::new(false);
+
+/// Sets the style used by `truncate` for the rest of the process.
+pub(crate) fn set_ellipsis_style(style: EllipsisStyle) {
+    MIDDLE_ELLIPSIS.store(style == EllipsisStyle::Middle, atomic::Ordering::Relaxed);
+}
+
 pub(crate) fn truncate(s: &str, max: usize) -> String {
-    let mut out = s.to_string();
-    if out.chars().count() > max {
-        out = out.chars().take(max.saturating_sub(3)).collect::<String>() + "...";
+    let style = if MIDDLE_ELLIPSIS.load(atomic::Ordering::Relaxed) {
+        EllipsisStyle::Middle
+    } else {
+        EllipsisStyle::End
+    };
+    truncate_with_style(s, max, style)
+}
+
+pub(crate) fn truncate_with_style(s: &str, max: usize, style: EllipsisStyle) -> String {
+    match style {
+        EllipsisStyle::End => {
+            let mut out = s.to_string();
+            if out.chars().count() > max {
+                out = out.chars().take(max.saturating_sub(3)).collect::<String>() + "...";
+            }
+            out
+        }
+        EllipsisStyle::Middle => truncate_middle(s, max),
     }
-    out
+}
+
+fn truncate_middle(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    let budget = max.saturating_sub(1);
+    let mut head_budget = budget.div_ceil(2);
+    let mut tail_budget = budget - head_budget;
+    let graphemes = s.graphemes(true).collect::<Vec<_>>();
+
+    let mut head_len = 0;
+    for grapheme in &graphemes {
+        let width = grapheme.width();
+        if width > head_budget {
+            break;
+        }
+        head_budget -= width;
+        head_len += 1;
+    }
+    // Columns the head could not use (e.g. half of a wide character) go to the tail.
+    tail_budget += head_budget;
+    let mut tail_start = graphemes.len();
+    while tail_start > head_len {
+        let width = graphemes[tail_start - 1].width();
+        if width > tail_budget {
+            break;
+        }
+        tail_budget -= width;
+        tail_start -= 1;
+    }
+
+    let head = graphemes[..head_len].concat();
+    let tail = graphemes[tail_start..].concat();
+    format!("{}…{}", head.trim_end(), tail.trim_start())
+}
+
+/// Word-wraps `s` into at most `max_lines` lines of `width` display columns; words longer than a
+/// line are split between graphemes and the last line ends in `...` when text is left over.
+pub(crate) fn wrap_title(s: &str, width: usize, max_lines: usize) -> Vec<String> {
+    if width == 0 || max_lines == 0 {
+        return Vec::new();
+    }
+    let mut lines = Vec::new();
+    let mut rest = s.trim();
+    while lines.len() + 1 < max_lines && rest.width() > width {
+        let mut used = 0;
+        let mut fit_end = 0;
+        let mut last_break = None;
+        for (idx, grapheme) in rest.grapheme_indices(true) {
+            used += grapheme.width();
+            if used > width {
+                break;
+            }
+            if grapheme.trim().is_empty() {
+                last_break = Some(idx);
+            }
+            fit_end = idx + grapheme.len();
+        }
+        // A space right after the last fitting grapheme is also a clean break.
+        if rest[fit_end..].starts_with(char::is_whitespace) {
+            last_break = Some(fit_end);
+        }
+        let split = match last_break.filter(|idx| *idx > 0) {
+            Some(idx) => idx,
+            None if fit_end > 0 => fit_end,
+            // Even one grapheme is wider than the line; give it a line of its own.
+            None => rest.graphemes(true).next().map_or(rest.len(), str::len),
+        };
+        lines.push(rest[..split].trim_end().to_string());
+        rest = rest[split..].trim_start();
+    }
+    if rest.width() > width {
+        let budget = width.saturating_sub(3);
+        let mut used = 0;
+        let head = rest
+            .graphemes(true)
+            .take_while(|grapheme| {
+                used += grapheme.width();
+                used <= budget
+            })
+            .collect::<String>();
+        lines.push(format!("{}...", head.trim_end()));
+    } else if !rest.is_empty() || lines.is_empty() {
+        lines.push(rest.to_string());
+    }
+    lines
+}
+
+/// Length of the `ani_id` prefix shown while the TUI is in short-id mode.
+const SHORT_ANI_ID_LEN: usize = 8;
+
+/// Display form of an `ani_id`: the full value, or its first few characters when collapsed.
+/// Only used for rendering; anything that acts on the id must keep using the full value.
+pub(crate) fn display_ani_id(ani_id: &str, full: bool) -> String {
+    if full || ani_id.chars().count() <= SHORT_ANI_ID_LEN {
+        return ani_id.to_string();
+    }
+    ani_id.chars().take(SHORT_ANI_ID_LEN).collect::<String>() + "…"
 }
 
 pub(crate) fn sanitize_title_for_search(title: &str) -> String {
     let trimmed = title.trim();
     if let Some(open_idx) = trimmed.rfind('(')
         && trimmed.ends_with(')')
-        && trimmed[open_idx..].contains("episodes")
+        && trimmed[open_idx + 1..trimmed.len() - 1]
+            .split(|ch: char| !ch.is_alphabetic())
+            .any(is_episode_suffix)
     {
         return trimmed[..open_idx].trim().to_string();
     }
     trimmed.to_string()
 }
 
+/// Whole episode number of a label; a range label such as `1-2` counts as its first episode.
 pub(crate) fn parse_episode_u32(ep: &str) -> Option<u32> {
-    ep.trim().parse::<u32>().ok()
+    let ep = ep.trim();
+    ep.parse::<u32>()
+        .ok()
+        .or_else(|| episode_range_start(ep)?.parse::<u32>().ok())
 }
 
 pub(crate) fn format_last_seen_display(raw: &str) -> String {
@@ -0,0 +1,100 @@
+use std::process::{Command as ProcessCommand, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+
+use anyhow::{Context, Result};
+
+use crate::db::SeenEntry;
+
+/// Placeholders a progress hook argument may contain.
+const PROGRESS_PLACEHOLDERS: [&str; 5] = [
+    "{ani_id}",
+    "{title}",
+    "{episode}",
+    "{external_provider}",
+    "{external_id}",
+];
+
+static PROGRESS_HOOK: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Sets the command run after playback records progress, for the rest of the process. The
+/// template is split on whitespace into a program and its arguments; blank disables the hook.
+pub(crate) fn set_progress_hook(template: Option<&str>) {
+    let argv = template
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if !argv.is_empty() {
+        let _ = PROGRESS_HOOK.set(argv);
+    }
+}
+
+/// Fills the placeholders in each template argument for `item` now on `episode`; an unlinked
+/// entry fills the external placeholders with nothing. Values are substituted once, never
+/// re-scanned, and each argument stays a single argv entry, so a title cannot inject arguments
+/// or shell syntax.
+pub(crate) fn progress_hook_argv(
+    template: &[String],
+    item: &SeenEntry,
+    episode: &str,
+) -> Vec<String> {
+    let values = [
+        item.ani_id.as_str(),
+        item.title.as_str(),
+        episode,
+        item.external_provider.as_deref().unwrap_or_default(),
+        item.external_id.as_deref().unwrap_or_default(),
+    ];
+    template
+        .iter()
+        .map(|arg| {
+            let mut filled = String::with_capacity(arg.len());
+            let mut rest = arg.as_str();
+            while let Some(start) = rest.find('{') {
+                filled.push_str(&rest[..start]);
+                rest = &rest[start..];
+                match PROGRESS_PLACEHOLDERS
+                    .iter()
+                    .position(|placeholder| rest.starts_with(placeholder))
+                {
+                    Some(idx) => {
+                        filled.push_str(values[idx]);
+                        rest = &rest[PROGRESS_PLACEHOLDERS[idx].len()..];
+                    }
+                    None => {
+                        filled.push('{');
+                        rest = &rest[1..];
+                    }
+                }
+            }
+            filled.push_str(rest);
+            filled
+        })
+        .collect()
+}
+
+/// Starts the progress hook, if one is set, without waiting for it. Its output is discarded; an
+/// error only means it could not be started, and callers report it as a warning.
+pub(crate) fn run_progress_hook(item: &SeenEntry, episode: &str) -> Result<()> {
+    let Some(template) = PROGRESS_HOOK.get() else {
+        return Ok(());
+    };
+    spawn_hook(&progress_hook_argv(template, item, episode))
+}
+
+pub(crate) fn spawn_hook(argv: &[String]) -> Result<()> {
+    let Some((program, args)) = argv.split_first() else {
+        return Ok(());
+    };
+    let mut child = ProcessCommand::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("progress hook `{program}` failed to start"))?;
+    // Reap the hook in the background so it never holds up playback or lingers as a zombie.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use anyhow::{Result, anyhow, bail};
+
+use crate::cli::EpisodeFormat;
+use crate::db::SeenEntry;
+
+use super::episode::{
+    display_episode_label, entry_title_and_total, episode_progress_position,
+    format_last_seen_display, format_progress,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    AniId,
+    Title,
+    Episode,
+    Total,
+    LastSeen,
+    Progress,
+}
+
+impl Field {
+    const ALL: [Self; 6] = [
+        Self::AniId,
+        Self::Title,
+        Self::Episode,
+        Self::Total,
+        Self::LastSeen,
+        Self::Progress,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::AniId => "ani_id",
+            Self::Title => "title",
+            Self::Episode => "episode",
+            Self::Total => "total",
+            Self::LastSeen => "last_seen",
+            Self::Progress => "progress",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.as_str() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Field(Field),
+}
+
+/// A `list --format` template: literal text with `{placeholder}` fields resolved per entry.
+/// `{{` and `}}` produce literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ListTemplate {
+    parts: Vec<Part>,
+    progress_format: EpisodeFormat,
+}
+
+impl ListTemplate {
+    pub(crate) fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => bail!("unclosed `{{` in list format template"),
+                        }
+                    }
+                    let field = Field::parse(name.trim()).ok_or_else(|| {
+                        anyhow!(
+                            "unknown list format placeholder `{{{name}}}`; valid placeholders: {}",
+                            valid_placeholders()
+                        )
+                    })?;
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Field(field));
+                }
+                '}' => {
+                    bail!("unmatched `}}` in list format template (use `}}}}` for a literal brace)")
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self {
+            parts,
+            progress_format: EpisodeFormat::Slash,
+        })
+    }
+
+    /// Format for `{progress}`; defaults to `x/y`.
+    pub(crate) fn with_progress_format(mut self, format: EpisodeFormat) -> Self {
+        self.progress_format = format;
+        self
+    }
+
+    pub(crate) fn render(&self, item: &SeenEntry) -> String {
+        let (title, total_eps) = entry_title_and_total(item);
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Field(Field::AniId) => out.push_str(&item.ani_id),
+                Part::Field(Field::Title) => out.push_str(&title),
+                Part::Field(Field::Episode) => {
+                    out.push_str(&display_episode_label(&item.last_episode))
+                }
+                Part::Field(Field::Total) => match total_eps {
+                    Some(total) => out.push_str(&total.to_string()),
+                    None => out.push('-'),
+                },
+                Part::Field(Field::LastSeen) => {
+                    out.push_str(&format_last_seen_display(&item.last_seen_at))
+                }
+                Part::Field(Field::Progress) => {
+                    let progress = total_eps.and_then(|total| {
+                        let position = episode_progress_position(&item.last_episode, total, None)?;
+                        Some(format_progress(
+                            &position.to_string(),
+                            Some(f64::from(position) / f64::from(total)),
+                            total,
+                            self.progress_format,
+                        ))
+                    });
+                    match progress {
+                        Some(progress) => out.push_str(&progress),
+                        None => out.push('-'),
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn valid_placeholders() -> String {
+    Field::ALL
+        .iter()
+        .map(|field| format!("{{{}}}", field.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,79 +1,359 @@
+mod backup;
+mod color;
 mod episode;
+mod hooks;
+mod list_format;
 mod tracking;
 mod tui;
+mod verify;
+mod watch_later;
 
 #[cfg(test)]
 mod tests;
 
-use anyhow::Result;
+use std::cmp::Ordering;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
 
-use crate::cli::{Cli, Command};
-use crate::db::Database;
-use crate::paths::database_file_path;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Utc};
+use serde_json::json;
 
-use self::episode::{format_last_seen_display, truncate};
+use crate::cli::{Cli, Command, ExportFormat, parse_age};
+use crate::db::{
+    Database, DbOpenIssue, EntryKind, JournalMode, ListOrder, SCHEMA_VERSION, SeenEntry,
+    SortTieBreak, classify_open_error, normalize_tags, normalize_watched_episodes,
+};
+use crate::paths::{database_file_path, tui_state_file_path};
+
+use self::backup::{export_backup, export_csv, export_history, export_markdown, import_backup};
+use self::color::{Paint, color_enabled, paint, paint_message, set_color_enabled, warning_label};
+use self::episode::{
+    EllipsisStyle, PreviousConfirm, compare_episode_labels, display_episode_label,
+    entry_has_next_episode, entry_title_and_total, fetch_episode_labels_with_diagnostics,
+    first_episode_after, format_last_seen_display, is_movie, last_episode_after_play,
+    parse_title_and_total_eps, record_watched_episode, restart_episode, set_ellipsis_style,
+    set_extra_episode_suffixes, set_store_clean_titles, set_track_watched_episodes,
+    stored_title_and_total, truncate,
+};
+use self::hooks::{run_progress_hook, set_progress_hook};
+use self::list_format::ListTemplate;
 use self::tracking::{
-    playback_failure_message, run_ani_cli_continue, run_ani_cli_replay, run_ani_cli_search,
+    HistEntry, HistRead, LaunchOptions, ReplayMode, STALE_TEMP_HIST_AGE, ani_cli_histfile,
+    detect_title_first_from_env, interrupt_requested, playback_failure_message, read_hist_map,
+    resolve_ani_cli_bin, resolve_player, run_ani_cli_continue, run_ani_cli_replay,
+    run_ani_cli_search, set_require_episode_change, sweep_stale_temp_hist_dirs,
+    sync_progress_from_history, untracked_history_entries, watch_for_interrupt,
 };
+use self::tui::{LibraryColumn, TuiOptions};
+use self::verify::run_verify;
+
+/// Default line printed by `anitrack now`.
+pub(crate) const NOW_TEMPLATE: &str = "{title} · ep {episode}";
 
 pub fn run(cli: Cli) -> Result<()> {
-    let db = open_db()?;
+    set_color_enabled(color_enabled(
+        cli.color,
+        io::stdout().is_terminal(),
+        env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+    ));
+    set_ellipsis_style(ellipsis_style_from_env(
+        env::var("ANI_TRACK_ELLIPSIS").ok(),
+    )?);
+    set_store_clean_titles(env_flag("ANI_TRACK_STORE_CLEAN_TITLES"));
+    set_track_watched_episodes(env_flag("ANI_TRACK_WATCHED_EPISODES"));
+    set_extra_episode_suffixes(env::var("ANI_TRACK_EPISODE_SUFFIXES").ok().as_deref());
+    set_require_episode_change(env_flag("ANI_TRACK_REQUIRE_EPISODE_CHANGE"));
+    set_progress_hook(env::var("ANI_TRACK_ON_PROGRESS").ok().as_deref());
+    let interactive_command = match cli.command {
+        Some(Command::Start { .. }) => Some("start"),
+        Some(Command::Next) => Some("next"),
+        Some(Command::Replay) => Some("replay"),
+        Some(Command::ContinueFrom { .. }) => Some("continue-from"),
+        Some(Command::Tui { .. }) | None => Some("tui"),
+        _ => None,
+    };
+    if let Some(name) = interactive_command {
+        require_interactive_stdin(name, io::stdin().is_terminal())?;
+        // Only playback leaves temp history directories behind, so only it pays for the sweep.
+        sweep_stale_temp_hist_dirs(&env::temp_dir(), STALE_TEMP_HIST_AGE);
+    }
+    let db = open_db(
+        matches!(
+            cli.command,
+            Some(
+                Command::List { .. }
+                    | Command::Now { .. }
+                    | Command::Untracked { .. }
+                    | Command::Doctor { .. }
+                    | Command::Verify { .. }
+                    | Command::DumpHistory { .. }
+            )
+        ),
+        matches!(
+            cli.command,
+            Some(Command::Migrate {
+                allow_foreign_db: true
+            })
+        ),
+        if matches!(cli.command, Some(Command::Migrate { .. })) {
+            SchemaPolicy::Leave
+        } else if cli.no_migrate {
+            SchemaPolicy::RequireCurrent
+        } else {
+            SchemaPolicy::Migrate
+        },
+    )?;
+    let launch = LaunchOptions {
+        player: cli.player,
+        max_runtime: cli.max_runtime.map(Duration::from_secs),
+        extra_args: ani_cli_args_from_env(env::var("ANI_TRACK_ANI_CLI_ARGS").ok())
+            .into_iter()
+            .chain(cli.ani_cli_args)
+            .collect(),
+        replay_mode: replay_mode_from_env(env::var("ANI_TRACK_REPLAY_MODE").ok())?,
+    };
 
     match cli.command {
-        Some(Command::Start) => run_start(&db)?,
-        Some(Command::Next) => run_next(&db)?,
-        Some(Command::Replay) => run_replay(&db)?,
-        Some(Command::List) => run_list(&db)?,
-        Some(Command::Tui) | None => tui::run_tui(&db)?,
+        Some(Command::Start { movie }) => run_start(&db, &launch, movie)?,
+        Some(Command::Next) => run_next(&db, &launch)?,
+        Some(Command::Replay) => run_replay(&db, &launch)?,
+        Some(Command::ContinueFrom { ani_id, episode }) => {
+            run_continue_from(&db, &launch, &ani_id, &episode)?
+        }
+        Some(Command::Restart { ani_id }) => run_restart(&db, &ani_id)?,
+        Some(Command::List {
+            watch,
+            json,
+            format,
+            tags,
+            all_tags,
+            sort,
+        }) => {
+            let order = ListOrder {
+                sort,
+                tie_break: sort_tie_break_from_env(env::var("ANI_TRACK_SORT_TIEBREAK").ok())?
+                    .unwrap_or_default(),
+            };
+            let filter = TagFilter {
+                tags: normalize_tags(&tags),
+                match_all: all_tags,
+            };
+            let output = match format {
+                Some(template) => {
+                    let mut template = ListTemplate::parse(&template)?;
+                    if let Some(format) = cli.episode_format {
+                        template = template.with_progress_format(format);
+                    }
+                    ListOutput::Template(template)
+                }
+                None if json => ListOutput::Json,
+                None => ListOutput::Table,
+            };
+            match watch {
+                Some(interval) => {
+                    run_list_watch(&db, Duration::from_secs(interval), &output, &filter, order)?
+                }
+                None => run_list(&db, &output, &filter, order)?,
+            }
+        }
+        Some(Command::Now { format, no_newline }) => {
+            let mut template = ListTemplate::parse(format.as_deref().unwrap_or(NOW_TEMPLATE))?;
+            if let Some(format) = cli.episode_format {
+                template = template.with_progress_format(format);
+            }
+            run_now(&db, &template, no_newline)?
+        }
+        Some(Command::Untracked { json }) => run_untracked(&db, json)?,
+        Some(Command::Export { output, format }) => run_export(&db, output.as_deref(), format)?,
+        Some(Command::ExportHistory { path, overwrite }) => {
+            run_export_history(&db, path, overwrite)?
+        }
+        Some(Command::Import { file }) => run_import(&db, &file)?,
+        Some(Command::Stats { by_status, json }) => run_stats(&db, by_status, json)?,
+        Some(Command::Prune {
+            older_than,
+            status,
+            dry_run,
+            yes,
+        }) => run_prune(&db, older_than, status.as_deref(), yes && !dry_run)?,
+        Some(Command::Sync) => println!("{}", paint_message(&sync_progress_from_history(&db)?)),
+        Some(Command::Add {
+            ani_id,
+            title,
+            episode,
+        }) => run_add(&db, &ani_id, &title, &episode)?,
+        Some(Command::Link {
+            ani_id,
+            anilist,
+            mal,
+            clear: _,
+        }) => run_link(&db, &ani_id, external_link(anilist, mal))?,
+        Some(Command::Merge { keep_id, drop_id }) => run_merge(&db, &keep_id, &drop_id)?,
+        Some(Command::NormalizeTitles) => run_normalize_titles(&db)?,
+        Some(Command::Doctor { check }) => run_doctor(&launch, check)?,
+        Some(Command::Verify { jobs }) => run_verify(&db, usize::from(jobs))?,
+        Some(Command::Migrate { allow_foreign_db }) => run_migrate(&db, allow_foreign_db)?,
+        Some(Command::DumpHistory { hist_dir }) => run_dump_history(hist_dir.as_deref()),
+        command @ (Some(Command::Tui { .. }) | None) => {
+            let options = TuiOptions {
+                plain: matches!(command, Some(Command::Tui { plain: true })),
+                prefetch_concurrency: usize::from(cli.concurrency),
+                episode_format: cli.episode_format.unwrap_or_default(),
+                title_case: env_flag("ANI_TRACK_TITLE_CASE"),
+                state_file: tui_state_file_from_env()?,
+                confirm_previous: previous_confirm_from_env(
+                    env::var("ANI_TRACK_CONFIRM_PREVIOUS").ok(),
+                )?,
+                wrap_selected_title: env_flag("ANI_TRACK_WRAP_TITLE"),
+                advance_selection_after_play: env_flag("ANI_TRACK_ADVANCE_AFTER_PLAY"),
+                mpv_watch_later_dir: env::var_os("ANI_TRACK_MPV_WATCH_LATER_DIR")
+                    .filter(|value| !value.is_empty())
+                    .map(PathBuf::from),
+                sort_tie_break: sort_tie_break_from_env(env::var("ANI_TRACK_SORT_TIEBREAK").ok())?,
+                show_episode_ordinal: env_flag("ANI_TRACK_SHOW_ORDINAL"),
+                columns: library_columns_from_env(env::var("ANI_TRACK_COLUMNS").ok())?,
+                wal_checkpoint_interval: wal_checkpoint_interval_from_env(
+                    env::var("ANI_TRACK_WAL_CHECKPOINT_SECS").ok(),
+                )?,
+                stale_after: stale_after_from_env(env::var("ANI_TRACK_STALE_AFTER").ok())?,
+                confirm_quit: env_flag("ANI_TRACK_CONFIRM_QUIT"),
+            };
+            tui::run_tui(&db, &launch, &options)?
+        }
     }
 
     Ok(())
 }
 
-fn run_start(db: &Database) -> Result<()> {
-    let (message, _) = run_ani_cli_search(db)?;
-    println!("\n{message}");
+fn run_now(db: &Database, template: &ListTemplate, no_newline: bool) -> Result<()> {
+    let Some(item) = db.last_seen()? else {
+        return Ok(());
+    };
+    let line = template.render(&item);
+    let mut stdout = io::stdout().lock();
+    if no_newline {
+        write!(stdout, "{line}")?;
+    } else {
+        writeln!(stdout, "{line}")?;
+    }
+    stdout.flush()?;
     Ok(())
 }
 
-fn run_next(db: &Database) -> Result<()> {
+fn run_start(db: &Database, launch: &LaunchOptions, movie: bool) -> Result<()> {
+    let (message, changed_id) = run_ani_cli_search(db, launch)?;
+    println!("\n{}", paint_message(&message));
+    if movie {
+        match changed_id {
+            Some(ani_id) if db.set_kind(&ani_id, EntryKind::Movie)? => {
+                println!("Marked as a movie.");
+            }
+            _ => println!("Nothing was tracked, so nothing was marked as a movie."),
+        }
+    }
+    Ok(())
+}
+
+fn run_next(db: &Database, launch: &LaunchOptions) -> Result<()> {
     match db.last_seen()? {
+        Some(item) if is_movie(&item) => {
+            println!(
+                "{} is a movie, so there is no next episode. Run `anitrack replay` to watch it again.",
+                item.title
+            );
+        }
         Some(item) => {
             println!("Playing next episode for last seen show:");
             println!("  Title: {}", item.title);
             println!("  Current stored episode: {}", item.last_episode);
-
-            let outcome = match run_ani_cli_continue(&item, &item.last_episode) {
-                Ok(outcome) => outcome,
-                Err(err) => {
-                    println!("ani-cli launch failed: {err}");
-                    println!("Progress not updated.");
-                    return Ok(());
-                }
-            };
-            if outcome.success {
-                let updated_ep = outcome
-                    .final_episode
-                    .unwrap_or_else(|| item.last_episode.clone());
-                db.upsert_seen(&item.ani_id, &item.title, &updated_ep)?;
-                println!("Updated progress: {} -> episode {}", item.title, updated_ep);
-            } else {
-                println!("{}", playback_failure_message(&outcome));
-            }
+            continue_and_record(db, launch, &item, &item.last_episode)?;
         }
         None => println!("No last seen entry yet. Run `anitrack start` first."),
     }
     Ok(())
 }
 
-fn run_replay(db: &Database) -> Result<()> {
+fn run_continue_from(
+    db: &Database,
+    launch: &LaunchOptions,
+    ani_id: &str,
+    episode: &str,
+) -> Result<()> {
+    let item = db
+        .get_seen(ani_id)?
+        .ok_or_else(|| anyhow!("no tracked entry with id `{ani_id}`"))?;
+    println!("Continuing after a chosen episode:");
+    println!("  Title: {}", item.title);
+    println!("  Current stored episode: {}", item.last_episode);
+    println!("  Continuing after episode: {episode}");
+    continue_and_record(db, launch, &item, episode)
+}
+
+fn run_restart(db: &Database, ani_id: &str) -> Result<()> {
+    let item = db
+        .get_seen(ani_id)?
+        .ok_or_else(|| anyhow!("no tracked entry with id `{ani_id}`"))?;
+    let fetched = fetch_episode_labels_with_diagnostics(ani_id, entry_title_and_total(&item).1);
+    for warning in &fetched.warnings {
+        eprintln!("{} {warning}", warning_label());
+    }
+    let episode_list = fetched.episode_list.as_deref();
+    let restart = restart_episode(episode_list);
+    let first = first_episode_after(&restart, episode_list).unwrap_or_else(|| "1".to_string());
+    db.upsert_seen(&item.ani_id, &item.title, &restart)?;
+    println!(
+        "Restarted {}: was on episode {}, next plays episode {first}.",
+        item.title,
+        display_episode_label(&item.last_episode)
+    );
+    Ok(())
+}
+
+/// Runs ani-cli's continue with `seed_episode` as the last watched episode and stores the episode
+/// it ends on. Stored progress is kept when ani-cli fails or records nothing.
+fn continue_and_record(
+    db: &Database,
+    launch: &LaunchOptions,
+    item: &SeenEntry,
+    seed_episode: &str,
+) -> Result<()> {
+    let outcome = match run_ani_cli_continue(item, seed_episode, launch) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            println!("ani-cli launch failed: {err}");
+            println!("Progress not updated.");
+            return Ok(());
+        }
+    };
+    if outcome.success {
+        let played_ep = outcome
+            .final_episode
+            .unwrap_or_else(|| item.last_episode.clone());
+        let updated_ep = last_episode_after_play(&item.last_episode, &played_ep);
+        db.upsert_seen(&item.ani_id, &item.title, &updated_ep)?;
+        record_watched_episode(db, &item.ani_id, &played_ep)?;
+        println!("Updated progress: {} -> episode {}", item.title, updated_ep);
+        warn_on_hook_failure(run_progress_hook(item, &updated_ep));
+    } else {
+        println!("{}", playback_failure_message(&outcome));
+    }
+    Ok(())
+}
+
+fn run_replay(db: &Database, launch: &LaunchOptions) -> Result<()> {
     match db.last_seen()? {
         Some(item) => {
             println!("Replaying last seen episode:");
             println!("  Title: {}", item.title);
             println!("  Episode: {}", item.last_episode);
 
-            let outcome = run_ani_cli_replay(&item, None);
+            let outcome = run_ani_cli_replay(&item, None, launch);
             let outcome = match outcome {
                 Ok(outcome) => outcome,
                 Err(err) => {
@@ -83,14 +363,17 @@ fn run_replay(db: &Database) -> Result<()> {
                 }
             };
             if outcome.success {
-                let updated_ep = outcome
+                let played_ep = outcome
                     .final_episode
                     .unwrap_or_else(|| item.last_episode.clone());
+                let updated_ep = last_episode_after_play(&item.last_episode, &played_ep);
                 db.upsert_seen(&item.ani_id, &item.title, &updated_ep)?;
+                record_watched_episode(db, &item.ani_id, &played_ep)?;
                 println!(
                     "Replay finished: {} now on episode {}",
                     item.title, updated_ep
                 );
+                warn_on_hook_failure(run_progress_hook(&item, &updated_ep));
             } else {
                 println!("{}", playback_failure_message(&outcome));
             }
@@ -100,32 +383,940 @@ fn run_replay(db: &Database) -> Result<()> {
     Ok(())
 }
 
-fn run_list(db: &Database) -> Result<()> {
-    let items = db.list_seen()?;
+fn warn_on_hook_failure(result: Result<()>) {
+    if let Err(err) = result {
+        eprintln!("{} {err:#}", warning_label());
+    }
+}
+
+enum ListOutput {
+    Table,
+    Json,
+    Template(ListTemplate),
+}
+
+/// `list --tag` filter; an empty tag set matches every entry.
+pub(crate) struct TagFilter {
+    pub(crate) tags: Vec<String>,
+    pub(crate) match_all: bool,
+}
+
+impl TagFilter {
+    pub(crate) fn matches(&self, item: &SeenEntry) -> bool {
+        if self.tags.is_empty() {
+            return true;
+        }
+        let has_tag = |tag: &String| item.tags.contains(tag);
+        if self.match_all {
+            self.tags.iter().all(has_tag)
+        } else {
+            self.tags.iter().any(has_tag)
+        }
+    }
+}
+
+fn run_list(
+    db: &Database,
+    output: &ListOutput,
+    filter: &TagFilter,
+    order: ListOrder,
+) -> Result<()> {
+    let mut items = db.list_seen_sorted(order)?;
+    items.retain(|item| filter.matches(item));
+    match output {
+        ListOutput::Json => {
+            println!("{}", list_json(&items));
+            return Ok(());
+        }
+        ListOutput::Template(template) => {
+            for item in &items {
+                println!("{}", template.render(item));
+            }
+            return Ok(());
+        }
+        ListOutput::Table => {}
+    }
     if items.is_empty() {
-        println!("No tracked entries yet. Run `anitrack start` first.");
+        if filter.tags.is_empty() {
+            println!("No tracked entries yet. Run `anitrack start` first.");
+        } else {
+            println!("No tracked entries match the given tags.");
+        }
         return Ok(());
     }
 
-    println!(
+    let header = format!(
         "{:<20} {:<40} {:<10} {:<28}",
         "ANI ID", "TITLE", "EP", "LAST SEEN"
     );
-    for item in items {
+    println!("{}", paint(&header, Paint::Bold));
+    for item in &items {
         println!(
             "{:<20} {:<40} {:<10} {:<28}",
             truncate(&item.ani_id, 20),
             truncate(&item.title, 40),
-            item.last_episode,
+            display_episode_label(&item.last_episode),
             format_last_seen_display(&item.last_seen_at)
         );
     }
+    println!();
+    println!("{}", list_summary(&items));
+    Ok(())
+}
+
+/// Footer under the `list` table: how many entries are shown, how many are caught up (no next
+/// episode, as the TUI's unwatched jump decides without an episode list), and the latest watch.
+pub(crate) fn list_summary(items: &[SeenEntry]) -> String {
+    let caught_up = items
+        .iter()
+        .filter(|item| !entry_has_next_episode(item, None))
+        .count();
+    let mut summary = format!(
+        "{} entr{}, {caught_up} caught up",
+        items.len(),
+        if items.len() == 1 { "y" } else { "ies" }
+    );
+    if let Some(latest) = items.iter().map(|item| &item.last_seen_at).max() {
+        summary.push_str(&format!(
+            ", last watched {}",
+            format_last_seen_display(latest)
+        ));
+    }
+    summary
+}
+
+fn run_list_watch(
+    db: &Database,
+    interval: Duration,
+    output: &ListOutput,
+    filter: &TagFilter,
+    order: ListOrder,
+) -> Result<()> {
+    watch_for_interrupt()?;
+    while !interrupt_requested() {
+        if !matches!(output, ListOutput::Json) {
+            // Clear screen and home the cursor so each refresh replaces the previous one.
+            print!("\x1b[2J\x1b[H");
+        }
+        run_list(db, output, filter, order)?;
+        io::stdout().flush()?;
+
+        let mut waited = Duration::ZERO;
+        while waited < interval && !interrupt_requested() {
+            let step = Duration::from_millis(100).min(interval - waited);
+            thread::sleep(step);
+            waited += step;
+        }
+    }
+    Ok(())
+}
+
+fn run_export(db: &Database, output: Option<&Path>, format: ExportFormat) -> Result<()> {
+    let (document, kind) = match format {
+        ExportFormat::Json => (format!("{}\n", export_backup(db)?), "backup"),
+        ExportFormat::Csv => (export_csv(&db.list_seen()?), "watch list"),
+        ExportFormat::Markdown => (export_markdown(&db.list_seen()?), "watch list"),
+    };
+    match output {
+        Some(path) => {
+            fs::write(path, document)
+                .with_context(|| format!("failed writing {kind} to {}", path.display()))?;
+            println!("Exported {kind} to {}.", path.display());
+        }
+        None => print!("{document}"),
+    }
+    Ok(())
+}
+
+fn run_export_history(db: &Database, path: Option<PathBuf>, overwrite: bool) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => database_file_path()?.with_file_name("ani-hsts"),
+    };
+    let items = db.list_seen()?;
+    write_history_file(&path, &export_history(&items), overwrite)
+        .with_context(|| format!("failed writing ani-cli history to {}", path.display()))?;
+    println!(
+        "Exported {} show(s) as ani-cli history to {}.",
+        items.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Writes an exported history, refusing to replace an existing file (which may be `ani-cli`'s own
+/// history) unless `overwrite` is set. The existence check and the create are one step.
+pub(crate) fn write_history_file(path: &Path, contents: &str, overwrite: bool) -> Result<()> {
+    if overwrite {
+        fs::write(path, contents)?;
+        return Ok(());
+    }
+    let mut file = match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+    {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            bail!("the file already exists; pass --overwrite to replace it")
+        }
+        Err(err) => return Err(err.into()),
+    };
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+fn run_import(db: &Database, path: &Path) -> Result<()> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed reading backup {}", path.display()))?;
+    let count = import_backup(db, &raw)
+        .with_context(|| format!("failed importing backup {}", path.display()))?;
+    println!("Imported {count} show(s) from {}.", path.display());
     Ok(())
 }
 
-fn open_db() -> Result<Database> {
+/// No watch status is stored yet, so every entry counts as `watching` and any other status
+/// filter could never match.
+fn ensure_status_filter_supported(status: &str) -> Result<()> {
+    if status != "watching" {
+        bail!(
+            "`--status {status}` cannot match any entry: anitrack does not store a watch status \
+             yet, so every entry counts as `watching`"
+        );
+    }
+    Ok(())
+}
+
+fn run_prune(db: &Database, older_than: Duration, status: Option<&str>, apply: bool) -> Result<()> {
+    if let Some(status) = status {
+        ensure_status_filter_supported(status)?;
+    }
+    let cutoff = Utc::now() - chrono::Duration::from_std(older_than)?;
+    let candidates = prune_candidates(db.list_seen()?, cutoff);
+    let cutoff_display = format_last_seen_display(&cutoff.to_rfc3339());
+    if candidates.is_empty() {
+        println!("No tracked entries last watched before {cutoff_display}.");
+        return Ok(());
+    }
+
+    if apply {
+        let ids = candidates
+            .iter()
+            .map(|item| item.ani_id.clone())
+            .collect::<Vec<_>>();
+        let removed = db.delete_seen_entries(&ids)?;
+        println!("Pruned {removed} show(s) last watched before {cutoff_display}:");
+    } else {
+        println!(
+            "Would prune {} show(s) last watched before {cutoff_display}:",
+            candidates.len()
+        );
+    }
+    for item in &candidates {
+        println!(
+            "  {} (episode {}, last seen {})",
+            item.title,
+            display_episode_label(&item.last_episode),
+            format_last_seen_display(&item.last_seen_at)
+        );
+    }
+    if !apply {
+        println!("Nothing was deleted. Re-run with --yes to delete these entries.");
+    }
+    Ok(())
+}
+
+/// Entries whose `last_seen_at` is before `cutoff`. Unparseable timestamps are never pruned.
+pub(crate) fn prune_candidates(items: Vec<SeenEntry>, cutoff: DateTime<Utc>) -> Vec<SeenEntry> {
+    items
+        .into_iter()
+        .filter(|item| {
+            DateTime::parse_from_rfc3339(&item.last_seen_at).is_ok_and(|seen| seen < cutoff)
+        })
+        .collect()
+}
+
+fn run_merge(db: &Database, keep_id: &str, drop_id: &str) -> Result<()> {
+    if keep_id == drop_id {
+        bail!("cannot merge `{keep_id}` with itself");
+    }
+    let lookup = |ani_id: &str| {
+        db.get_seen(ani_id)?
+            .ok_or_else(|| anyhow!("no tracked entry with id `{ani_id}`"))
+    };
+    let keep = lookup(keep_id)?;
+    let drop = lookup(drop_id)?;
+    let merged = merge_entries(&keep, &drop);
+    db.merge_seen(&merged, drop_id)?;
+
+    println!("Merged {drop_id} into {keep_id}:");
+    println!("  Title: {}", merged.title);
+    println!("  Episode: {}", display_episode_label(&merged.last_episode));
+    println!(
+        "  Last seen: {}",
+        format_last_seen_display(&merged.last_seen_at)
+    );
+    if !merged.tags.is_empty() {
+        println!("  Tags: {}", merged.tags.join(", "));
+    }
+    if merged.intro_skip_seconds > 0 {
+        println!("  Intro skip: {}s", merged.intro_skip_seconds);
+    }
+    Ok(())
+}
+
+/// Folds `drop` into `keep`: the further episode, the later last-seen time, and the earlier
+/// first-seen time win (a missing or invalid time never does), tags are combined, and `keep`'s
+/// intro skip is used unless it has none. Id, title, and kind stay `keep`'s.
+pub(crate) fn merge_entries(keep: &SeenEntry, drop: &SeenEntry) -> SeenEntry {
+    let last_episode =
+        if compare_episode_labels(&drop.last_episode, &keep.last_episode) == Ordering::Greater {
+            drop.last_episode.clone()
+        } else {
+            keep.last_episode.clone()
+        };
+    let parsed = |raw: &str| DateTime::parse_from_rfc3339(raw).ok();
+    let last_seen_at = match (parsed(&keep.last_seen_at), parsed(&drop.last_seen_at)) {
+        (Some(kept), Some(dropped)) if dropped > kept => drop.last_seen_at.clone(),
+        (None, Some(_)) => drop.last_seen_at.clone(),
+        _ => keep.last_seen_at.clone(),
+    };
+    // Compared as instants: the stored strings can carry different UTC offsets.
+    let first_seen_at = match (parsed(&keep.first_seen_at), parsed(&drop.first_seen_at)) {
+        (Some(kept), Some(dropped)) if dropped < kept => drop.first_seen_at.clone(),
+        (None, Some(_)) => drop.first_seen_at.clone(),
+        _ => keep.first_seen_at.clone(),
+    };
+    let intro_skip_seconds = match keep.intro_skip_seconds {
+        0 => drop.intro_skip_seconds,
+        seconds => seconds,
+    };
+    // The external link is a pair, so it is taken whole from whichever entry has one.
+    let linked = if keep.external_provider.is_some() {
+        keep
+    } else {
+        drop
+    };
+    SeenEntry {
+        last_episode,
+        last_seen_at,
+        intro_skip_seconds,
+        first_seen_at,
+        tags: normalize_tags(keep.tags.iter().chain(&drop.tags)),
+        total_eps_override: keep.total_eps_override.or(drop.total_eps_override),
+        default_action: keep
+            .default_action
+            .clone()
+            .or_else(|| drop.default_action.clone()),
+        airing_status: keep
+            .airing_status
+            .clone()
+            .or_else(|| drop.airing_status.clone()),
+        rating: keep.rating.or(drop.rating),
+        watched_episodes: match (&keep.watched_episodes, &drop.watched_episodes) {
+            (None, None) => None,
+            (kept, dropped) => Some(normalize_watched_episodes(
+                kept.iter().chain(dropped).flatten(),
+            )),
+        },
+        external_provider: linked.external_provider.clone(),
+        external_id: linked.external_id.clone(),
+        ..keep.clone()
+    }
+}
+
+fn run_migrate(db: &Database, allow_foreign_db: bool) -> Result<()> {
+    let before = db.schema_version()?;
+    if allow_foreign_db {
+        db.migrate_allowing_foreign_tables()?;
+    } else {
+        db.migrate()?;
+    }
+    let after = db.schema_version()?;
+    if before == after {
+        println!("Database schema is already at version {after}.");
+    } else {
+        println!("Migrated database schema from version {before} to {after}.");
+    }
+    Ok(())
+}
+
+fn run_normalize_titles(db: &Database) -> Result<()> {
+    let updates = normalized_title_updates(&db.list_seen()?);
+    if updates.is_empty() {
+        println!("All titles are already normalized.");
+        return Ok(());
+    }
+    let updated = db.set_titles_and_totals(&updates)?;
+    for (_, title, total) in &updates {
+        println!("{title}\t{total} episodes");
+    }
+    println!("Normalized {updated} title(s).");
+    Ok(())
+}
+
+/// `(ani_id, title, total)` for each entry whose title still carries an `(N episodes)` suffix:
+/// the title without it, and the count to store as the episode-count override.
+pub(crate) fn normalized_title_updates(items: &[SeenEntry]) -> Vec<(String, String, u32)> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let (title, total) = parse_title_and_total_eps(&item.title);
+            Some((item.ani_id.clone(), title, total?))
+        })
+        .collect()
+}
+
+/// The `(provider, id)` pair `link` stores; `None` clears the link.
+pub(crate) fn external_link(
+    anilist: Option<u64>,
+    mal: Option<u64>,
+) -> Option<(&'static str, String)> {
+    match (anilist, mal) {
+        (Some(id), _) => Some(("anilist", id.to_string())),
+        (None, Some(id)) => Some(("mal", id.to_string())),
+        (None, None) => None,
+    }
+}
+
+/// Display name of a stored external provider.
+pub(crate) fn external_provider_label(provider: &str) -> &str {
+    match provider {
+        "anilist" => "AniList",
+        "mal" => "MyAnimeList",
+        other => other,
+    }
+}
+
+fn run_add(db: &Database, ani_id: &str, title: &str, episode: &str) -> Result<()> {
+    let item = add_entry(db, ani_id, title, episode)?;
+    println!("Added {}:", item.ani_id);
+    println!("  Title: {}", item.title);
+    println!("  Episode: {}", item.last_episode);
+    println!(
+        "  Last seen: {}",
+        format_last_seen_display(&item.last_seen_at)
+    );
+    Ok(())
+}
+
+/// Inserts a new entry as if `episode` of `title` had just been watched, storing the title the
+/// same way playback would. Existing entries are left alone, so their progress and settings
+/// cannot be overwritten by accident.
+pub(crate) fn add_entry(
+    db: &Database,
+    ani_id: &str,
+    title: &str,
+    episode: &str,
+) -> Result<SeenEntry> {
+    let ani_id = ani_id.trim();
+    let title = title.trim();
+    if ani_id.is_empty() {
+        bail!("ANI_ID must not be empty");
+    }
+    if title.is_empty() {
+        bail!("TITLE must not be blank");
+    }
+    if let Some(existing) = db.get_seen(ani_id)? {
+        bail!(
+            "`{ani_id}` is already tracked as {} (episode {}); use `anitrack continue-from` or the TUI to change it",
+            existing.title,
+            existing.last_episode
+        );
+    }
+    let (stored_title, total_eps_override) = stored_title_and_total(title);
+    db.record_seen(ani_id, &stored_title, total_eps_override, episode)?;
+    db.get_seen(ani_id)?
+        .ok_or_else(|| anyhow!("entry `{ani_id}` was not stored"))
+}
+
+fn run_link(db: &Database, ani_id: &str, link: Option<(&str, String)>) -> Result<()> {
+    let item = db
+        .get_seen(ani_id)?
+        .ok_or_else(|| anyhow!("no tracked entry with id `{ani_id}`"))?;
+    db.set_external_link(
+        ani_id,
+        link.as_ref().map(|(provider, id)| (*provider, id.as_str())),
+    )?;
+    match link {
+        Some((provider, id)) => println!(
+            "Linked {} to {} id {id}.",
+            item.title,
+            external_provider_label(provider)
+        ),
+        None => println!("Removed the external link from {}.", item.title),
+    }
+    Ok(())
+}
+
+fn run_doctor(launch: &LaunchOptions, check: bool) -> Result<()> {
+    println!("database\t{}", database_file_path()?.display());
+    println!("ani-cli\t{}", resolve_ani_cli_bin().display());
+    println!("history\t{}", ani_cli_histfile().display());
+    let player = resolve_player(launch);
+    println!("player\t{} ({})", player.command, player.source);
+    if !check {
+        return Ok(());
+    }
+
+    let program = player.command.split_whitespace().next().unwrap_or_default();
+    match find_on_path(program, env::var_os("PATH")) {
+        Some(path) => {
+            println!("player found\t{}", path.display());
+            Ok(())
+        }
+        None => Err(anyhow!(
+            "player `{program}` was not found on PATH; install it or pass --player"
+        )),
+    }
+}
+
+/// Resolves a command the way a shell would: paths are checked directly, bare names are looked
+/// up in each `PATH` entry (with `.exe` appended on Windows).
+pub(crate) fn find_on_path(program: &str, path_var: Option<OsString>) -> Option<PathBuf> {
+    if program.is_empty() {
+        return None;
+    }
+    let candidate = Path::new(program);
+    if candidate.components().count() > 1 {
+        return candidate.is_file().then(|| candidate.to_path_buf());
+    }
+    let names = if cfg!(windows) {
+        vec![program.to_string(), format!("{program}.exe")]
+    } else {
+        vec![program.to_string()]
+    };
+    env::split_paths(&path_var?).find_map(|dir| {
+        names
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+fn run_untracked(db: &Database, json: bool) -> Result<()> {
+    let (entries, warnings) = untracked_history_entries(db)?;
+    for warning in &warnings {
+        eprintln!("{} {warning}", warning_label());
+    }
+    if json {
+        println!("{}", untracked_json(&entries));
+        return Ok(());
+    }
+
+    for entry in &entries {
+        println!("{}\t{}\t{}", entry.ep, entry.id, entry.title);
+    }
+    println!("{} untracked show(s) in ani-cli history.", entries.len());
+    Ok(())
+}
+
+fn run_dump_history(hist_dir: Option<&Path>) {
+    let histfile = match hist_dir {
+        Some(path) if path.is_file() => path.to_path_buf(),
+        Some(dir) => dir.join("ani-hsts"),
+        None => ani_cli_histfile(),
+    };
+    let hist_read = read_hist_map(&histfile);
+    for warning in &hist_read.warnings {
+        eprintln!("{} {warning}", warning_label());
+    }
+    for line in history_dump_lines(
+        &histfile,
+        histfile.exists(),
+        detect_title_first_from_env(),
+        &hist_read,
+    ) {
+        println!("{line}");
+    }
+}
+
+/// Tab-separated dump of a parsed history: the file, how it was parsed, every parsed line in
+/// file order with its index, the per-show map detection uses (sorted by id), and the number of
+/// skipped lines.
+pub(crate) fn history_dump_lines(
+    histfile: &Path,
+    exists: bool,
+    title_first: bool,
+    hist_read: &HistRead,
+) -> Vec<String> {
+    let mut lines = vec![
+        format!("file\t{}", histfile.display()),
+        format!("exists\t{exists}"),
+        format!("title_first\t{title_first}"),
+        format!("ordered\t{}", hist_read.ordered_entries.len()),
+    ];
+    lines.extend(
+        hist_read
+            .ordered_entries
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| format!("{idx}\t{}\t{}\t{}", entry.ep, entry.id, entry.title)),
+    );
+    let mut deduped = hist_read.entries.values().collect::<Vec<_>>();
+    deduped.sort_by(|a, b| a.id.cmp(&b.id));
+    lines.push(format!("deduped\t{}", deduped.len()));
+    lines.extend(
+        deduped
+            .iter()
+            .map(|entry| format!("{}\t{}\t{}", entry.id, entry.ep, entry.title)),
+    );
+    lines.push(format!("skipped_lines\t{}", hist_read.skipped_lines));
+    lines
+}
+
+pub(crate) fn untracked_json(entries: &[HistEntry]) -> String {
+    let rows = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "ani_id": entry.id,
+                "title": entry.title,
+                "last_episode": entry.ep,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::Value::Array(rows).to_string()
+}
+
+const WATCH_STATUSES: [&str; 4] = ["watching", "completed", "dropped", "on_hold"];
+
+fn run_stats(db: &Database, by_status: bool, json: bool) -> Result<()> {
+    let counts = status_breakdown(&db.status_counts()?);
+    let total: u64 = counts.iter().map(|(_, count)| count).sum();
+
+    if json {
+        let mut out = serde_json::Map::new();
+        out.insert("total".to_string(), json!(total));
+        if by_status {
+            let statuses = counts
+                .iter()
+                .map(|(status, count)| (status.clone(), json!(count)))
+                .collect::<serde_json::Map<_, _>>();
+            out.insert("by_status".to_string(), serde_json::Value::Object(statuses));
+        }
+        println!("{}", serde_json::Value::Object(out));
+        return Ok(());
+    }
+
+    // Tab-separated `key value` lines keep the output easy to parse from scripts.
+    println!("total\t{total}");
+    if by_status {
+        for (status, count) in &counts {
+            println!("{status}\t{count}");
+        }
+    }
+    Ok(())
+}
+
+/// Counts for every known status in a fixed order, zeros included.
+pub(crate) fn status_breakdown(raw_counts: &[(String, u64)]) -> Vec<(String, u64)> {
+    WATCH_STATUSES
+        .iter()
+        .map(|status| {
+            let count = raw_counts
+                .iter()
+                .filter(|(raw, _)| raw == status)
+                .map(|(_, count)| count)
+                .sum();
+            (status.to_string(), count)
+        })
+        .collect()
+}
+
+pub(crate) fn journal_mode_from_env(env_value: Option<String>) -> Result<JournalMode> {
+    match env_value {
+        Some(value) if !value.trim().is_empty() => JournalMode::parse(&value).ok_or_else(|| {
+            anyhow!("invalid ANI_TRACK_JOURNAL_MODE `{value}` (expected wal, delete, or truncate)")
+        }),
+        _ => Ok(JournalMode::default()),
+    }
+}
+
+/// Parses `$ANI_TRACK_STALE_AFTER` with the `prune --older-than` age syntax (`30d`, `6w`); unset,
+/// empty, or zero flags no entries as stale.
+pub(crate) fn stale_after_from_env(env_value: Option<String>) -> Result<Option<Duration>> {
+    match env_value {
+        Some(value) if !value.trim().is_empty() => {
+            let age =
+                parse_age(&value).map_err(|err| anyhow!("invalid ANI_TRACK_STALE_AFTER: {err}"))?;
+            Ok((!age.is_zero()).then_some(age))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Default `$ANI_TRACK_WAL_CHECKPOINT_SECS`: how often a running TUI checkpoints the WAL.
+const DEFAULT_WAL_CHECKPOINT_SECS: u64 = 300;
+
+/// Parses `$ANI_TRACK_WAL_CHECKPOINT_SECS`; `0` turns periodic checkpoints off.
+pub(crate) fn wal_checkpoint_interval_from_env(
+    env_value: Option<String>,
+) -> Result<Option<Duration>> {
+    let secs = match env_value {
+        Some(value) if !value.trim().is_empty() => {
+            value.trim().parse::<u64>().map_err(|_| {
+                anyhow!(
+                    "invalid ANI_TRACK_WAL_CHECKPOINT_SECS `{value}` (expected whole seconds, 0 to disable)"
+                )
+            })?
+        }
+        _ => DEFAULT_WAL_CHECKPOINT_SECS,
+    };
+    Ok((secs > 0).then(|| Duration::from_secs(secs)))
+}
+
+/// Commands that launch ani-cli or the TUI need a terminal on stdin; without one (cron, CI)
+/// ani-cli's menus misbehave, so refuse up front instead.
+pub(crate) fn require_interactive_stdin(command: &str, stdin_is_terminal: bool) -> Result<()> {
+    if stdin_is_terminal {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "`anitrack {command}` requires an interactive terminal (stdin is not a TTY); list, stats, export, and sync work non-interactively"
+    ))
+}
+
+/// Whether an opt-in environment variable is set to `1`, `true`, or `yes`.
+fn env_flag(name: &str) -> bool {
+    env::var(name).is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"))
+}
+
+/// Where the TUI remembers its selection, when `ANI_TRACK_TUI_REMEMBER` opts into it.
+fn tui_state_file_from_env() -> Result<Option<PathBuf>> {
+    env_flag("ANI_TRACK_TUI_REMEMBER")
+        .then(tui_state_file_path)
+        .transpose()
+}
+
+pub(crate) fn previous_confirm_from_env(env_value: Option<String>) -> Result<PreviousConfirm> {
+    match env_value {
+        Some(value) if !value.trim().is_empty() => {
+            PreviousConfirm::parse(&value).ok_or_else(|| {
+                anyhow!(
+                    "invalid ANI_TRACK_CONFIRM_PREVIOUS `{value}` (expected never, zero, or special)"
+                )
+            })
+        }
+        _ => Ok(PreviousConfirm::default()),
+    }
+}
+
+pub(crate) fn replay_mode_from_env(env_value: Option<String>) -> Result<ReplayMode> {
+    match env_value {
+        Some(value) if !value.trim().is_empty() => ReplayMode::parse(&value).ok_or_else(|| {
+            anyhow!("invalid ANI_TRACK_REPLAY_MODE `{value}` (expected continue or direct)")
+        }),
+        _ => Ok(ReplayMode::default()),
+    }
+}
+
+/// Parses `$ANI_TRACK_COLUMNS`, a comma-separated list of Library columns in display order.
+/// Unset or empty shows every column; the title column cannot be left out.
+pub(crate) fn library_columns_from_env(env_value: Option<String>) -> Result<Vec<LibraryColumn>> {
+    let Some(value) = env_value.filter(|value| !value.trim().is_empty()) else {
+        return Ok(LibraryColumn::ALL.to_vec());
+    };
+    let mut columns = Vec::new();
+    for name in value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let column = LibraryColumn::parse(name).ok_or_else(|| {
+            anyhow!(
+                "invalid ANI_TRACK_COLUMNS column `{name}` (expected title, total, last_ep, rating, or last_seen)"
+            )
+        })?;
+        if columns.contains(&column) {
+            bail!("ANI_TRACK_COLUMNS lists `{name}` more than once");
+        }
+        columns.push(column);
+    }
+    if !columns.contains(&LibraryColumn::Title) {
+        bail!("ANI_TRACK_COLUMNS must include title");
+    }
+    Ok(columns)
+}
+
+/// The tie-break set by `ANI_TRACK_SORT_TIEBREAK`; `None` when unset.
+pub(crate) fn sort_tie_break_from_env(env_value: Option<String>) -> Result<Option<SortTieBreak>> {
+    match env_value {
+        Some(value) if !value.trim().is_empty() => SortTieBreak::parse(&value)
+            .map(Some)
+            .ok_or_else(|| {
+                anyhow!(
+                    "invalid ANI_TRACK_SORT_TIEBREAK `{value}` (expected last_seen, title, or added)"
+                )
+            }),
+        _ => Ok(None),
+    }
+}
+
+/// Splits `$ANI_TRACK_ANI_CLI_ARGS` on whitespace; there is no quoting, so an argument
+/// containing spaces has to be given with `--ani-cli-arg` instead.
+pub(crate) fn ani_cli_args_from_env(env_value: Option<String>) -> Vec<String> {
+    env_value
+        .map(|value| value.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+pub(crate) fn ellipsis_style_from_env(env_value: Option<String>) -> Result<EllipsisStyle> {
+    match env_value {
+        Some(value) if !value.trim().is_empty() => EllipsisStyle::parse(&value).ok_or_else(|| {
+            anyhow!("invalid ANI_TRACK_ELLIPSIS `{value}` (expected end or middle)")
+        }),
+        _ => Ok(EllipsisStyle::default()),
+    }
+}
+
+pub(crate) fn list_json(items: &[SeenEntry]) -> String {
+    let rows = items
+        .iter()
+        .map(|item| {
+            json!({
+                "ani_id": item.ani_id,
+                "title": item.title,
+                "last_episode": item.last_episode,
+                "last_seen_at": item.last_seen_at,
+                "first_seen_at": item.first_seen_at,
+                "tags": item.tags,
+                "kind": item.kind.as_str(),
+                "airing_status": item.airing_status,
+                "rating": item.rating,
+                "external_provider": item.external_provider,
+                "external_id": item.external_id,
+                "watched_episodes": item.watched_episodes,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::Value::Array(rows).to_string()
+}
+
+/// Creates `dir` if needed and checks that a file can be created in it, so a read-only home or a
+/// permissions problem is reported with the path instead of as a generic SQLite open failure.
+pub(crate) fn ensure_writable_dir(dir: &Path) -> Result<()> {
+    let not_writable = || format!("data directory is not writable: {}", dir.display());
+    fs::create_dir_all(dir).with_context(not_writable)?;
+    let nanos = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    let probe = dir.join(format!(
+        ".anitrack-write-check-{}-{nanos}",
+        std::process::id()
+    ));
+    match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+    {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            Ok(())
+        }
+        // Only a file someone managed to create there can be in the way, so the dir is writable.
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Ok(()),
+        Err(err) => Err(err).with_context(not_writable),
+    }
+}
+
+/// Refuses a read-only fallback whose schema is older than this build's: it cannot be migrated
+/// read-only, and queries on it would fail with raw SQL errors about missing columns.
+fn ensure_read_only_schema(db: &Database, db_path: &Path, reason: &str) -> Result<()> {
+    let version = db.schema_version()?;
+    if version < SCHEMA_VERSION {
+        bail!(
+            "database at {} needs migration (schema version {version}, this build uses {SCHEMA_VERSION}) but can only be opened read-only because {reason}; fix that and run anitrack again to upgrade it",
+            db_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// What `open_db` does with a database whose schema is older than this build's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SchemaPolicy {
+    /// Upgrade it in place (the default).
+    Migrate,
+    /// Fail instead of upgrading (`--no-migrate`).
+    RequireCurrent,
+    /// Leave it as is, for `anitrack migrate` to upgrade.
+    Leave,
+}
+
+/// Opens the database and brings its schema up to date as `schema` says. Only
+/// `anitrack migrate --allow-foreign-db` sets `allow_foreign_db`, which skips the check that the
+/// file holds no tables anitrack did not create.
+fn open_db(
+    allow_read_only: bool,
+    allow_foreign_db: bool,
+    schema: SchemaPolicy,
+) -> Result<Database> {
     let db_path = database_file_path()?;
-    let db = Database::open(&db_path)?;
-    db.migrate()?;
-    Ok(db)
+    if let Some(data_dir) = db_path.parent()
+        && let Err(err) = ensure_writable_dir(data_dir)
+    {
+        if allow_read_only
+            && db_path.exists()
+            && let Ok(db) = Database::open_read_only(&db_path)
+        {
+            ensure_read_only_schema(&db, &db_path, &format!("{err:#}"))?;
+            eprintln!("{} {err:#}; showing read-only data.", warning_label());
+            return Ok(db);
+        }
+        return Err(err);
+    }
+    let journal_mode = journal_mode_from_env(env::var("ANI_TRACK_JOURNAL_MODE").ok())?;
+    let opened = Database::open(&db_path).and_then(|db| {
+        // Refuse another app's file before switching its journal mode, which would leave WAL files.
+        if !allow_foreign_db {
+            db.ensure_anitrack_database()?;
+        }
+        if let Err(err) = db.set_journal_mode(journal_mode)
+            && classify_open_error(&err) != Some(DbOpenIssue::Locked)
+        {
+            eprintln!(
+                "{} {err:#}. If the database is on a network or FUSE filesystem, set ANI_TRACK_JOURNAL_MODE=delete (or truncate).",
+                warning_label()
+            );
+        }
+        Ok(db)
+    });
+    let migrated = opened.and_then(|db| {
+        match schema {
+            SchemaPolicy::Migrate => db.migrate()?,
+            SchemaPolicy::RequireCurrent => db.ensure_current_schema()?,
+            SchemaPolicy::Leave => {}
+        }
+        Ok(db)
+    });
+    let err = match migrated {
+        Ok(db) => return Ok(db),
+        Err(err) => err,
+    };
+
+    match classify_open_error(&err) {
+        Some(DbOpenIssue::Locked) => {
+            if allow_read_only && let Ok(db) = Database::open_read_only(&db_path) {
+                ensure_read_only_schema(
+                    &db,
+                    &db_path,
+                    "the database is locked by another process",
+                )?;
+                eprintln!(
+                    "{} database at {} is locked for writing; showing read-only data.",
+                    warning_label(),
+                    db_path.display()
+                );
+                return Ok(db);
+            }
+            Err(anyhow!(
+                "database at {} is locked by another process. Another anitrack instance may be running; close it and retry.",
+                db_path.display()
+            ))
+        }
+        Some(DbOpenIssue::Corrupt) => Err(anyhow!(
+            "database at {path} is corrupted or not an anitrack database. Move it aside (for example `mv {path} {path}.bak`) to start fresh, or restore a backup copy.",
+            path = db_path.display()
+        )),
+        None => Err(err.context(format!("failed to open database at {}", db_path.display()))),
+    }
 }
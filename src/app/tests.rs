@@ -15,19 +15,45 @@ use std::sync::{Mutex, OnceLock};
 use chrono::{DateTime, Local};
 
 #[cfg(any(unix, windows))]
-use crate::db::Database;
-
+use crate::cli::EpisodeFormat;
+use crate::cli::{Cli, ColorChoice, Command, parse_age, parse_episode_label};
+use crate::db::{Database, JournalMode, ListSort, SortTieBreak};
+
+use super::backup::{
+    export_backup, export_csv, export_history, export_markdown, import_backup, parse_backup,
+};
+use super::color::{Paint, color_enabled, paint_if, paint_message_if};
 use super::episode::*;
+use super::hooks::{progress_hook_argv, spawn_hook};
+use super::list_format::ListTemplate;
 #[cfg(unix)]
 use super::run_replay;
 use super::tracking::*;
-use super::tui::TuiAction;
+use super::tui::{
+    EpisodePicker, HintMode, LibraryColumn, PendingPrevious, TuiAction, TuiOptions, TuiTheme,
+    TuiUiState, action_for_row, adjust_rating_input, editor_argv, episode_column_text, hint_text,
+    jump_row_index, last_watched_index, next_unwatched_index, parse_rating_input,
+    popup_rect_for_text, quick_find_index, quit_needs_confirmation, refresh_items,
+    render_main_view, selection_after_play, stale_entry_ids, timeline_rows,
+};
+use super::verify::{VerifyOutcome, verify_entries};
+use super::watch_later::{
+    WatchLater, format_resume_position, parse_watch_later, read_watch_later_dir,
+};
+use super::{
+    NOW_TEMPLATE, TagFilter, add_entry, ani_cli_args_from_env, ellipsis_style_from_env,
+    ensure_read_only_schema, ensure_status_filter_supported, ensure_writable_dir, find_on_path,
+    history_dump_lines, journal_mode_from_env, library_columns_from_env, list_json, list_summary,
+    merge_entries, normalized_title_updates, previous_confirm_from_env, prune_candidates,
+    replay_mode_from_env, require_interactive_stdin, sort_tie_break_from_env, stale_after_from_env,
+    status_breakdown, untracked_json, wal_checkpoint_interval_from_env, write_history_file,
+};
 #[cfg(any(unix, windows))]
-use super::{run_next, run_start};
+use super::{run_continue_from, run_next, run_start};
 
 #[test]
 fn parse_hist_line_accepts_valid_format() {
-    let entry = parse_hist_line("12\tshow-123\tShow Title").expect("line should parse");
+    let entry = parse_hist_line("12\tshow-123\tShow Title", false).expect("line should parse");
     assert_eq!(entry.ep, "12");
     assert_eq!(entry.id, "show-123");
     assert_eq!(entry.title, "Show Title");
@@ -35,7 +61,7 @@ fn parse_hist_line_accepts_valid_format() {
 
 #[test]
 fn parse_hist_line_accepts_space_separated_format_with_episode_zero() {
-    let entry = parse_hist_line("0 show-0 Episode Zero Title").expect("line should parse");
+    let entry = parse_hist_line("0 show-0 Episode Zero Title", false).expect("line should parse");
     assert_eq!(entry.ep, "0");
     assert_eq!(entry.id, "show-0");
     assert_eq!(entry.title, "Episode Zero Title");
@@ -43,15 +69,38 @@ fn parse_hist_line_accepts_space_separated_format_with_episode_zero() {
 
 #[test]
 fn parse_hist_line_preserves_decimal_episode_value() {
-    let entry = parse_hist_line("13.5\tshow-135\tMid-season OVA").expect("line should parse");
+    let entry =
+        parse_hist_line("13.5\tshow-135\tMid-season OVA", false).expect("line should parse");
     assert_eq!(entry.ep, "13.5");
     assert_eq!(entry.id, "show-135");
 }
 
+#[test]
+fn parse_hist_line_detects_title_first_order_only_when_enabled() {
+    let swapped = "7\tShow Title: Part 2\tReooPAxPMsHM4KPMY";
+    let entry = parse_hist_line(swapped, true).expect("line should parse");
+    assert_eq!(entry.ep, "7");
+    assert_eq!(entry.id, "ReooPAxPMsHM4KPMY");
+    assert_eq!(entry.title, "Show Title: Part 2");
+
+    let literal = parse_hist_line(swapped, false).expect("line should parse");
+    assert_eq!(literal.id, "Show Title: Part 2");
+
+    let standard =
+        parse_hist_line("7\tReooPAxPMsHM4KPMY\tShow Title", true).expect("line should parse");
+    assert_eq!(standard.id, "ReooPAxPMsHM4KPMY");
+    assert_eq!(standard.title, "Show Title");
+
+    // Single-word titles are indistinguishable from ids, so the standard order wins.
+    let ambiguous =
+        parse_hist_line("7\tNaruto\tReooPAxPMsHM4KPMY", true).expect("line should parse");
+    assert_eq!(ambiguous.id, "Naruto");
+}
+
 #[test]
 fn parse_hist_map_ignores_malformed_lines() {
     let raw = "1\tid-1\tShow One\nbadline\n\tid-2\tMissing episode\n2\tid-2\tShow Two\n";
-    let (parsed, ordered, skipped) = parse_hist_map(raw);
+    let (parsed, ordered, skipped) = parse_hist_map(raw, false);
     assert_eq!(parsed.len(), 2);
     assert_eq!(ordered.len(), 2);
     assert_eq!(skipped, 2);
@@ -61,6 +110,106 @@ fn parse_hist_map_ignores_malformed_lines() {
     );
 }
 
+#[test]
+fn color_auto_requires_a_terminal_and_no_no_color() {
+    assert!(color_enabled(ColorChoice::Auto, true, false));
+    assert!(!color_enabled(ColorChoice::Auto, false, false));
+    assert!(!color_enabled(ColorChoice::Auto, true, true));
+    assert!(color_enabled(ColorChoice::Always, false, true));
+    assert!(!color_enabled(ColorChoice::Never, true, false));
+}
+
+#[test]
+fn paint_message_colors_recorded_line_and_warning_labels_only() {
+    let message = "Recorded last seen: Frieren | episode 7\nNote: episode count changed\nWarning: ignored 1 malformed line(s)";
+    assert_eq!(paint_message_if(false, message), message);
+    assert_eq!(
+        paint_message_if(true, message),
+        "\x1b[32mRecorded last seen: Frieren | episode 7\x1b[0m\nNote: episode count changed\n\x1b[33mWarning:\x1b[0m ignored 1 malformed line(s)"
+    );
+}
+
+#[test]
+fn paint_wraps_already_padded_columns() {
+    let header = format!("{:<8} {:<4}", "ANI ID", "EP");
+    let painted = paint_if(true, &header, Paint::Bold);
+    assert_eq!(painted, format!("\x1b[1m{header}\x1b[0m"));
+    assert_eq!(
+        painted
+            .trim_start_matches("\x1b[1m")
+            .trim_end_matches("\x1b[0m"),
+        header
+    );
+    assert_eq!(paint_if(false, &header, Paint::Bold), header);
+}
+
+#[test]
+fn history_format_probe_needs_a_few_lines_and_a_majority_skipped() {
+    assert!(!history_format_looks_changed(0, 0));
+    assert!(!history_format_looks_changed(2, 2));
+    assert!(!history_format_looks_changed(4, 2));
+    assert!(history_format_looks_changed(3, 2));
+    assert!(history_format_looks_changed(5, 5));
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn read_hist_map_flags_a_history_in_an_unknown_format() {
+    let sandbox = TestSandbox::new("hist-format");
+    let histfile = sandbox.root.join("ani-hsts");
+    fs::write(
+        &histfile,
+        "{\"id\":\"id-1\",\"ep\":3}\n{\"id\":\"id-2\",\"ep\":7}\n{\"id\":\"id-3\",\"ep\":1}\n",
+    )
+    .expect("history should be written");
+
+    let read = read_hist_map(&histfile);
+    assert!(read.entries.is_empty());
+    assert_eq!(read.warnings.len(), 1);
+    assert!(read.warnings[0].contains("3 of 3 line(s)"));
+    assert!(read.warnings[0].contains("may have changed its history format"));
+
+    fs::write(&histfile, "1\tid-1\tShow One\nbadline\n2\tid-2\tShow Two\n")
+        .expect("history should be written");
+    let read = read_hist_map(&histfile);
+    assert_eq!(read.entries.len(), 2);
+    assert_eq!(read.warnings.len(), 1);
+    assert!(read.warnings[0].starts_with("ignored 1 malformed line(s)"));
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn history_dump_lines_show_ordered_deduped_and_skipped_entries() {
+    let sandbox = TestSandbox::new("hist-dump");
+    let histfile = sandbox.root.join("ani-hsts");
+    fs::write(
+        &histfile,
+        "3\tid-b\tShow B\nbadline\n1\tid-a\tShow A\n4\tid-b\tShow B\n",
+    )
+    .expect("history should be written");
+
+    let read = read_hist_map(&histfile);
+    assert_eq!(read.skipped_lines, 1);
+    let lines = history_dump_lines(&histfile, true, false, &read);
+    assert_eq!(
+        lines[1..],
+        [
+            "exists\ttrue",
+            "title_first\tfalse",
+            "ordered\t3",
+            "0\t3\tid-b\tShow B",
+            "1\t1\tid-a\tShow A",
+            "2\t4\tid-b\tShow B",
+            // The map keeps each show's last line, as detection sees it.
+            "deduped\t2",
+            "id-a\t1\tShow A",
+            "id-b\t4\tShow B",
+            "skipped_lines\t1",
+        ]
+    );
+    assert_eq!(lines[0], format!("file\t{}", histfile.display()));
+}
+
 #[test]
 fn detect_changed_latest_returns_most_recent_changed_entry() {
     let mut before = HashMap::new();
@@ -91,7 +240,7 @@ fn detect_changed_latest_returns_most_recent_changed_entry() {
         },
     ];
 
-    let changed = detect_changed_latest(&before, &after_ordered)
+    let changed = detect_changed_latest(&before, &after_ordered, false)
         .expect("entry should be detected as changed");
     assert_eq!(changed.id, "id-1");
     assert_eq!(changed.ep, "2");
@@ -106,7 +255,7 @@ fn detect_changed_latest_handles_episode_zero() {
         title: "Episode Zero Show".to_string(),
     }];
 
-    let changed = detect_changed_latest(&before, &after_ordered)
+    let changed = detect_changed_latest(&before, &after_ordered, false)
         .expect("episode 0 entry should be treated as a valid change");
     assert_eq!(changed.id, "id-0");
     assert_eq!(changed.ep, "0");
@@ -126,12 +275,76 @@ fn detect_latest_watch_event_accepts_appended_duplicate_episode_zero() {
     let before_ordered = vec![before_entry.clone()];
     let after_ordered = vec![before_entry.clone(), before_entry.clone()];
 
-    let changed = detect_latest_watch_event(&before_map, &before_ordered, &after_ordered)
+    let changed = detect_latest_watch_event(&before_map, &before_ordered, &after_ordered, false)
         .expect("appended duplicate entry should count as a watch event");
     assert_eq!(changed.id, "id-0");
     assert_eq!(changed.ep, "0");
 }
 
+#[test]
+fn added_episodes_for_collects_every_line_a_run_appended_for_the_show() {
+    let line = |ep: &str, id: &str| HistEntry {
+        ep: ep.to_string(),
+        id: id.to_string(),
+        title: "Show".to_string(),
+    };
+    let before_ordered = vec![line("4", "id-a"), line("9", "id-b")];
+    let after_ordered = vec![
+        line("4", "id-a"),
+        line("9", "id-b"),
+        line("5", "id-a"),
+        line("6", "id-a"),
+        line("10", "id-b"),
+        line("7", "id-a"),
+        line("7", "id-a"),
+    ];
+
+    assert_eq!(
+        added_episodes_for(&before_ordered, &after_ordered, "id-a"),
+        ["5", "6", "7"]
+    );
+    assert_eq!(
+        added_episodes_for(&before_ordered, &after_ordered, "id-b"),
+        ["10"]
+    );
+    assert!(added_episodes_for(&before_ordered, &before_ordered, "id-a").is_empty());
+}
+
+#[test]
+fn detect_latest_watch_event_can_require_an_episode_change() {
+    let line = |ep: &str, id: &str, title: &str| HistEntry {
+        ep: ep.to_string(),
+        id: id.to_string(),
+        title: title.to_string(),
+    };
+    let watched = line("3", "id-a", "Show A");
+    let before_ordered = vec![watched.clone()];
+    let before_map = HashMap::from([(watched.id.clone(), watched.clone())]);
+
+    // Re-opening the same episode appends a duplicate line.
+    let reopened = vec![watched.clone(), watched.clone()];
+    assert!(detect_latest_watch_event(&before_map, &before_ordered, &reopened, false).is_some());
+    assert!(detect_latest_watch_event(&before_map, &before_ordered, &reopened, true).is_none());
+    // A title-only rewrite of the line is no episode change either.
+    let retitled = vec![line("3", "id-a", "Show A (12 episodes)")];
+    assert!(detect_latest_watch_event(&before_map, &before_ordered, &retitled, false).is_some());
+    assert!(detect_latest_watch_event(&before_map, &before_ordered, &retitled, true).is_none());
+
+    // Moving on, and a show new to the history, still count.
+    let next = vec![watched.clone(), line("4", "id-a", "Show A")];
+    let changed = detect_latest_watch_event(&before_map, &before_ordered, &next, true)
+        .expect("a new episode is a watch");
+    assert_eq!(changed.ep, "4");
+    let first_add = vec![
+        watched.clone(),
+        line("1", "id-b", "Show B"),
+        watched.clone(),
+    ];
+    let changed = detect_latest_watch_event(&before_map, &before_ordered, &first_add, true)
+        .expect("a first-time add is a watch");
+    assert_eq!(changed.id, "id-b");
+}
+
 #[test]
 fn detect_latest_watch_event_prefers_new_added_entry_over_unchanged_trailing_line() {
     let before_a = HistEntry {
@@ -160,12 +373,63 @@ fn detect_latest_watch_event_prefers_new_added_entry_over_unchanged_trailing_lin
         before_b.clone(),
     ];
 
-    let changed = detect_latest_watch_event(&before_map, &before_ordered, &after_ordered)
+    let changed = detect_latest_watch_event(&before_map, &before_ordered, &after_ordered, false)
         .expect("new appended entry should be selected");
     assert_eq!(changed.id, "id-new");
     assert_eq!(changed.ep, "0");
 }
 
+#[test]
+fn detect_latest_watch_event_records_same_id_rewatch_in_other_mode() {
+    let sub_entry = HistEntry {
+        ep: "10".to_string(),
+        id: "id-show".to_string(),
+        title: "Show (12 episodes)".to_string(),
+    };
+    let dub_entry = HistEntry {
+        ep: "4".to_string(),
+        id: "id-show".to_string(),
+        title: "Show (8 episodes)".to_string(),
+    };
+    let mut before_map = HashMap::new();
+    before_map.insert(sub_entry.id.clone(), sub_entry.clone());
+    let before_ordered = vec![sub_entry.clone()];
+    let after_ordered = vec![dub_entry.clone()];
+
+    // The newest line wins even though its episode is behind the previous mode's progress.
+    let changed = detect_latest_watch_event(&before_map, &before_ordered, &after_ordered, false)
+        .expect("same-id rewrite should count as a watch event");
+    assert_eq!(changed, dub_entry);
+    assert!(looks_like_mode_switch(&sub_entry, &changed));
+}
+
+#[test]
+fn looks_like_mode_switch_ignores_regular_progress_and_other_shows() {
+    let entry = |ep: &str, id: &str, title: &str| HistEntry {
+        ep: ep.to_string(),
+        id: id.to_string(),
+        title: title.to_string(),
+    };
+    let prev = entry("3", "id-show", "Show (12 episodes)");
+
+    assert!(!looks_like_mode_switch(
+        &prev,
+        &entry("4", "id-show", "Show (12 episodes)")
+    ));
+    assert!(!looks_like_mode_switch(
+        &prev,
+        &entry("4", "id-other", "Show (8 episodes)")
+    ));
+    assert!(!looks_like_mode_switch(
+        &prev,
+        &entry("4", "id-show", "Renamed Show (8 episodes)")
+    ));
+    assert!(looks_like_mode_switch(
+        &prev,
+        &entry("1", "id-show", "Show (8 episodes)")
+    ));
+}
+
 #[test]
 fn detect_latest_watch_event_returns_none_when_content_is_unchanged() {
     let before_entry = HistEntry {
@@ -179,10 +443,49 @@ fn detect_latest_watch_event_returns_none_when_content_is_unchanged() {
     let before_ordered = vec![before_entry.clone()];
     let after_ordered = vec![before_entry];
 
-    let changed = detect_latest_watch_event(&before_map, &before_ordered, &after_ordered);
+    let changed = detect_latest_watch_event(&before_map, &before_ordered, &after_ordered, false);
     assert!(changed.is_none());
 }
 
+#[test]
+fn plan_history_sync_only_advances_tracked_shows_behind_history() {
+    let tracked = vec![
+        crate::db::SeenEntry {
+            ani_id: "behind".to_string(),
+            title: "Behind Show".to_string(),
+            last_episode: "2".to_string(),
+            last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+            first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+            ..crate::db::SeenEntry::default()
+        },
+        crate::db::SeenEntry {
+            ani_id: "ahead".to_string(),
+            title: "Ahead Show".to_string(),
+            last_episode: "10".to_string(),
+            last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+            first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+            ..crate::db::SeenEntry::default()
+        },
+        crate::db::SeenEntry {
+            ani_id: "untracked-in-history".to_string(),
+            title: "Missing Show".to_string(),
+            last_episode: "1".to_string(),
+            last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+            first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+            ..crate::db::SeenEntry::default()
+        },
+    ];
+    let (history, _, _) = parse_hist_map(
+        "10.5\tbehind\tBehind Show\n9\tahead\tAhead Show\n3\tother\tOther\n",
+        false,
+    );
+
+    let updates = plan_history_sync(&tracked, &history);
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].ani_id, "behind");
+    assert_eq!(updates[0].episode, "10.5");
+}
+
 #[test]
 fn history_file_touched_detects_metadata_change() {
     let before = Some(HistFileSig {
@@ -297,6 +600,33 @@ fn episode_ordinal_from_list_counts_zero_and_decimal_entries() {
     assert_eq!(ordinal, 27);
 }
 
+#[test]
+fn range_episode_labels_order_and_match_by_their_first_episode() {
+    use std::cmp::Ordering;
+
+    assert_eq!(compare_episode_labels("1-2", "3"), Ordering::Less);
+    assert_eq!(compare_episode_labels("1-2", "1"), Ordering::Equal);
+    assert_eq!(compare_episode_labels("3", "1-2"), Ordering::Greater);
+    let mut labels = vec!["3".to_string(), "1-2".to_string(), "0".to_string()];
+    labels.sort_by(|a, b| compare_episode_labels(a, b));
+    assert_eq!(labels, ["0", "1-2", "3"]);
+
+    assert!(episode_labels_match("1-2", "1-2"));
+    assert!(episode_labels_match(" 1-2", "1"));
+    assert!(!episode_labels_match("1-2", "2"));
+    // A negative label is a number, not a range.
+    assert_eq!(parse_episode_f64("-1"), Some(-1.0));
+    assert!(!episode_labels_match("-1", "1"));
+    assert_eq!(parse_episode_u32("12-13"), Some(12));
+    assert_eq!(parse_episode_u32("1-"), None);
+
+    // The raw label is kept for display and stays findable in the list.
+    assert_eq!(display_episode_label("01-02"), "01-02");
+    let episodes = vec!["1-2".to_string(), "3".to_string(), "4".to_string()];
+    assert_eq!(episode_ordinal_from_list("1-2", &episodes), Some(1));
+    assert_eq!(episode_ordinal_from_list("3", &episodes), Some(2));
+}
+
 #[test]
 fn build_progress_gauge_uses_episode_ordinal_when_list_available() {
     let mut episodes = vec!["0".to_string()];
@@ -308,12 +638,73 @@ fn build_progress_gauge_uses_episode_ordinal_when_list_available() {
         episodes.push(ep.to_string());
     }
 
+    let episodes = EpisodeList::new(episodes);
     let (ratio, label) =
         build_progress_gauge("25", 27, Some(&episodes)).expect("gauge should be generated");
     assert!((ratio - 1.0).abs() < 0.000_001);
     assert_eq!(label, "27/27");
 }
 
+#[test]
+fn indexed_episode_list_matches_linear_scan_on_large_lists() {
+    // One Piece-sized list with a prologue, recap specials, decimal episodes, and duplicates.
+    let mut labels = vec!["0".to_string()];
+    for ep in 1..=1100u32 {
+        labels.push(ep.to_string());
+        if ep % 97 == 0 {
+            labels.push(format!("{ep}.5"));
+        }
+        if ep % 250 == 0 {
+            labels.push(format!("Recap {ep}"));
+            labels.push(ep.to_string());
+        }
+    }
+    let episodes = EpisodeList::new(labels.clone());
+
+    let mut queries = labels.clone();
+    queries.extend(
+        [
+            "01",
+            " 42 ",
+            "0097.5",
+            "-0",
+            "1101",
+            "1100.25",
+            "Recap 500",
+            "recap 500",
+            "Special",
+            "",
+            "NaN",
+        ]
+        .map(str::to_string),
+    );
+    // Deterministic pseudo-random probes, including values off the list.
+    let mut seed = 0x2545_f491_u64;
+    for _ in 0..500 {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let value = (seed >> 33) % 1300;
+        queries.push(match seed % 3 {
+            0 => value.to_string(),
+            1 => format!("{value}.5"),
+            _ => format!("{value:04}"),
+        });
+    }
+
+    for query in &queries {
+        assert_eq!(
+            episodes.ordinal_of(query),
+            episode_ordinal_from_list(query, &labels),
+            "ordinal mismatch for {query:?}"
+        );
+    }
+    assert_eq!(episodes.labels(), labels.as_slice());
+
+    let short = EpisodeList::new(vec!["1".to_string(), "2".to_string()]);
+    assert_eq!(short.ordinal_of("02"), Some(2));
+}
+
 #[test]
 fn build_progress_gauge_falls_back_to_numeric_episode_without_list() {
     let (ratio, label) =
@@ -322,6 +713,39 @@ fn build_progress_gauge_falls_back_to_numeric_episode_without_list() {
     assert_eq!(label, "25/27");
 }
 
+#[test]
+fn progress_uses_the_episode_number_when_the_list_ends_before_it() {
+    // Only the first six episodes came back, but episode 10 was watched.
+    let labels = (1..=6).map(|ep| ep.to_string()).collect::<Vec<_>>();
+    let truncated = EpisodeList::new(labels.clone());
+    assert_eq!(episode_list_ends_before("10", &labels), Some("6"));
+    assert_eq!(episode_list_ends_before("6", &labels), None);
+    assert_eq!(episode_list_ends_before("Special", &labels), None);
+
+    // With a known total the gauge counts from the episode number, not the list length.
+    assert_eq!(
+        build_progress_gauge("10", 12, Some(&truncated)),
+        Some((10.0 / 12.0, "10/12".to_string()))
+    );
+    assert_eq!(
+        ProgressSource::of("10", Some(&truncated)),
+        ProgressSource::NumericEstimate
+    );
+    let with_total = render_entry("show-1", "Show (12 episodes)", "10");
+    assert_eq!(
+        next_episode_label(&with_total, Some(&labels)).as_deref(),
+        Some("11")
+    );
+
+    // Without one, Next still has somewhere to go.
+    let without_total = render_entry("show-1", "Show", "10");
+    assert!(entry_has_next_episode(&without_total, Some(&labels)));
+    assert_eq!(
+        next_episode_label(&without_total, Some(&labels)).as_deref(),
+        Some("11")
+    );
+}
+
 #[test]
 fn format_episode_progress_text_uses_ordinal_and_keeps_raw_label_when_needed() {
     let mut episodes = vec!["0".to_string()];
@@ -333,16 +757,100 @@ fn format_episode_progress_text_uses_ordinal_and_keeps_raw_label_when_needed() {
         episodes.push(ep.to_string());
     }
 
-    let text = format_episode_progress_text("25", 27, Some(&episodes));
+    let episodes = EpisodeList::new(episodes);
+    let text = format_episode_progress_text("25", 27, Some(&episodes), EpisodeFormat::XOfY);
     assert_eq!(text, "27 of 27 (episode 25)");
 }
 
 #[test]
 fn format_episode_progress_text_uses_plain_numeric_when_ordinal_matches() {
-    let text = format_episode_progress_text("12", 24, None);
+    let text = format_episode_progress_text("12", 24, None, EpisodeFormat::XOfY);
     assert_eq!(text, "12 of 24");
 }
 
+#[test]
+fn display_episode_label_strips_integer_padding_and_keeps_decimals() {
+    assert_eq!(display_episode_label("01"), "1");
+    assert_eq!(display_episode_label("1"), "1");
+    assert_eq!(display_episode_label("13.5"), "13.5");
+    assert_eq!(display_episode_label("013.50"), "13.50");
+    assert_eq!(display_episode_label("00"), "0");
+    assert_eq!(display_episode_label("SP1"), "SP1");
+}
+
+#[test]
+fn display_ani_id_collapses_to_short_prefix_unless_full() {
+    assert_eq!(display_ani_id("abcdef1234567890", false), "abcdef12…");
+    assert_eq!(display_ani_id("abcdef1234567890", true), "abcdef1234567890");
+    assert_eq!(display_ani_id("short", false), "short");
+}
+
+#[test]
+fn format_episode_progress_text_supports_all_presets() {
+    assert_eq!(
+        format_episode_progress_text("5", 12, None, EpisodeFormat::Slash),
+        "5/12"
+    );
+    assert_eq!(
+        format_episode_progress_text("5", 12, None, EpisodeFormat::Percent),
+        "42%"
+    );
+    assert_eq!(
+        format_episode_progress_text("5", 12, None, EpisodeFormat::SlashPercent),
+        "5/12 (42%)"
+    );
+    // Labels without a position still get a percentage when they are numeric.
+    assert_eq!(
+        format_episode_progress_text("6.5", 13, None, EpisodeFormat::SlashPercent),
+        "6.5/13 (50%)"
+    );
+}
+
+#[test]
+fn format_episode_progress_text_marks_unlisted_non_numeric_label_as_special() {
+    assert_eq!(
+        format_episode_progress_text("Special", 13, None, EpisodeFormat::XOfY),
+        "Special (special)"
+    );
+    assert!(build_progress_gauge("Special", 13, None).is_none());
+
+    let episodes = EpisodeList::new(vec!["1".to_string(), "2".to_string()]);
+    assert_eq!(
+        format_episode_progress_text("Movie", 2, Some(&episodes), EpisodeFormat::Slash),
+        "Movie (special)"
+    );
+    assert!(build_progress_gauge("Movie", 2, Some(&episodes)).is_none());
+}
+
+#[test]
+fn format_episode_progress_text_places_listed_special_by_ordinal() {
+    let episodes = EpisodeList::new(vec![
+        "1".to_string(),
+        "Special".to_string(),
+        "2".to_string(),
+    ]);
+    assert_eq!(
+        format_episode_progress_text("Special", 3, Some(&episodes), EpisodeFormat::XOfY),
+        "2 of 3 (episode Special)"
+    );
+    let (ratio, label) =
+        build_progress_gauge("Special", 3, Some(&episodes)).expect("listed special has a gauge");
+    assert_eq!(label, "2/3");
+    assert!((ratio - 2.0 / 3.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn format_episode_progress_text_displays_unpadded_label() {
+    assert_eq!(
+        format_episode_progress_text("01", 12, None, EpisodeFormat::XOfY),
+        "1 of 12"
+    );
+    assert_eq!(
+        format_episode_progress_text("013.5", 0, None, EpisodeFormat::XOfY),
+        "13.5 of 0"
+    );
+}
+
 #[test]
 fn replay_seed_episode_uses_previous_episode_from_list() {
     let episodes = vec![
@@ -380,10 +888,12 @@ fn replay_plan_uses_select_nth_for_episode_zero_fallback() {
         title: "Replay Zero Show (2 episodes)".to_string(),
         last_episode: "0".to_string(),
         last_seen_at: "2026-02-27T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-02-27T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
     };
     let episodes = vec!["0".to_string(), "1".to_string(), "2".to_string()];
 
-    let plan = build_replay_plan(&item, Some(&episodes), |_| Some(4));
+    let plan = build_replay_plan(&item, Some(&episodes), ReplayMode::Continue, |_| Some(4));
     assert_eq!(
         plan,
         ReplayPlan::Episode {
@@ -400,9 +910,11 @@ fn replay_plan_uses_continue_seed_when_available() {
         title: "Replay Normal Show (12 episodes)".to_string(),
         last_episode: "5".to_string(),
         last_seen_at: "2026-02-27T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-02-27T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
     };
 
-    let plan = build_replay_plan(&item, None, |_| Some(99));
+    let plan = build_replay_plan(&item, None, ReplayMode::Continue, |_| Some(99));
     assert_eq!(
         plan,
         ReplayPlan::Continue {
@@ -411,6 +923,61 @@ fn replay_plan_uses_continue_seed_when_available() {
     );
 }
 
+#[test]
+fn replay_plan_in_direct_mode_plays_the_current_episode() {
+    let item = render_entry("show-5", "Replay Normal Show (12 episodes)", "13.5");
+    let episodes = ["13", "13.5", "14"].map(String::from);
+
+    let mut resolved = false;
+    let plan = build_replay_plan(&item, Some(&episodes), ReplayMode::Direct, |_| {
+        resolved = true;
+        Some(7)
+    });
+    assert!(resolved, "direct mode resolves the search position");
+    assert_eq!(
+        plan,
+        ReplayPlan::Episode {
+            episode: "13.5".to_string(),
+            select_nth: Some(7),
+        }
+    );
+    // The same entry seeds the continue flow in the default mode.
+    assert_eq!(
+        build_replay_plan(&item, Some(&episodes), ReplayMode::Continue, |_| Some(7)),
+        ReplayPlan::Continue {
+            seed_episode: "13".to_string(),
+        }
+    );
+}
+
+#[test]
+fn replay_mode_from_env_parses_known_modes() {
+    assert_eq!(
+        replay_mode_from_env(None).expect("default"),
+        ReplayMode::Continue
+    );
+    assert_eq!(
+        replay_mode_from_env(Some(" Direct ".to_string())).expect("direct"),
+        ReplayMode::Direct
+    );
+    assert!(replay_mode_from_env(Some("again".to_string())).is_err());
+}
+
+#[test]
+fn sort_tie_break_from_env_parses_known_orders() {
+    assert_eq!(sort_tie_break_from_env(None).expect("unset"), None);
+    assert_eq!(
+        sort_tie_break_from_env(Some(" ".to_string())).expect("blank"),
+        None
+    );
+    assert_eq!(
+        sort_tie_break_from_env(Some(" title ".to_string())).expect("title"),
+        Some(SortTieBreak::Title)
+    );
+    let err = sort_tie_break_from_env(Some("rating".to_string())).expect_err("unknown order");
+    assert!(err.to_string().contains("ANI_TRACK_SORT_TIEBREAK"));
+}
+
 #[test]
 fn previous_target_episode_uses_episode_list_for_non_linear_numbering() {
     let episodes = vec![
@@ -503,6 +1070,207 @@ fn find_select_nth_index_by_title_matches_normalized_title() {
     );
 }
 
+#[test]
+fn select_search_plan_dedupes_queries_and_modes() {
+    let plan = select_search_plan("Frieren (28 episodes)", "dub");
+    assert_eq!(
+        plan,
+        vec![
+            ("Frieren".to_string(), "dub".to_string()),
+            ("Frieren".to_string(), "sub".to_string()),
+            ("Frieren (28 episodes)".to_string(), "dub".to_string()),
+            ("Frieren (28 episodes)".to_string(), "sub".to_string()),
+        ]
+    );
+    assert_eq!(select_search_plan("Frieren", " SUB ").len(), 2);
+}
+
+#[test]
+fn resolve_select_nth_with_reports_attempts_mode_and_budget() {
+    let item = crate::db::SeenEntry {
+        ani_id: "id-2".to_string(),
+        title: "Death Note".to_string(),
+        last_episode: "3".to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    let plan = select_search_plan(&item.title, "sub");
+    let results = |mode: &str| SearchEntriesFetchOutcome {
+        entries: (mode == "dub").then(|| {
+            vec![SearchResultEntry {
+                id: "id-2".to_string(),
+                title: "Death Note".to_string(),
+            }]
+        }),
+        warning: None,
+    };
+
+    let resolution = resolve_select_nth_with(
+        &item,
+        &plan,
+        std::time::Duration::from_secs(60),
+        |_, mode| results(mode),
+    );
+    assert_eq!(resolution.index, Some(1));
+    assert_eq!(resolution.attempts, 2);
+    assert_eq!(resolution.matched_mode.as_deref(), Some("dub"));
+    assert_eq!(resolution.matched_query.as_deref(), Some("Death Note"));
+    assert_eq!(
+        resolution.summary(),
+        "matched in dub results after 2 search attempt(s)"
+    );
+
+    let mut calls = 0;
+    let exhausted = resolve_select_nth_with(&item, &plan, std::time::Duration::ZERO, |_, _| {
+        calls += 1;
+        SearchEntriesFetchOutcome::default()
+    });
+    assert_eq!(calls, 1);
+    assert_eq!(exhausted.index, None);
+    assert_eq!(exhausted.summary(), "no match after 1 search attempt(s)");
+    assert!(exhausted.warnings[0].contains("time budget"));
+
+    // Positions past ani-cli's own list cannot be passed to `-S`, whatever the search limit.
+    let mut long_results = (1..=45)
+        .map(|n| SearchResultEntry {
+            id: format!("other-{n}"),
+            title: format!("Other {n}"),
+        })
+        .collect::<Vec<_>>();
+    long_results[44] = SearchResultEntry {
+        id: "id-2".to_string(),
+        title: "Death Note".to_string(),
+    };
+    let too_far = resolve_select_nth_with(
+        &item,
+        &plan,
+        std::time::Duration::from_secs(60),
+        |_, mode| SearchEntriesFetchOutcome {
+            entries: (mode == "sub").then(|| long_results.clone()),
+            warning: None,
+        },
+    );
+    assert_eq!(too_far.index, None);
+    assert!(
+        too_far.warnings[0].contains("position 45 of the sub results"),
+        "{:?}",
+        too_far.warnings
+    );
+
+    assert_eq!(
+        parse_select_budget("20"),
+        Some(std::time::Duration::from_secs(20))
+    );
+    assert_eq!(parse_select_budget("0"), None);
+    assert_eq!(parse_select_budget("soon"), None);
+
+    assert_eq!(parse_search_limit(" 60 "), Some(60));
+    assert_eq!(parse_search_limit("100"), Some(100));
+    assert_eq!(parse_search_limit("0"), None);
+    assert_eq!(parse_search_limit("101"), None);
+}
+
+#[test]
+fn revalidate_select_nth_with_catches_shifted_and_shrunk_results() {
+    let item = render_entry("id-2", "Death Note", "3");
+    let result = |id: &str, title: &str| SearchResultEntry {
+        id: id.to_string(),
+        title: title.to_string(),
+    };
+    let fetched = |entries: Vec<SearchResultEntry>| {
+        move |query: &str, mode: &str| {
+            assert_eq!((query, mode), ("Death Note", "sub"));
+            SearchEntriesFetchOutcome {
+                entries: Some(entries.clone()),
+                warning: None,
+            }
+        }
+    };
+    let by_id = SelectNthResolution {
+        index: Some(2),
+        matched_mode: Some("sub".to_string()),
+        matched_query: Some("Death Note".to_string()),
+        ..SelectNthResolution::default()
+    };
+
+    let unchanged = vec![
+        result("id-1", "Death Note Relight"),
+        result("id-2", "Death Note"),
+    ];
+    assert_eq!(
+        revalidate_select_nth_with(&item, &by_id, fetched(unchanged.clone())),
+        SelectNthCheck::Valid
+    );
+
+    let shifted = vec![
+        result("id-2", "Death Note"),
+        result("id-1", "Death Note Relight"),
+    ];
+    let SelectNthCheck::Stale(reason) = revalidate_select_nth_with(&item, &by_id, fetched(shifted))
+    else {
+        panic!("a shifted result should be stale");
+    };
+    assert!(reason.contains("\"Death Note Relight\""), "{reason}");
+
+    let shrunk = vec![result("id-2", "Death Note")];
+    let SelectNthCheck::Stale(reason) = revalidate_select_nth_with(&item, &by_id, fetched(shrunk))
+    else {
+        panic!("an index past the results should be stale");
+    };
+    assert!(reason.contains("fewer than position 2"), "{reason}");
+
+    // A title match is re-checked against the title that was confirmed.
+    let by_title = SelectNthResolution {
+        title_match: Some("Death Note".to_string()),
+        ..by_id.clone()
+    };
+    let renumbered = vec![
+        result("id-1", "Death Note Relight"),
+        result("id-9", "Death Note"),
+    ];
+    assert_eq!(
+        revalidate_select_nth_with(&item, &by_title, fetched(renumbered)),
+        SelectNthCheck::Valid
+    );
+
+    let failed = revalidate_select_nth_with(&item, &by_id, |_, _| SearchEntriesFetchOutcome {
+        entries: None,
+        warning: Some("show search request failed".to_string()),
+    });
+    assert_eq!(
+        failed,
+        SelectNthCheck::Unverified("show search request failed".to_string())
+    );
+
+    // An index that did not come from a search (e.g. a test override) is left alone.
+    let mut calls = 0;
+    let unsearched = SelectNthResolution {
+        index: Some(3),
+        ..SelectNthResolution::default()
+    };
+    assert_eq!(
+        revalidate_select_nth_with(&item, &unsearched, |_, _| {
+            calls += 1;
+            SearchEntriesFetchOutcome::default()
+        }),
+        SelectNthCheck::Valid
+    );
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn journal_permission_denied_recognizes_journalctl_hints() {
+    assert!(journal_permission_denied(
+        "Hint: You are currently not seeing messages from other users and the system.\n      Users in groups 'adm', 'systemd-journal', 'wheel' can see all messages."
+    ));
+    assert!(journal_permission_denied(
+        "No journal files were opened due to insufficient permissions."
+    ));
+    assert!(!journal_permission_denied("-- No entries --"));
+    assert!(!journal_permission_denied(""));
+}
+
 #[test]
 fn json_escape_handles_quotes_backslashes_and_controls() {
     let escaped = json_escape("A\"B\\C\n");
@@ -525,6 +1293,99 @@ fn tui_action_horizontal_navigation_respects_edges() {
     assert_eq!(TuiAction::Select.move_left(), TuiAction::Previous);
 }
 
+#[test]
+fn episode_picker_starts_after_stored_episode_and_filters_by_substring() {
+    let item = crate::db::SeenEntry {
+        ani_id: "show-1".to_string(),
+        title: "Show One".to_string(),
+        last_episode: "2".to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    let episodes = EpisodeList::new(["1", "2", "3", "12", "13.5"].map(str::to_string).to_vec());
+
+    let mut picker = EpisodePicker::new(&item, &episodes);
+    assert_eq!(picker.selected_episode(), Some("3"));
+    assert!(picker.is_watched(1, "2"));
+    assert!(!picker.is_watched(2, "3"));
+
+    picker.query = "1".to_string();
+    picker.selected = 2;
+    assert_eq!(picker.filtered(), vec!["1", "12", "13.5"]);
+    assert_eq!(picker.selected_episode(), Some("13.5"));
+
+    picker.query = "99".to_string();
+    assert_eq!(picker.selected_episode(), None);
+}
+
+#[test]
+fn episode_picker_query_prefers_exact_match_then_keeps_selection() {
+    let item = crate::db::SeenEntry {
+        ani_id: "show-1".to_string(),
+        title: "Long Show".to_string(),
+        last_episode: "0".to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    let episodes = EpisodeList::new((1..=320).map(|ep| format!("{ep:03}")).collect());
+    let mut picker = EpisodePicker::new(&item, &episodes);
+
+    picker.set_query("1".to_string());
+    assert_eq!(picker.selected_episode(), Some("001"));
+    picker.set_query("15".to_string());
+    assert_eq!(picker.selected_episode(), Some("015"));
+    assert_eq!(
+        picker.filtered(),
+        vec![
+            "015", "115", "150", "151", "152", "153", "154", "155", "156", "157", "158", "159",
+            "215", "315"
+        ]
+    );
+
+    picker.set_query("1551".to_string());
+    assert_eq!(picker.selected_episode(), None);
+    picker.set_query("155".to_string());
+    assert_eq!(picker.selected_episode(), Some("155"));
+    picker.set_query(String::new());
+    assert_eq!(picker.selected_episode(), Some("155"));
+}
+
+#[test]
+fn movies_are_detected_and_never_have_a_next_episode() {
+    let entry = |title: &str, last_episode: &str, kind| crate::db::SeenEntry {
+        ani_id: "movie-1".to_string(),
+        title: title.to_string(),
+        last_episode: last_episode.to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        kind,
+        ..crate::db::SeenEntry::default()
+    };
+    let series = crate::db::EntryKind::Series;
+    let movie = crate::db::EntryKind::Movie;
+
+    assert!(is_movie(&entry("Your Name (1 episodes)", "1", series)));
+    assert!(is_movie(&entry("Your Name (1 episodes)", "0", series)));
+    assert!(!is_movie(&entry("Special Cut (1 episodes)", "1.5", series)));
+    assert!(is_movie(&entry("Perfect Blue", "1", movie)));
+    assert!(!is_movie(&entry("Show (12 episodes)", "1", series)));
+
+    assert!(!entry_has_next_episode(
+        &entry("Perfect Blue", "1", movie),
+        None
+    ));
+    assert!(!entry_has_next_episode(
+        &entry("Your Name (1 episodes)", "0", series),
+        None
+    ));
+    assert!(entry_has_next_episode(
+        &entry("Show (12 episodes)", "1", series),
+        None
+    ));
+}
+
 #[test]
 fn has_next_episode_uses_episode_list_for_non_linear_numbering() {
     let mut episodes = vec!["0".to_string()];
@@ -540,6 +1401,33 @@ fn has_next_episode_uses_episode_list_for_non_linear_numbering() {
     assert!(has_next_episode("24", Some(27), Some(&episodes)));
 }
 
+#[test]
+fn restart_progress_sits_before_the_first_listed_episode() {
+    let from_one = ["1", "2", "3"].map(String::from);
+    let from_zero = ["0", "1", "2"].map(String::from);
+
+    assert_eq!(restart_episode(Some(&from_one)), "0");
+    assert_eq!(restart_episode(Some(&from_zero)), "-1");
+    assert_eq!(restart_episode(None), "0");
+
+    assert_eq!(
+        first_episode_after("0", Some(&from_one)).as_deref(),
+        Some("1")
+    );
+    assert_eq!(
+        first_episode_after("-1", Some(&from_zero)).as_deref(),
+        Some("0")
+    );
+    assert_eq!(first_episode_after("0", None).as_deref(), Some("1"));
+    // Progress on a listed episode continues normally.
+    assert_eq!(first_episode_after("0", Some(&from_zero)), None);
+    assert_eq!(first_episode_after("2", Some(&from_one)), None);
+    assert_eq!(first_episode_after("2", None), None);
+
+    assert!(has_next_episode("0", Some(3), Some(&from_one)));
+    assert!(has_next_episode("-1", Some(3), Some(&from_zero)));
+}
+
 #[test]
 fn has_next_episode_falls_back_to_numeric_when_list_missing() {
     assert!(has_next_episode("25", Some(27), None));
@@ -547,10 +1435,33 @@ fn has_next_episode_falls_back_to_numeric_when_list_missing() {
 }
 
 #[test]
-fn format_last_seen_display_parses_rfc3339_timestamp() {
-    let raw = "2026-02-25T18:27:06.100701256+00:00";
-    let formatted = format_last_seen_display(raw);
-    let expected = DateTime::parse_from_rfc3339(raw)
+fn has_next_episode_without_list_rejects_decimal_labels() {
+    assert!(!has_next_episode("13.5", Some(25), None));
+    assert!(has_next_episode("5", Some(12), None));
+    // Episode 0 is a plain integer: episode 1 follows it.
+    assert!(has_next_episode("0", Some(1), None));
+    assert!(has_next_episode("0", Some(12), None));
+}
+
+#[test]
+fn restarted_show_without_episode_list_still_has_a_next_episode() {
+    let mut item = render_entry("show-1", "Show One (12 episodes)", "7");
+    item.last_episode = restart_episode(None);
+
+    assert!(entry_has_next_episode(&item, None));
+    assert_eq!(next_episode_label(&item, None).as_deref(), Some("1"));
+    assert!(
+        list_summary(std::slice::from_ref(&item)).starts_with("1 entry, 0 caught up"),
+        "{}",
+        list_summary(std::slice::from_ref(&item))
+    );
+}
+
+#[test]
+fn format_last_seen_display_parses_rfc3339_timestamp() {
+    let raw = "2026-02-25T18:27:06.100701256+00:00";
+    let formatted = format_last_seen_display(raw);
+    let expected = DateTime::parse_from_rfc3339(raw)
         .expect("timestamp should parse")
         .with_timezone(&Local)
         .format("%Y-%m-%d %H:%M %:z")
@@ -559,27 +1470,1342 @@ fn format_last_seen_display_parses_rfc3339_timestamp() {
 }
 
 #[test]
-fn format_last_seen_display_tui_parses_rfc3339_timestamp_without_offset() {
-    let raw = "2026-02-25T18:27:06.100701256+00:00";
-    let formatted = format_last_seen_display_tui(raw);
-    let expected = DateTime::parse_from_rfc3339(raw)
-        .expect("timestamp should parse")
-        .with_timezone(&Local)
-        .format("%Y-%m-%d %H:%M")
-        .to_string();
-    assert_eq!(formatted, expected);
+fn format_last_seen_display_tui_parses_rfc3339_timestamp_without_offset() {
+    let raw = "2026-02-25T18:27:06.100701256+00:00";
+    let formatted = format_last_seen_display_tui(raw);
+    let expected = DateTime::parse_from_rfc3339(raw)
+        .expect("timestamp should parse")
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string();
+    assert_eq!(formatted, expected);
+}
+
+#[test]
+fn format_last_seen_display_keeps_raw_when_invalid() {
+    let raw = "not-a-timestamp";
+    assert_eq!(format_last_seen_display(raw), raw);
+}
+
+#[test]
+fn format_last_seen_display_tui_keeps_raw_when_invalid() {
+    let raw = "not-a-timestamp";
+    assert_eq!(format_last_seen_display_tui(raw), raw);
+}
+
+#[test]
+fn tui_theme_defaults_and_accepts_overrides() {
+    assert_eq!(
+        TuiTheme::from_values(None, Some(String::new()), None).expect("defaults should apply"),
+        TuiTheme::default()
+    );
+
+    let theme = TuiTheme::from_values(
+        Some("> ".to_string()),
+        Some("white".to_string()),
+        Some("#336699".to_string()),
+    )
+    .expect("overrides should parse");
+    assert_eq!(theme.highlight_symbol, "> ");
+    assert_eq!(theme.highlight_fg, ratatui::style::Color::White);
+    assert_eq!(
+        theme.highlight_bg,
+        ratatui::style::Color::Rgb(0x33, 0x66, 0x99)
+    );
+
+    let err = TuiTheme::from_values(None, None, Some("not-a-color".to_string()))
+        .expect_err("invalid color should be rejected");
+    assert!(err.to_string().contains("ANI_TRACK_HIGHLIGHT_BG"));
+}
+
+#[test]
+fn popup_rect_for_text_sizes_wide_characters_by_display_width() {
+    let area = ratatui::layout::Rect::new(0, 0, 120, 40);
+    let title = "進撃の巨人進撃の巨人進撃の巨人進撃の巨人進撃の巨人進撃の巨人";
+    let text = format!("Delete tracked entry?\n\n{title}\n\nPress y to confirm.");
+
+    let rect = popup_rect_for_text(area, &text);
+    // 30 wide characters need 60 columns plus the popup borders.
+    assert!(rect.width >= 62, "popup too narrow: {}", rect.width);
+
+    let ascii_rect = popup_rect_for_text(area, "Short notice");
+    assert_eq!(ascii_rect.width, 48);
+}
+
+#[test]
+fn last_watched_index_ignores_the_list_order() {
+    let mut rated = render_entry("show-1", "Frieren", "7");
+    rated.last_seen_at = "2026-03-01T00:00:00+00:00".to_string();
+    let mut latest = render_entry("show-2", "Dandadan", "3");
+    latest.last_seen_at = "2026-03-05T00:00:00+00:00".to_string();
+    let mut older = render_entry("show-3", "Suzume", "1");
+    older.last_seen_at = "2026-02-01T00:00:00+00:00".to_string();
+
+    assert_eq!(last_watched_index(&[rated, latest, older]), Some(1));
+    assert_eq!(last_watched_index(&[]), None);
+}
+
+#[test]
+fn next_unwatched_index_skips_caught_up_shows_and_wraps() {
+    let entry = |ani_id: &str, title: &str, last_episode: &str| crate::db::SeenEntry {
+        ani_id: ani_id.to_string(),
+        title: title.to_string(),
+        last_episode: last_episode.to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    let items = vec![
+        entry("a", "Alpha (12 episodes)", "3"),
+        entry("b", "Beta (12 episodes)", "12"),
+        entry("c", "Gamma", "5"),
+    ];
+    let gamma_list = vec!["4".to_string(), "5".to_string()];
+    let lists = |item: &crate::db::SeenEntry| (item.ani_id == "c").then_some(gamma_list.as_slice());
+
+    assert_eq!(next_unwatched_index(&items, Some(0), lists), Some(0));
+    assert_eq!(next_unwatched_index(&items, Some(1), lists), Some(0));
+    assert_eq!(next_unwatched_index(&items, None, lists), Some(0));
+
+    let caught_up = vec![entry("b", "Beta (12 episodes)", "12")];
+    assert_eq!(next_unwatched_index(&caught_up, Some(0), |_| None), None);
+    assert_eq!(next_unwatched_index(&[], None, |_| None), None);
+}
+
+fn buffer_lines(buffer: &ratatui::buffer::Buffer) -> Vec<String> {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn render_entry(ani_id: &str, title: &str, last_episode: &str) -> crate::db::SeenEntry {
+    crate::db::SeenEntry {
+        ani_id: ani_id.to_string(),
+        title: title.to_string(),
+        last_episode: last_episode.to_string(),
+        last_seen_at: "2026-01-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-01-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    }
+}
+
+fn render_options() -> TuiOptions {
+    TuiOptions {
+        prefetch_concurrency: 0,
+        episode_format: Default::default(),
+        title_case: false,
+        plain: true,
+        state_file: None,
+        confirm_previous: Default::default(),
+        wrap_selected_title: false,
+        advance_selection_after_play: false,
+        show_episode_ordinal: false,
+        mpv_watch_later_dir: None,
+        columns: LibraryColumn::ALL.to_vec(),
+        wal_checkpoint_interval: None,
+        stale_after: None,
+        sort_tie_break: None,
+        confirm_quit: false,
+    }
+}
+
+#[test]
+fn draw_tui_renders_rows_selection_and_progress_gauge() {
+    let items = vec![
+        render_entry("show-1", "Frieren (28 episodes)", "7"),
+        render_entry("show-2", "Dandadan (12 episodes)", "12"),
+    ];
+    let buffer = render_main_view(&items, Some(0), "INFO: ready", &render_options(), (120, 30))
+        .expect("render should succeed");
+    let lines = buffer_lines(&buffer);
+
+    assert!(lines[1].contains("2 entries   selected 1   NEXT"));
+    // Library table: header row, then one row per item with the selection marker on the first.
+    assert!(lines[4].starts_with("│  Title"));
+    assert!(lines[5].starts_with("│▸ Frieren"));
+    assert!(lines[5].contains("28         7"));
+    assert!(lines[6].starts_with("│  Dandadan"));
+    // Selected panel sits to the right of the table.
+    let panel = |y: usize| lines[y].split("││").nth(1).unwrap_or_default();
+    assert!(panel(4).starts_with("Title"));
+    assert!(panel(5).starts_with("Frieren"));
+    assert!(panel(8).starts_with("7 of 28"));
+    // The progress gauge is the last panel above the controls.
+    // Without a fetched episode list the gauge says it is only an estimate.
+    assert!(lines[21].contains("╭Progress (numeric estimate)"));
+    assert!(lines[22].contains("7/28"));
+    assert!(lines[25].contains(" NEXT "));
+    assert!(lines[28].starts_with("│INFO: ready"));
+}
+
+#[test]
+fn draw_tui_renders_only_configured_columns_and_widens_titles() {
+    let title = "A Very Long Title That Does Not Fit Beside Every Column (24 episodes)";
+    let items = vec![render_entry("show-1", title, "7")];
+    let mut options = render_options();
+    options.wrap_selected_title = false;
+    let full = buffer_lines(
+        &render_main_view(&items, Some(0), "", &options, (120, 30)).expect("render should succeed"),
+    );
+    assert!(full[4].contains("Last Seen"), "{}", full[4]);
+
+    options.columns = vec![LibraryColumn::LastEp, LibraryColumn::Title];
+    let lines = buffer_lines(
+        &render_main_view(&items, Some(0), "", &options, (120, 30)).expect("render should succeed"),
+    );
+    let table = |y: usize| lines[y].split("││").next().unwrap_or_default().to_string();
+    assert!(table(4).starts_with("│  Last Ep    Title"), "{}", table(4));
+    assert!(!table(4).contains("Last Seen"));
+    assert!(!table(4).contains("Total Eps"));
+    assert!(
+        table(5).starts_with("│▸ 7          A Very Long"),
+        "{}",
+        table(5)
+    );
+    assert!(
+        table(5).contains("Beside Every"),
+        "freed width should go to the title: {}",
+        table(5)
+    );
+    assert!(!full[5].contains("Beside Every"), "{}", full[5]);
+}
+
+#[test]
+fn wal_checkpoint_interval_from_env_defaults_and_disables() {
+    assert_eq!(
+        wal_checkpoint_interval_from_env(None).expect("default"),
+        Some(std::time::Duration::from_secs(300))
+    );
+    assert_eq!(
+        wal_checkpoint_interval_from_env(Some(" 60 ".to_string())).expect("custom"),
+        Some(std::time::Duration::from_secs(60))
+    );
+    assert_eq!(
+        wal_checkpoint_interval_from_env(Some("0".to_string())).expect("disabled"),
+        None
+    );
+    let err = wal_checkpoint_interval_from_env(Some("5m".to_string()))
+        .expect_err("units are not accepted");
+    assert!(
+        err.to_string()
+            .contains("ANI_TRACK_WAL_CHECKPOINT_SECS `5m`")
+    );
+}
+
+#[test]
+fn library_columns_from_env_requires_the_title_column() {
+    assert_eq!(
+        library_columns_from_env(None).expect("default"),
+        LibraryColumn::ALL
+    );
+    assert_eq!(
+        library_columns_from_env(Some(" ".to_string())).expect("blank"),
+        LibraryColumn::ALL
+    );
+    assert_eq!(
+        library_columns_from_env(Some("Title, last_ep,".to_string())).expect("subset"),
+        [LibraryColumn::Title, LibraryColumn::LastEp]
+    );
+    let err = library_columns_from_env(Some("total,last_seen".to_string()))
+        .expect_err("title is required");
+    assert!(err.to_string().contains("must include title"), "{err}");
+    let err =
+        library_columns_from_env(Some("title,status".to_string())).expect_err("unknown column");
+    assert!(err.to_string().contains("`status`"), "{err}");
+    let err = library_columns_from_env(Some("title,rating,rating".to_string()))
+        .expect_err("duplicate column");
+    assert!(err.to_string().contains("more than once"), "{err}");
+}
+
+#[test]
+fn draw_tui_gauge_shows_watched_episode_coverage_when_tracked() {
+    let mut item = render_entry("show-1", "Dandadan (12 episodes)", "5");
+    item.watched_episodes = Some(["1", "3", "5"].map(String::from).to_vec());
+    let buffer = render_main_view(&[item], Some(0), "", &render_options(), (120, 30))
+        .expect("render should succeed");
+    let lines = buffer_lines(&buffer);
+    assert!(
+        lines[21].contains("╭Progress (watched episodes)"),
+        "{}",
+        lines[21]
+    );
+    assert!(lines[22].contains("3/12 watched"), "{}", lines[22]);
+
+    assert_eq!(build_coverage_gauge(&[], 12), None);
+    let all = (1..=14).map(|ep| ep.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        build_coverage_gauge(&all, 12),
+        Some((1.0, "12/12 watched".to_string()))
+    );
+}
+
+#[test]
+fn progress_source_reports_whether_the_episode_list_placed_the_episode() {
+    let list = EpisodeList::new(vec!["1".to_string(), "2".to_string(), "5".to_string()]);
+    assert_eq!(
+        ProgressSource::of("5", Some(&list)),
+        ProgressSource::EpisodeList
+    );
+    assert_eq!(
+        ProgressSource::of("3", Some(&list)),
+        ProgressSource::NumericEstimate
+    );
+    assert_eq!(
+        ProgressSource::of("5", None),
+        ProgressSource::NumericEstimate
+    );
+}
+
+#[test]
+fn stale_entry_ids_skip_recent_finished_and_unparseable_entries() {
+    let now = DateTime::parse_from_rfc3339("2026-03-31T00:00:00+00:00")
+        .expect("timestamp should parse")
+        .with_timezone(&chrono::Utc);
+    let entry = |id: &str, last_seen_at: &str| {
+        let mut item = render_entry(id, id, "1");
+        item.last_seen_at = last_seen_at.to_string();
+        item
+    };
+    let items = vec![
+        entry("old", "2026-02-01T00:00:00+00:00"),
+        entry("recent", "2026-03-20T00:00:00+00:00"),
+        entry("done", "2025-12-01T00:00:00+00:00"),
+        entry("garbled", "yesterday"),
+    ];
+    let finished = ["1".to_string()];
+    let episode_list_for =
+        |item: &crate::db::SeenEntry| (item.ani_id == "done").then_some(&finished[..]);
+    let month = Some(std::time::Duration::from_secs(30 * 24 * 60 * 60));
+
+    let stale = stale_entry_ids(&items, month, now, episode_list_for);
+    assert_eq!(stale, ["old".to_string()].into_iter().collect());
+    assert!(stale_entry_ids(&items, None, now, episode_list_for).is_empty());
+}
+
+#[test]
+fn quit_asks_only_when_enabled_and_playback_runs_in_the_background() {
+    assert!(!quit_needs_confirmation(false, false));
+    assert!(!quit_needs_confirmation(false, true));
+    assert!(!quit_needs_confirmation(true, false));
+    assert!(quit_needs_confirmation(true, true));
+}
+
+#[test]
+fn stale_after_from_env_uses_the_age_syntax() {
+    assert_eq!(stale_after_from_env(None).expect("unset"), None);
+    assert_eq!(
+        stale_after_from_env(Some("0".to_string())).expect("zero"),
+        None
+    );
+    assert_eq!(
+        stale_after_from_env(Some("30d".to_string())).expect("days"),
+        Some(std::time::Duration::from_secs(30 * 24 * 60 * 60))
+    );
+    assert_eq!(
+        stale_after_from_env(Some("6w".to_string())).expect("weeks"),
+        Some(std::time::Duration::from_secs(6 * 7 * 24 * 60 * 60))
+    );
+    let err = stale_after_from_env(Some("a month".to_string())).expect_err("invalid age");
+    assert!(
+        err.to_string()
+            .starts_with("invalid ANI_TRACK_STALE_AFTER: "),
+        "{err}"
+    );
+}
+
+#[test]
+fn quick_find_index_matches_title_prefixes_from_the_start_row() {
+    let items = vec![
+        render_entry("a", "Frieren (28 episodes)", "1"),
+        render_entry("b", "Dandadan", "1"),
+        render_entry("c", "Fate/Zero", "1"),
+        render_entry("d", "fire force", "1"),
+    ];
+    assert_eq!(quick_find_index(&items, "f", 1), Some(2));
+    assert_eq!(quick_find_index(&items, "F", 3), Some(3));
+    // Wraps past the last row.
+    assert_eq!(quick_find_index(&items, "f", 4), Some(0));
+    assert_eq!(quick_find_index(&items, "fri", 0), Some(0));
+    assert_eq!(quick_find_index(&items, "fir", 0), Some(3));
+    // Punctuation and case are ignored on both sides.
+    assert_eq!(quick_find_index(&items, "fate z", 0), Some(2));
+    assert_eq!(quick_find_index(&items, "FATE/ze", 0), Some(2));
+    assert_eq!(quick_find_index(&items, "x", 0), None);
+    assert_eq!(quick_find_index(&items, "", 0), None);
+    assert_eq!(quick_find_index(&[], "f", 0), None);
+}
+
+#[test]
+fn timeline_rows_list_entries_newest_first_with_relative_days() {
+    let now = DateTime::parse_from_rfc3339("2026-03-12T20:00:00+00:00")
+        .expect("timestamp should parse")
+        .with_timezone(&chrono::Utc);
+    let entry = |id: &str, last_seen_at: &str| {
+        let mut item = render_entry(id, id, "1");
+        item.last_seen_at = last_seen_at.to_string();
+        item
+    };
+    let items = vec![
+        entry("older", "2026-02-20T08:00:00+00:00"),
+        entry("garbled", "not a time"),
+        entry("today", "2026-03-12T18:05:00+00:00"),
+        entry("weekday", "2026-03-09T21:14:00+00:00"),
+        // Another offset is shown in `now`'s timezone: 23:30 UTC on the 11th.
+        entry("yesterday", "2026-03-12T01:30:00+02:00"),
+    ];
+
+    let rows = timeline_rows(&items, &now)
+        .into_iter()
+        .map(|(when, item)| (when, item.ani_id.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            ("today 18:05".to_string(), "today"),
+            ("yesterday 23:30".to_string(), "yesterday"),
+            ("Mon 21:14".to_string(), "weekday"),
+            ("2026-02-20 08:00".to_string(), "older"),
+            ("not a time".to_string(), "garbled"),
+        ]
+    );
+    assert!(timeline_rows(&[], &now).is_empty());
+}
+
+#[test]
+fn controls_hints_only_list_keys_the_current_prompt_accepts() {
+    assert!(hint_text(HintMode::Main).contains("d delete"));
+    assert!(hint_text(HintMode::Main).ends_with("q quit"));
+    assert_eq!(
+        hint_text(HintMode::Confirm),
+        "y/Enter confirm  n/Esc cancel"
+    );
+    assert_eq!(hint_text(HintMode::Notice), "any key close");
+    assert!(hint_text(HintMode::Main).contains("w timeline"));
+    assert!(hint_text(HintMode::Timeline).contains("w/Esc back to library"));
+    for mode in [
+        HintMode::Confirm,
+        HintMode::ConfirmPlay,
+        HintMode::Picker,
+        HintMode::TextPrompt,
+        HintMode::RatingPrompt,
+        HintMode::Jump,
+        HintMode::Find,
+    ] {
+        let hint = hint_text(mode);
+        assert!(hint.contains("Esc cancel"), "{mode:?}: {hint}");
+        assert!(!hint.contains("q quit"), "{mode:?}: {hint}");
+    }
+}
+
+#[test]
+fn draw_tui_shows_continue_card_for_the_last_watched_show_with_a_next_episode() {
+    let mut latest = render_entry("show-2", "Dandadan (12 episodes)", "4");
+    latest.last_seen_at = "2026-02-01T00:00:00+00:00".to_string();
+    let items = vec![render_entry("show-1", "Frieren (28 episodes)", "7"), latest];
+    let buffer = render_main_view(&items, Some(0), "", &render_options(), (120, 30))
+        .expect("render should succeed");
+    let lines = buffer_lines(&buffer);
+    assert!(
+        lines[1].contains("continue: Dandadan · ep 5 [c]"),
+        "{}",
+        lines[1]
+    );
+
+    // Caught up on the last watched show: no card.
+    let mut caught_up = items.clone();
+    caught_up[1].last_episode = "12".to_string();
+    let buffer = render_main_view(&caught_up, Some(0), "", &render_options(), (120, 30))
+        .expect("render should succeed");
+    assert!(!buffer_lines(&buffer)[1].contains("continue:"));
+}
+
+#[test]
+fn draw_tui_shows_external_link_in_selected_panel() {
+    let mut item = render_entry("show-1", "Frieren (28 episodes)", "7");
+    let buffer = render_main_view(&[item.clone()], Some(0), "", &render_options(), (120, 30))
+        .expect("render should succeed");
+    assert!(!buffer_lines(&buffer).join("\n").contains("Linked"));
+
+    item.external_provider = Some("anilist".to_string());
+    item.external_id = Some("154587".to_string());
+    let buffer = render_main_view(&[item], Some(0), "", &render_options(), (120, 30))
+        .expect("render should succeed");
+    let rendered = buffer_lines(&buffer).join("\n");
+    assert!(rendered.contains("Linked"), "{rendered}");
+    assert!(rendered.contains("AniList 154587"), "{rendered}");
+}
+
+#[test]
+fn episode_column_text_switches_between_last_and_next_episode() {
+    let item = render_entry("show-1", "Show (12 episodes)", "3");
+    let episodes = EpisodeList::new(["1", "2", "3", "3.5", "4"].map(String::from).to_vec());
+    assert_eq!(
+        episode_column_text(&item, Some(&episodes), false, false),
+        "3"
+    );
+    assert_eq!(
+        episode_column_text(&item, Some(&episodes), true, false),
+        "3.5"
+    );
+    assert_eq!(episode_column_text(&item, None, true, false), "4");
+
+    let finished = render_entry("show-1", "Show (12 episodes)", "12");
+    assert_eq!(episode_column_text(&finished, None, false, false), "12");
+    assert_eq!(episode_column_text(&finished, None, true, false), "done");
+    // Without a total or a list, a non-numeric episode has no nameable successor.
+    let special = render_entry("show-1", "Show", "Special");
+    assert_eq!(episode_column_text(&special, None, true, false), "-");
+}
+
+#[test]
+fn episode_column_text_appends_the_list_ordinal_when_enabled() {
+    let item = render_entry("show-1", "Show (5 episodes)", "4");
+    let episodes = EpisodeList::new(["1", "2", "2.5", "3", "4"].map(String::from).to_vec());
+    assert_eq!(
+        episode_column_text(&item, Some(&episodes), false, false),
+        "4"
+    );
+    assert_eq!(
+        episode_column_text(&item, Some(&episodes), false, true),
+        "4 (5)"
+    );
+    // Labels that match their position, and rows without a fetched list, show the label alone.
+    let early = render_entry("show-1", "Show (5 episodes)", "2");
+    assert_eq!(
+        episode_column_text(&early, Some(&episodes), false, true),
+        "2"
+    );
+    assert_eq!(episode_column_text(&item, None, false, true), "4");
+}
+
+#[test]
+fn next_episode_label_follows_the_list_or_the_next_whole_number() {
+    let item = render_entry("show-1", "Show (12 episodes)", "3");
+    let episodes = ["1", "2", "3", "3.5", "4"].map(String::from);
+    assert_eq!(
+        next_episode_label(&item, Some(&episodes)).as_deref(),
+        Some("3.5")
+    );
+    assert_eq!(next_episode_label(&item, None).as_deref(), Some("4"));
+    let special = render_entry("show-1", "Show (12 episodes)", "3.5");
+    assert_eq!(next_episode_label(&special, None), None);
+    let finished = render_entry("show-1", "Show (12 episodes)", "12");
+    assert_eq!(next_episode_label(&finished, None), None);
+}
+
+#[test]
+fn draw_tui_shows_airing_badge_next_to_progress() {
+    let mut item = render_entry("show-1", "Frieren (28 episodes)", "7");
+    item.airing_status = Some("airing".to_string());
+    let buffer = render_main_view(&[item], Some(0), "", &render_options(), (120, 30))
+        .expect("render should succeed");
+    let lines = buffer_lines(&buffer);
+    let panel = |y: usize| lines[y].split("││").nth(1).unwrap_or_default();
+    assert!(panel(8).starts_with("7 of 28 · Airing"));
+}
+
+#[test]
+fn draw_tui_renders_empty_state_without_gauge() {
+    let buffer = render_main_view(&[], None, "", &render_options(), (120, 30))
+        .expect("render should succeed");
+    let lines = buffer_lines(&buffer);
+
+    assert!(lines[1].contains("0 entries   selected -"));
+    assert!(lines[4].contains("Title") && lines[4].contains("Last Seen"));
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("No tracked entries yet."))
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("Press s to run ani-cli search"))
+    );
+    assert!(!lines.iter().any(|line| line.contains("Progress")));
+}
+
+#[test]
+fn draw_tui_wraps_only_the_selected_title_when_enabled() {
+    let title = "Ascendance of a Bookworm Season 3 Part 2 Special Edition";
+    let items = vec![
+        render_entry("show-1", &format!("{title} (12 episodes)"), "4"),
+        render_entry("show-2", &format!("{title} (10 episodes)"), "2"),
+    ];
+    let options = TuiOptions {
+        wrap_selected_title: true,
+        ..render_options()
+    };
+    let buffer =
+        render_main_view(&items, Some(0), "", &options, (120, 30)).expect("render should succeed");
+    let lines = buffer_lines(&buffer);
+
+    // The selected row takes two lines and the next row starts below them, truncated.
+    assert!(lines[5].starts_with("│▸ Ascendance of a   12         4"));
+    assert!(lines[6].starts_with("│  Bookworm Seaso...  "));
+    assert!(lines[7].starts_with("│  Ascendance of ... 10         2"));
+}
+
+#[test]
+fn wrap_title_breaks_on_words_and_truncates_the_last_line() {
+    assert_eq!(wrap_title("Frieren", 20, 2), ["Frieren"]);
+    assert_eq!(
+        wrap_title("Attack on Titan Final Season", 16, 2),
+        ["Attack on Titan", "Final Season"]
+    );
+    assert_eq!(
+        wrap_title("Attack on Titan The Final Season Part 2", 16, 2),
+        ["Attack on Titan", "The Final Sea..."]
+    );
+    // A word longer than the line is split between characters, never mid-grapheme.
+    assert_eq!(
+        wrap_title("Supercalifragilistic", 8, 2),
+        ["Supercal", "ifrag..."]
+    );
+    assert_eq!(wrap_title("進撃の巨人 完結編", 6, 2), ["進撃の", "巨..."]);
+    assert!(wrap_title("anything", 0, 2).is_empty());
+}
+
+#[test]
+fn action_for_row_prefers_stored_default_over_global_action() {
+    let mut item = crate::db::SeenEntry {
+        ani_id: "a".to_string(),
+        title: "Anthology".to_string(),
+        last_episode: "3".to_string(),
+        last_seen_at: "2026-01-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-01-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    assert_eq!(
+        action_for_row(Some(&item), TuiAction::Replay),
+        TuiAction::Replay
+    );
+    item.default_action = Some(TuiAction::Select.as_str().to_string());
+    assert_eq!(
+        action_for_row(Some(&item), TuiAction::Replay),
+        TuiAction::Select
+    );
+    item.default_action = Some("sideways".to_string());
+    assert_eq!(
+        action_for_row(Some(&item), TuiAction::Next),
+        TuiAction::Next
+    );
+    assert_eq!(
+        action_for_row(None, TuiAction::Previous),
+        TuiAction::Previous
+    );
+
+    for action in [
+        TuiAction::Next,
+        TuiAction::Replay,
+        TuiAction::Previous,
+        TuiAction::Select,
+    ] {
+        assert_eq!(TuiAction::parse(action.as_str()), Some(action));
+    }
+}
+
+#[test]
+fn editor_argv_splits_arguments_and_rejects_unset_editor() {
+    assert_eq!(
+        editor_argv(Some("code --wait".to_string())).expect("editor should parse"),
+        vec!["code".to_string(), "--wait".to_string()]
+    );
+    assert_eq!(
+        editor_argv(Some(" nvim ".to_string())).expect("editor should parse"),
+        vec!["nvim".to_string()]
+    );
+    for unset in [None, Some(String::new()), Some("   ".to_string())] {
+        let err = editor_argv(unset).expect_err("unset editor should be rejected");
+        assert!(err.to_string().contains("$EDITOR is not set"));
+    }
+}
+
+#[test]
+fn rating_input_accepts_one_to_ten_and_empty_clears() {
+    assert_eq!(parse_rating_input("").expect("empty clears"), None);
+    assert_eq!(parse_rating_input("7").expect("valid rating"), Some(7));
+    assert_eq!(parse_rating_input("10").expect("valid rating"), Some(10));
+    assert!(parse_rating_input("0").is_err());
+    assert!(parse_rating_input("11").is_err());
+
+    assert_eq!(adjust_rating_input("", 1), "1");
+    assert_eq!(adjust_rating_input("", -1), "10");
+    assert_eq!(adjust_rating_input("5", 1), "6");
+    assert_eq!(adjust_rating_input("10", 1), "10");
+    assert_eq!(adjust_rating_input("1", -1), "1");
+    assert_eq!(adjust_rating_input("42", -1), "10");
+}
+
+#[test]
+fn selection_after_play_moves_to_the_following_row_only_when_enabled() {
+    let items = vec![
+        render_entry("show-1", "One", "1"),
+        render_entry("show-2", "Two", "1"),
+        render_entry("show-3", "Three", "1"),
+    ];
+    assert_eq!(selection_after_play(&items, 0, true), Some("show-2"));
+    assert_eq!(selection_after_play(&items, 1, true), Some("show-3"));
+    // The last row has nothing after it, so the played show stays selected.
+    assert_eq!(selection_after_play(&items, 2, true), None);
+    assert_eq!(selection_after_play(&items, 0, false), None);
+}
+
+#[test]
+fn jump_row_index_clamps_one_based_input() {
+    assert_eq!(jump_row_index("1", 5), Some(0));
+    assert_eq!(jump_row_index("3", 5), Some(2));
+    assert_eq!(jump_row_index("0", 5), Some(0));
+    assert_eq!(jump_row_index("42", 5), Some(4));
+    assert_eq!(jump_row_index("99999999999999999999999", 5), Some(4));
+    assert_eq!(jump_row_index("", 5), None);
+    assert_eq!(jump_row_index("3", 0), None);
+}
+
+#[test]
+fn tui_ui_state_round_trips_and_keeps_unknown_fields() {
+    let state = TuiUiState::parse(
+        r#"{"selected_ani_id":"show-1","sort":"added","tie_break":"title","future":[1,2]}"#,
+    );
+    assert_eq!(state.selected_ani_id.as_deref(), Some("show-1"));
+    assert_eq!(state.sort, Some(ListSort::Added));
+    assert_eq!(state.tie_break, Some(SortTieBreak::Title));
+
+    let reparsed: serde_json::Value =
+        serde_json::from_str(&state.to_json()).expect("state should serialize to JSON");
+    assert_eq!(reparsed["selected_ani_id"], "show-1");
+    assert_eq!(reparsed["sort"], "added");
+    assert_eq!(reparsed["tie_break"], "title");
+    assert_eq!(reparsed["future"], serde_json::json!([1, 2]));
+    assert_eq!(TuiUiState::parse(&state.to_json()), state);
+
+    assert_eq!(TuiUiState::parse("not json"), TuiUiState::default());
+    assert_eq!(
+        TuiUiState::parse(r#"{"selected_ani_id":42}"#).selected_ani_id,
+        None
+    );
+    // An order this build does not know falls back to the default.
+    assert_eq!(TuiUiState::parse(r#"{"sort":"reverse"}"#).sort, None);
+}
+
+#[test]
+fn previous_crossing_note_only_flags_configured_boundaries() {
+    use super::episode::PreviousConfirm;
+
+    assert_eq!(
+        previous_crossing_note("1", "0", PreviousConfirm::Zero).as_deref(),
+        Some("episode 0 (prologue)")
+    );
+    assert_eq!(
+        previous_crossing_note("1", "0", PreviousConfirm::Never),
+        None
+    );
+    assert_eq!(
+        previous_crossing_note("5", "4", PreviousConfirm::Special),
+        None
+    );
+    assert_eq!(
+        previous_crossing_note("13.5", "13", PreviousConfirm::Special),
+        None
+    );
+    assert_eq!(
+        previous_crossing_note("13", "12.5", PreviousConfirm::Zero),
+        None
+    );
+    assert_eq!(
+        previous_crossing_note("13", "12.5", PreviousConfirm::Special).as_deref(),
+        Some("episode 12.5 (special)")
+    );
+    assert_eq!(
+        previous_crossing_note("01", "SP1", PreviousConfirm::Special).as_deref(),
+        Some("episode SP1 (special)")
+    );
+
+    assert_eq!(
+        previous_confirm_from_env(None).expect("unset should default"),
+        PreviousConfirm::Zero
+    );
+    assert_eq!(
+        previous_confirm_from_env(Some("Special".to_string())).expect("value should parse"),
+        PreviousConfirm::Special
+    );
+    assert!(previous_confirm_from_env(Some("always".to_string())).is_err());
+}
+
+#[test]
+fn tui_previous_asks_only_when_crossing_into_episode_zero() {
+    use super::episode::PreviousConfirm;
+
+    let episodes = ["0", "1", "2", "3"].map(String::from);
+    let crossing = render_entry("show-1", "Show One", "1");
+    let confirm = PendingPrevious::for_crossing(&crossing, Some(&episodes), PreviousConfirm::Zero)
+        .expect("stepping back to episode 0 should ask first");
+    assert_eq!(confirm.ani_id, "show-1");
+    assert_eq!(confirm.target, "episode 0 (prologue)");
+
+    // An ordinary step back plays straight away, as does any step with the prompt turned off.
+    let ordinary = render_entry("show-1", "Show One", "3");
+    assert!(
+        PendingPrevious::for_crossing(&ordinary, Some(&episodes), PreviousConfirm::Zero).is_none()
+    );
+    assert!(
+        PendingPrevious::for_crossing(&crossing, Some(&episodes), PreviousConfirm::Never).is_none()
+    );
+}
+
+#[test]
+fn parse_episode_label_accepts_numeric_labels_only() {
+    assert_eq!(parse_episode_label("12"), Ok("12".to_string()));
+    assert_eq!(parse_episode_label(" 12.5 "), Ok("12.5".to_string()));
+    assert_eq!(parse_episode_label("0"), Ok("0".to_string()));
+    for bad in ["", "-1", "12.", ".5", "1.2.3", "inf", "1e3", "12\tx"] {
+        assert!(
+            parse_episode_label(bad).is_err(),
+            "{bad:?} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn verify_jobs_default_to_three_and_reject_zero() {
+    use clap::Parser;
+
+    let jobs = |args: &[&str]| match Cli::try_parse_from(args).map(|cli| cli.command) {
+        Ok(Some(Command::Verify { jobs })) => Ok(jobs),
+        Ok(other) => panic!("expected verify, got {other:?}"),
+        Err(err) => Err(err),
+    };
+    assert_eq!(jobs(&["anitrack", "verify"]).expect("default"), 3);
+    assert_eq!(
+        jobs(&["anitrack", "verify", "--jobs", "8"]).expect("eight"),
+        8
+    );
+    assert!(jobs(&["anitrack", "verify", "--jobs", "0"]).is_err());
+    // The TUI's prefetch setting no longer changes how verify searches.
+    assert_eq!(
+        jobs(&["anitrack", "--concurrency", "0", "verify"]).expect("concurrency"),
+        3
+    );
+}
+
+#[test]
+fn parse_age_accepts_units_and_rejects_garbage() {
+    use std::time::Duration;
+
+    assert_eq!(parse_age("180d"), Ok(Duration::from_secs(180 * 86_400)));
+    assert_eq!(parse_age("2w"), Ok(Duration::from_secs(14 * 86_400)));
+    assert_eq!(parse_age("36h"), Ok(Duration::from_secs(36 * 3_600)));
+    assert_eq!(parse_age("30"), Ok(Duration::from_secs(30 * 86_400)));
+    assert!(parse_age("d").is_err());
+    assert!(parse_age("10y").is_err());
+    assert!(parse_age("99999999999999999999w").is_err());
+}
+
+#[test]
+fn prune_candidates_keeps_only_entries_seen_before_cutoff() {
+    let entry = |ani_id: &str, last_seen_at: &str| crate::db::SeenEntry {
+        ani_id: ani_id.to_string(),
+        title: ani_id.to_string(),
+        last_episode: "1".to_string(),
+        last_seen_at: last_seen_at.to_string(),
+        first_seen_at: last_seen_at.to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    let cutoff = DateTime::parse_from_rfc3339("2026-01-01T00:00:00+00:00")
+        .expect("cutoff should parse")
+        .with_timezone(&chrono::Utc);
+    let items = vec![
+        entry("old", "2025-06-01T00:00:00+00:00"),
+        entry("offset-old", "2026-01-01T01:00:00+02:00"),
+        entry("recent", "2026-02-01T00:00:00+00:00"),
+        entry("garbage", "not a timestamp"),
+    ];
+
+    let ids = prune_candidates(items, cutoff)
+        .into_iter()
+        .map(|item| item.ani_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["old", "offset-old"]);
+}
+
+#[test]
+fn prune_status_filter_rejects_statuses_that_are_not_stored() {
+    assert!(ensure_status_filter_supported("watching").is_ok());
+    let err = ensure_status_filter_supported("dropped").expect_err("dropped should be rejected");
+    assert!(err.to_string().contains("does not store a watch status"));
+}
+
+#[test]
+fn title_case_display_only_rewrites_single_case_titles() {
+    assert_eq!(
+        title_case_display("the apothecary diaries"),
+        "The Apothecary Diaries"
+    );
+    assert_eq!(
+        title_case_display("ATTACK ON TITAN SEASON 2ND PART II"),
+        "Attack on Titan Season 2nd Part II"
+    );
+    assert_eq!(title_case_display("SPY X FAMILY"), "Spy x Family");
+    assert_eq!(title_case_display("DANDADAN TV"), "Dandadan TV");
+    assert_eq!(
+        title_case_display("JoJo's Bizarre Adventure"),
+        "JoJo's Bizarre Adventure"
+    );
+    assert_eq!(title_case_display("86"), "86");
+
+    let (title, total) = parse_title_and_total_eps("one piece (1100 episodes)");
+    assert_eq!(
+        (title_case_display(&title), total),
+        ("One Piece".to_string(), Some(1100))
+    );
 }
 
 #[test]
-fn format_last_seen_display_keeps_raw_when_invalid() {
-    let raw = "not-a-timestamp";
-    assert_eq!(format_last_seen_display(raw), raw);
+fn episode_count_suffix_is_recognized_in_other_languages() {
+    assert_eq!(
+        parse_title_and_total_eps("Frieren (28 épisodes)"),
+        ("Frieren".to_string(), Some(28))
+    );
+    assert_eq!(
+        parse_title_and_total_eps("Frieren (28 Folgen)"),
+        ("Frieren".to_string(), Some(28))
+    );
+    assert_eq!(
+        parse_title_and_total_eps("Frieren (28 EPISODES)"),
+        ("Frieren".to_string(), Some(28))
+    );
+    // Other parenthesized words are part of the title.
+    assert_eq!(
+        parse_title_and_total_eps("Frieren (28 minutes)"),
+        ("Frieren (28 minutes)".to_string(), None)
+    );
+
+    assert_eq!(sanitize_title_for_search("Frieren (28 Folgen)"), "Frieren");
+    assert_eq!(
+        sanitize_title_for_search("Frieren (28 épisodes)"),
+        "Frieren"
+    );
+    assert_eq!(
+        sanitize_title_for_search("Frieren (Season 2)"),
+        "Frieren (Season 2)"
+    );
 }
 
 #[test]
-fn format_last_seen_display_tui_keeps_raw_when_invalid() {
-    let raw = "not-a-timestamp";
-    assert_eq!(format_last_seen_display_tui(raw), raw);
+fn interactive_commands_require_terminal_stdin() {
+    assert!(require_interactive_stdin("start", true).is_ok());
+    let err =
+        require_interactive_stdin("next", false).expect_err("non-TTY stdin should be refused");
+    let message = err.to_string();
+    assert!(message.contains("`anitrack next` requires an interactive terminal"));
+    assert!(message.contains("list, stats, export"));
+}
+
+#[test]
+fn resolve_player_prefers_flag_then_env_then_default() {
+    let from_flag = resolve_player_from(Some("vlc"), Some("mpv".to_string()));
+    assert_eq!(from_flag.command, "vlc");
+    assert_eq!(from_flag.source, "--player");
+
+    let from_env = resolve_player_from(Some(""), Some("iina".to_string()));
+    assert_eq!(from_env.command, "iina");
+    assert_eq!(from_env.source, "ANI_CLI_PLAYER");
+
+    let fallback = resolve_player_from(None, Some(String::new()));
+    assert_eq!(fallback.source, "ani-cli default");
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn find_on_path_checks_each_path_entry() {
+    let sandbox = TestSandbox::new("find-on-path");
+    let bin_dir = sandbox.root.join("bin");
+    fs::create_dir_all(&bin_dir).expect("bin dir should be created");
+    let player_name = if cfg!(windows) {
+        "myplayer.exe"
+    } else {
+        "myplayer"
+    };
+    fs::write(bin_dir.join(player_name), "").expect("player stub should be written");
+
+    let path_var = std::env::join_paths([sandbox.root.join("empty"), bin_dir.clone()])
+        .expect("PATH should join");
+    assert_eq!(
+        find_on_path("myplayer", Some(path_var.clone())),
+        Some(bin_dir.join(player_name))
+    );
+    assert_eq!(find_on_path("missing-player", Some(path_var)), None);
+    assert_eq!(find_on_path("myplayer", None), None);
+}
+
+#[test]
+fn truncate_end_style_keeps_prefix_with_dots() {
+    let ascii = "Long Title of a Show Part 2";
+    assert_eq!(
+        truncate_with_style(ascii, 12, EllipsisStyle::End),
+        "Long Titl..."
+    );
+    assert_eq!(truncate_with_style(ascii, 40, EllipsisStyle::End), ascii);
+
+    let cjk = "進撃の巨人 The Final Season Part 2";
+    assert_eq!(
+        truncate_with_style(cjk, 10, EllipsisStyle::End),
+        "進撃の巨人 T..."
+    );
+}
+
+#[test]
+fn truncate_middle_style_is_width_aware_and_keeps_suffix() {
+    use unicode_width::UnicodeWidthStr;
+
+    let ascii = "Long Title of a Show Part 2";
+    let cut = truncate_with_style(ascii, 15, EllipsisStyle::Middle);
+    assert_eq!(cut, "Long Ti…Part 2");
+    assert!(cut.width() <= 15);
+    assert_eq!(truncate_with_style(ascii, 40, EllipsisStyle::Middle), ascii);
+
+    let cjk = "進撃の巨人進撃の巨人 Part 2";
+    let cut = truncate_with_style(cjk, 14, EllipsisStyle::Middle);
+    assert!(cut.width() <= 14, "too wide: {cut}");
+    assert!(cut.starts_with("進撃の"));
+    assert!(cut.ends_with("Part 2"));
+
+    // Combining marks stay attached to their base character.
+    let combining = "Pok\u{e9}mon Pokemon Pok\u{65}\u{301}mon";
+    let cut = truncate_with_style(combining, 9, EllipsisStyle::Middle);
+    assert!(cut.ends_with("e\u{301}mon"), "split grapheme: {cut:?}");
+}
+
+#[test]
+fn ellipsis_style_env_defaults_to_end() {
+    assert_eq!(
+        ellipsis_style_from_env(None).expect("unset should default"),
+        EllipsisStyle::End
+    );
+    assert_eq!(
+        ellipsis_style_from_env(Some(" Middle ".to_string())).expect("middle should parse"),
+        EllipsisStyle::Middle
+    );
+    assert!(ellipsis_style_from_env(Some("start".to_string())).is_err());
+}
+
+#[test]
+fn normalized_title_updates_moves_episode_suffix_to_override() {
+    let item = |ani_id: &str, title: &str| crate::db::SeenEntry {
+        ani_id: ani_id.to_string(),
+        title: title.to_string(),
+        last_episode: "3".to_string(),
+        last_seen_at: "2026-01-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-01-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    let items = vec![
+        item("a", "Naruto (220 episodes)"),
+        item("b", "Frieren"),
+        item("c", "Mob Psycho 100 (12 episodes)"),
+    ];
+    assert_eq!(
+        normalized_title_updates(&items),
+        vec![
+            ("a".to_string(), "Naruto".to_string(), 220),
+            ("c".to_string(), "Mob Psycho 100".to_string(), 12),
+        ]
+    );
+
+    let clean = crate::db::SeenEntry {
+        total_eps_override: Some(220),
+        ..item("a", "Naruto")
+    };
+    assert_eq!(
+        entry_title_and_total(&clean),
+        ("Naruto".to_string(), Some(220))
+    );
+    assert!(entry_has_next_episode(&clean, None));
+}
+
+#[test]
+fn merge_entries_keeps_furthest_progress_and_latest_timestamp() {
+    let keep = crate::db::SeenEntry {
+        ani_id: "keep".to_string(),
+        title: "Frieren (28 episodes)".to_string(),
+        last_episode: "9".to_string(),
+        last_seen_at: "2026-01-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-01-01T00:00:00+00:00".to_string(),
+        tags: vec!["fantasy".to_string()],
+        ..crate::db::SeenEntry::default()
+    };
+    let drop = crate::db::SeenEntry {
+        ani_id: "drop".to_string(),
+        title: "Sousou no Frieren".to_string(),
+        last_episode: "12".to_string(),
+        last_seen_at: "2026-02-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-02-01T00:00:00+00:00".to_string(),
+        intro_skip_seconds: 90,
+        tags: vec!["rewatch".to_string(), "fantasy".to_string()],
+        ..crate::db::SeenEntry::default()
+    };
+
+    let merged = merge_entries(&keep, &drop);
+    assert_eq!(merged.ani_id, "keep");
+    assert_eq!(merged.title, "Frieren (28 episodes)");
+    assert_eq!(merged.last_episode, "12");
+    assert_eq!(merged.last_seen_at, "2026-02-01T00:00:00+00:00");
+    assert_eq!(merged.intro_skip_seconds, 90);
+    assert_eq!(merged.tags, vec!["fantasy", "rewatch"]);
+
+    let merged_back = merge_entries(&drop, &keep);
+    assert_eq!(merged_back.last_episode, "12");
+    assert_eq!(merged_back.last_seen_at, "2026-02-01T00:00:00+00:00");
+    assert_eq!(merged_back.intro_skip_seconds, 90);
+    assert_eq!(merged_back.watched_episodes, None);
+
+    // Watched episodes are combined when either entry tracks them.
+    let keep = crate::db::SeenEntry {
+        watched_episodes: Some(vec!["1".to_string(), "9".to_string()]),
+        ..keep
+    };
+    let merged = merge_entries(&keep, &drop);
+    assert_eq!(
+        merged.watched_episodes,
+        Some(vec!["1".to_string(), "9".to_string()])
+    );
+    let drop = crate::db::SeenEntry {
+        watched_episodes: Some(vec!["12".to_string(), "9".to_string(), "2".to_string()]),
+        ..drop
+    };
+    assert_eq!(
+        merge_entries(&keep, &drop).watched_episodes,
+        Some(["1", "2", "9", "12"].map(String::from).to_vec())
+    );
+}
+
+#[test]
+fn merge_entries_keeps_the_earlier_first_seen_instant() {
+    let keep = crate::db::SeenEntry {
+        ani_id: "keep".to_string(),
+        // 2026-01-01T00:00Z, though it sorts after the other entry's string.
+        first_seen_at: "2026-01-01T09:00:00+09:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    let drop = crate::db::SeenEntry {
+        ani_id: "drop".to_string(),
+        first_seen_at: "2026-01-01T01:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    assert_eq!(
+        merge_entries(&keep, &drop).first_seen_at,
+        "2026-01-01T09:00:00+09:00"
+    );
+    assert_eq!(
+        merge_entries(&drop, &keep).first_seen_at,
+        "2026-01-01T09:00:00+09:00"
+    );
+
+    let blank = crate::db::SeenEntry {
+        first_seen_at: String::new(),
+        ..drop.clone()
+    };
+    assert_eq!(
+        merge_entries(&keep, &blank).first_seen_at,
+        "2026-01-01T09:00:00+09:00"
+    );
+    assert_eq!(
+        merge_entries(&blank, &drop).first_seen_at,
+        "2026-01-01T01:00:00+00:00"
+    );
+    let invalid = crate::db::SeenEntry {
+        first_seen_at: "yesterday".to_string(),
+        ..drop
+    };
+    assert_eq!(
+        merge_entries(&keep, &invalid).first_seen_at,
+        "2026-01-01T09:00:00+09:00"
+    );
+}
+
+#[test]
+fn list_summary_counts_caught_up_entries_and_latest_watch() {
+    let mut latest = render_entry("show-2", "Dandadan (12 episodes)", "12");
+    latest.last_seen_at = "2026-03-02T00:00:00+00:00".to_string();
+    let mut movie = render_entry("show-3", "Suzume", "1");
+    movie.kind = crate::db::EntryKind::Movie;
+    let items = vec![
+        render_entry("show-1", "Frieren (28 episodes)", "7"),
+        latest,
+        movie,
+        // Without a total there is always a next episode to try.
+        render_entry("show-4", "One Piece", "1100"),
+    ];
+    assert_eq!(
+        list_summary(&items),
+        format!(
+            "4 entries, 2 caught up, last watched {}",
+            format_last_seen_display("2026-03-02T00:00:00+00:00")
+        )
+    );
+    assert_eq!(
+        list_summary(&items[..1]),
+        format!(
+            "1 entry, 0 caught up, last watched {}",
+            format_last_seen_display("2026-01-01T00:00:00+00:00")
+        )
+    );
+}
+
+#[test]
+fn list_json_emits_one_object_per_entry() {
+    let items = vec![crate::db::SeenEntry {
+        ani_id: "show-1".to_string(),
+        title: "Show \"One\"".to_string(),
+        last_episode: "3".to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    }];
+
+    let parsed: serde_json::Value =
+        serde_json::from_str(&list_json(&items)).expect("output should be valid JSON");
+    assert_eq!(parsed[0]["ani_id"], "show-1");
+    assert_eq!(parsed[0]["title"], "Show \"One\"");
+    assert_eq!(parsed[0]["last_episode"], "3");
+    assert_eq!(list_json(&[]), "[]");
+}
+
+#[test]
+fn tag_filter_matches_any_tag_by_default_and_all_when_requested() {
+    let item = crate::db::SeenEntry {
+        ani_id: "show-1".to_string(),
+        title: "Show One".to_string(),
+        last_episode: "1".to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        tags: vec!["drama".to_string(), "rewatch".to_string()],
+        ..crate::db::SeenEntry::default()
+    };
+    let filter = |tags: &[&str], match_all: bool| TagFilter {
+        tags: crate::db::normalize_tags(tags),
+        match_all,
+    };
+
+    assert!(filter(&[], false).matches(&item));
+    assert!(filter(&["Comedy", "REWATCH"], false).matches(&item));
+    assert!(!filter(&["comedy"], false).matches(&item));
+    assert!(filter(&["drama", "rewatch"], true).matches(&item));
+    assert!(!filter(&["drama", "comedy"], true).matches(&item));
+}
+
+#[test]
+fn list_template_renders_placeholders_from_entry() {
+    let item = crate::db::SeenEntry {
+        ani_id: "show-1".to_string(),
+        title: "Show One (12 episodes)".to_string(),
+        last_episode: "03".to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+
+    let template = ListTemplate::parse("{{{ani_id}}} {title} — ep {episode}/{total} [{progress}]")
+        .expect("template should parse");
+    assert_eq!(template.render(&item), "{show-1} Show One — ep 3/12 [3/12]");
+
+    let untotaled = crate::db::SeenEntry {
+        title: "Show Two".to_string(),
+        ..item.clone()
+    };
+    let template = ListTemplate::parse("{total} {progress}").expect("template should parse");
+    assert_eq!(template.render(&untotaled), "- -");
+
+    let template = ListTemplate::parse("{progress}")
+        .expect("template should parse")
+        .with_progress_format(EpisodeFormat::XOfY);
+    assert_eq!(template.render(&item), "3 of 12");
+
+    let template = ListTemplate::parse(NOW_TEMPLATE).expect("default now line should parse");
+    assert_eq!(template.render(&item), "Show One · ep 3");
+}
+
+#[test]
+fn list_template_rejects_unknown_or_malformed_placeholders() {
+    let err = ListTemplate::parse("{title} {season}").expect_err("unknown placeholder");
+    let msg = err.to_string();
+    assert!(msg.contains("{season}"));
+    assert!(msg.contains("{ani_id}, {title}, {episode}, {total}, {last_seen}, {progress}"));
+
+    assert!(ListTemplate::parse("{title").is_err());
+    assert!(ListTemplate::parse("title}").is_err());
+}
+
+#[test]
+fn find_untracked_history_skips_tracked_and_keeps_latest_line_per_show() {
+    let tracked = vec![crate::db::SeenEntry {
+        ani_id: "tracked".to_string(),
+        title: "Tracked Show".to_string(),
+        last_episode: "2".to_string(),
+        last_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-03-01T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    }];
+    let hist = |ep: &str, id: &str, title: &str| HistEntry {
+        ep: ep.to_string(),
+        id: id.to_string(),
+        title: title.to_string(),
+    };
+    let ordered = vec![
+        hist("1", "alpha", "Alpha"),
+        hist("5", "tracked", "Tracked Show"),
+        hist("3", "beta", "Beta"),
+        hist("2", "alpha", "Alpha"),
+    ];
+
+    let untracked = find_untracked_history(&tracked, &ordered);
+    assert_eq!(
+        untracked,
+        vec![hist("3", "beta", "Beta"), hist("2", "alpha", "Alpha")]
+    );
+
+    let parsed: serde_json::Value =
+        serde_json::from_str(&untracked_json(&untracked)).expect("output should be valid JSON");
+    assert_eq!(parsed[0]["ani_id"], "beta");
+    assert_eq!(parsed[1]["last_episode"], "2");
+}
+
+#[test]
+fn status_breakdown_lists_every_status_in_stable_order() {
+    let raw = vec![("watching".to_string(), 3)];
+    let breakdown = status_breakdown(&raw);
+    assert_eq!(
+        breakdown,
+        vec![
+            ("watching".to_string(), 3),
+            ("completed".to_string(), 0),
+            ("dropped".to_string(), 0),
+            ("on_hold".to_string(), 0),
+        ]
+    );
+}
+
+#[test]
+fn journal_mode_from_env_defaults_to_wal_and_rejects_unknown_modes() {
+    assert_eq!(
+        journal_mode_from_env(None).expect("unset should default"),
+        JournalMode::Wal
+    );
+    assert_eq!(
+        journal_mode_from_env(Some(String::new())).expect("empty should default"),
+        JournalMode::Wal
+    );
+    assert_eq!(
+        journal_mode_from_env(Some("DELETE".to_string())).expect("delete should parse"),
+        JournalMode::Delete
+    );
+    let err = journal_mode_from_env(Some("memory".to_string())).expect_err("memory is rejected");
+    assert!(err.to_string().contains("ANI_TRACK_JOURNAL_MODE"));
 }
 
 #[test]
@@ -597,6 +2823,61 @@ fn resolve_ani_cli_bin_from_env_falls_back_on_missing_or_empty() {
     assert_eq!(empty, std::path::PathBuf::from("ani-cli"));
 }
 
+#[test]
+fn ani_cli_command_sets_player_env_only_when_configured() {
+    let bin = std::path::Path::new("ani-cli");
+    let launch = LaunchOptions {
+        player: Some("vlc".to_string()),
+        ..LaunchOptions::default()
+    };
+    let cmd = ani_cli_command(bin, &launch);
+    let player = cmd
+        .get_envs()
+        .find(|(key, _)| *key == "ANI_CLI_PLAYER")
+        .and_then(|(_, value)| value);
+    assert_eq!(player, Some(OsString::from("vlc").as_os_str()));
+
+    let cmd = ani_cli_command(bin, &LaunchOptions::default());
+    assert!(cmd.get_envs().all(|(key, _)| key != "ANI_CLI_PLAYER"));
+}
+
+#[test]
+fn episode_command_puts_extra_args_between_own_flags_and_title() {
+    let launch = LaunchOptions {
+        extra_args: vec!["--rofi".to_string(), "-q".to_string(), "1080".to_string()],
+        ..LaunchOptions::default()
+    };
+    let item = render_entry("show-1", "Show One (12 episodes)", "3");
+    let cmd = episode_command(
+        std::path::Path::new("ani-cli"),
+        &item,
+        Some(2),
+        "4",
+        &launch,
+    );
+    let args = cmd.get_args().collect::<Vec<_>>();
+    assert_eq!(
+        args,
+        ["-S", "2", "--rofi", "-q", "1080", "Show One", "-e", "4"]
+    );
+}
+
+#[test]
+fn ani_cli_args_from_env_splits_on_whitespace() {
+    assert_eq!(
+        ani_cli_args_from_env(Some("  --rofi   -q 720 ".to_string())),
+        ["--rofi", "-q", "720"]
+    );
+    assert!(ani_cli_args_from_env(Some("   ".to_string())).is_empty());
+    assert!(ani_cli_args_from_env(None).is_empty());
+}
+
+#[test]
+fn intro_skip_note_only_shown_for_nonzero_offsets() {
+    assert_eq!(intro_skip_note(0), None);
+    assert!(intro_skip_note(90).is_some_and(|note| note.starts_with("Intro skip: 90s")));
+}
+
 #[test]
 fn temp_hist_dir_drop_removes_directory() {
     let temp_hist_dir = TempHistDir::new().expect("temp history dir should be created");
@@ -619,6 +2900,56 @@ fn parse_mode_episode_labels_extracts_string_and_numeric_values() {
     assert_eq!(episodes, vec!["0", "1", "2"]);
 }
 
+#[test]
+fn parse_mode_episode_labels_keeps_labels_containing_brackets() {
+    let payload = r#"{"data":{"show":{"availableEpisodesDetail":{"sub":["1","2 [recap]","]3["],"dub":["1"]}}}}"#;
+    let episodes = parse_mode_episode_labels(payload, "sub").expect("sub episodes should parse");
+    assert_eq!(episodes, vec!["1", "2 [recap]", "]3["]);
+    let dub = parse_mode_episode_labels(payload, "dub").expect("dub episodes should parse");
+    assert_eq!(dub, vec!["1"]);
+}
+
+#[test]
+fn episode_labels_from_truncated_response_warn_instead_of_returning_a_partial_list() {
+    let payload = r#"{"data":{"show":{"availableEpisodesDetail":{"sub":["1","2","3"#;
+    assert_eq!(parse_mode_episode_labels(payload, "sub"), None);
+
+    let outcome = episode_labels_from_response("show-1", payload, None);
+    assert_eq!(outcome.episode_list, None);
+    assert_eq!(outcome.warnings.len(), 1);
+    assert!(
+        outcome.warnings[0].contains("show-1 was truncated"),
+        "unexpected warning: {}",
+        outcome.warnings[0]
+    );
+
+    let garbled = episode_labels_from_response("show-1", "{not json}", None);
+    assert!(garbled.warnings[0].contains("parse failed for show-1"));
+}
+
+#[test]
+fn parse_airing_status_maps_metadata_wording_and_leaves_unknown_blank() {
+    let status = |raw: &str| {
+        let value: serde_json::Value = serde_json::from_str(raw).expect("payload should parse");
+        parse_airing_status_from_value(&value)
+    };
+    assert_eq!(
+        status(r#"{"data":{"show":{"status":"Releasing"}}}"#),
+        Some(AiringStatus::Airing)
+    );
+    assert_eq!(
+        status(r#"{"data":{"show":{"status":"Finished"}}}"#),
+        Some(AiringStatus::Finished)
+    );
+    assert_eq!(
+        status(r#"{"data":{"show":{"status":"Not Yet Released"}}}"#),
+        None
+    );
+    assert_eq!(status(r#"{"data":{"show":{"status":null}}}"#), None);
+    assert_eq!(status(r#"{"data":{"show":{}}}"#), None);
+    assert_eq!(AiringStatus::parse("airing"), Some(AiringStatus::Airing));
+}
+
 #[test]
 fn parse_search_result_entries_handles_escaped_titles() {
     let raw = r#"{"data":{"shows":{"edges":[{"_id":"id-1","name":"Boku no Hero Academia: Heroes Rising \"Special\""}]}}}"#;
@@ -638,18 +2969,146 @@ fn parse_search_result_entries_returns_empty_on_invalid_json() {
 }
 
 #[cfg(any(unix, windows))]
-static ENV_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+static ENV_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+#[cfg(any(unix, windows))]
+fn env_lock() -> &'static Mutex<()> {
+    ENV_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+#[cfg(any(unix, windows))]
+fn env_lock_guard() -> std::sync::MutexGuard<'static, ()> {
+    env_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn select_budget_from_env_falls_back_to_the_default_with_a_warning() {
+    let _guard = env_lock_guard();
+    let _budget = ScopedEnvVar::set("ANI_TRACK_SELECT_BUDGET_SECS", OsStr::new("30"));
+    assert_eq!(
+        select_budget_from_env(),
+        (std::time::Duration::from_secs(30), None)
+    );
+
+    let _budget = ScopedEnvVar::set("ANI_TRACK_SELECT_BUDGET_SECS", OsStr::new("soon"));
+    let (budget, warning) = select_budget_from_env();
+    assert_eq!(budget, std::time::Duration::from_secs(15));
+    assert!(warning.is_some_and(|warning| warning.contains("ANI_TRACK_SELECT_BUDGET_SECS `soon`")));
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn ensure_writable_dir_reports_the_unwritable_path() {
+    let sandbox = TestSandbox::new("writable-dir");
+    let data_dir = sandbox.root.join("data").join("anitrack");
+    ensure_writable_dir(&data_dir).expect("missing directory should be created");
+    assert!(data_dir.is_dir());
+    assert_eq!(
+        fs::read_dir(&data_dir)
+            .expect("directory should be readable")
+            .count(),
+        0,
+        "the write probe should be removed"
+    );
+
+    let blocker = sandbox.root.join("not-a-dir");
+    fs::write(&blocker, "").expect("blocker file should be written");
+    let err = ensure_writable_dir(&blocker.join("anitrack"))
+        .expect_err("a file in the path should make the directory unwritable");
+    assert!(
+        err.to_string()
+            .starts_with("data directory is not writable: ")
+    );
+    assert!(err.to_string().contains("not-a-dir"));
+}
+
+#[test]
+fn read_only_fallback_reports_a_database_that_needs_migration() {
+    let sandbox = TestSandbox::new("read-only-schema");
+    let old_path = sandbox.root.join("old.db");
+    fs::write(&old_path, "").expect("empty database file should be written");
+    let old = Database::open_read_only(&old_path).expect("read-only open should work");
+    let err = ensure_read_only_schema(&old, &old_path, "the database is locked by another process")
+        .expect_err("an unmigrated database should be refused");
+    let message = err.to_string();
+    assert!(message.contains("needs migration"), "{message}");
+    assert!(message.contains("schema version 0"), "{message}");
+    assert!(message.contains("locked by another process"), "{message}");
+
+    let _current = open_test_db(&sandbox.root);
+    let current_path = sandbox.root.join("anitrack.db");
+    let current = Database::open_read_only(&current_path).expect("read-only open should work");
+    ensure_read_only_schema(&current, &current_path, "locked")
+        .expect("a current schema should be accepted");
+}
+
+#[test]
+fn parse_watch_later_reads_title_and_start() {
+    let plain =
+        "# redirect entry\nstart=1234.567000\nforce-media-title=Frieren Episode 3\nvolume=80\n";
+    assert_eq!(
+        parse_watch_later(plain),
+        Some(("Frieren Episode 3".to_string(), 1234.567))
+    );
+
+    let quoted = "start=90.000000\nforce-media-title=%19%Show=Name Episode 1\n";
+    assert_eq!(
+        parse_watch_later(quoted),
+        Some(("Show=Name Episode 1".to_string(), 90.0))
+    );
+
+    assert_eq!(parse_watch_later("start=12.0\n"), None);
+    assert_eq!(
+        parse_watch_later("force-media-title=Show Episode 1\n"),
+        None
+    );
+    assert_eq!(
+        parse_watch_later("start=nope\nforce-media-title=Show Episode 1\n"),
+        None
+    );
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn watch_later_matches_resume_files_to_the_stored_episode() {
+    let sandbox = TestSandbox::new("watch-later");
+    let missing = WatchLater::new(sandbox.root.join("missing"));
+    assert!(read_watch_later_dir(&sandbox.root.join("missing")).is_empty());
+
+    fs::write(
+        sandbox.root.join("A1B2C3"),
+        "start=754.2\nforce-media-title=Sousou no Frieren Episode 4\n",
+    )
+    .expect("resume file should be written");
+    fs::write(sandbox.root.join("D4E5F6"), "start=30\n").expect("resume file should be written");
+    fs::write(sandbox.root.join("G7H8I9"), [0xff, 0xfe, 0x00])
+        .expect("binary file should be written");
+
+    let mut watch_later = WatchLater::new(sandbox.root.clone());
+    watch_later.refresh_if_stale();
+    let mut item = crate::db::SeenEntry {
+        ani_id: "frieren".to_string(),
+        title: "Sousou no Frieren: (28 episodes)".to_string(),
+        last_episode: "4".to_string(),
+        last_seen_at: "2026-02-27T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-02-27T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
+    };
+    assert_eq!(watch_later.resume_position(&item), Some(754.2));
+    assert_eq!(missing.resume_position(&item), None);
 
-#[cfg(any(unix, windows))]
-fn env_lock() -> &'static Mutex<()> {
-    ENV_LOCK.get_or_init(|| Mutex::new(()))
+    item.last_episode = "5".to_string();
+    assert_eq!(watch_later.resume_position(&item), None);
 }
 
-#[cfg(any(unix, windows))]
-fn env_lock_guard() -> std::sync::MutexGuard<'static, ()> {
-    env_lock()
-        .lock()
-        .unwrap_or_else(|poisoned| poisoned.into_inner())
+#[test]
+fn format_resume_position_uses_hours_only_when_needed() {
+    assert_eq!(format_resume_position(0.0), "0:00");
+    assert_eq!(format_resume_position(754.9), "12:34");
+    assert_eq!(format_resume_position(3_725.0), "1:02:05");
 }
 
 #[cfg(any(unix, windows))]
@@ -719,6 +3178,198 @@ fn open_test_db(root: &Path) -> Database {
     db
 }
 
+#[test]
+fn add_entry_inserts_new_entries_and_refuses_to_overwrite() {
+    let sandbox = TestSandbox::new("add-entry");
+    let db = open_test_db(&sandbox.root);
+
+    let added = add_entry(&db, " show-1 ", " Frieren (28 episodes) ", "0")
+        .expect("new entry should be added");
+    assert_eq!(added.ani_id, "show-1");
+    assert_eq!(added.title, "Frieren (28 episodes)");
+    assert_eq!(added.last_episode, "0");
+    // Without `--episode` nothing has been watched yet, so Next starts at episode 1.
+    assert!(entry_has_next_episode(&added, None));
+    assert_eq!(next_episode_label(&added, None).as_deref(), Some("1"));
+
+    let err = add_entry(&db, "show-1", "Something Else", "5")
+        .expect_err("an existing entry should not be overwritten");
+    assert!(err.to_string().contains("already tracked"));
+    let kept = db
+        .get_seen("show-1")
+        .expect("get should succeed")
+        .expect("entry should still exist");
+    assert_eq!(kept.last_episode, "0");
+
+    assert!(add_entry(&db, "  ", "Title", "1").is_err());
+    assert!(add_entry(&db, "show-2", " \t", "1").is_err());
+    assert!(db.get_seen("show-2").expect("get should succeed").is_none());
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn refresh_items_keeps_cached_list_when_database_read_fails() {
+    let sandbox = TestSandbox::new("refresh-items-failure");
+    let db = open_test_db(&sandbox.root);
+    db.upsert_seen("show-1", "Show One", "3")
+        .expect("seed row should be inserted");
+    db.upsert_seen("show-2", "Show Two", "5")
+        .expect("seed row should be inserted");
+
+    let mut items = Vec::new();
+    let mut table_state = ratatui::widgets::TableState::default();
+    let mut status = "INFO: Ready.".to_string();
+    refresh_items(
+        &db,
+        ListSort::Watched.into(),
+        &mut items,
+        &mut table_state,
+        Some("show-1"),
+        &mut status,
+    );
+    assert_eq!(items.len(), 2);
+    assert_eq!(status, "INFO: Ready.");
+
+    // Another connection breaking the table stands in for a read that fails mid-session.
+    rusqlite::Connection::open(sandbox.root.join("anitrack.db"))
+        .expect("second connection should open")
+        .execute_batch("DROP TABLE seen_progress;")
+        .expect("table should be dropped");
+    refresh_items(
+        &db,
+        ListSort::Watched.into(),
+        &mut items,
+        &mut table_state,
+        Some("show-1"),
+        &mut status,
+    );
+    assert_eq!(items.len(), 2, "the cached list should be kept");
+    assert_eq!(
+        table_state
+            .selected()
+            .and_then(|idx| items.get(idx))
+            .map(|item| item.ani_id.as_str()),
+        Some("show-1")
+    );
+    assert!(status.starts_with("ERROR: Failed to reload library: "));
+    assert!(status.ends_with("; showing cached list"));
+}
+
+#[test]
+fn backup_round_trip_restores_all_columns() {
+    let sandbox = TestSandbox::new("backup-round-trip");
+    let db = open_test_db(&sandbox.root);
+    db.upsert_seen("show-1", "Show One (12 episodes)", "3")
+        .expect("seed row should be inserted");
+    db.upsert_seen("show-2", "Show Two", "13.5")
+        .expect("seed row should be inserted");
+    db.set_intro_skip("show-1", 85)
+        .expect("intro skip should be set");
+    db.set_tags("show-2", &["rewatch, with partner"])
+        .expect("tags should be set");
+    db.set_kind("show-2", crate::db::EntryKind::Movie)
+        .expect("kind should be set");
+    db.set_external_link("show-1", Some(("anilist", "154587")))
+        .expect("link should be set");
+    db.add_watched_episode("show-2", "13.5")
+        .expect("watched episode should be added");
+    let snapshot = |db: &Database| {
+        db.list_seen()
+            .expect("list should succeed")
+            .into_iter()
+            .map(|item| {
+                (
+                    item.ani_id,
+                    item.title,
+                    item.last_episode,
+                    item.last_seen_at,
+                    item.intro_skip_seconds,
+                    item.tags,
+                    item.kind,
+                    item.external_provider,
+                    item.external_id,
+                    item.watched_episodes,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let before = snapshot(&db);
+
+    let backup = export_backup(&db).expect("export should succeed");
+    let header: serde_json::Value = serde_json::from_str(&backup).expect("backup is JSON");
+    assert_eq!(header["format_version"], 1);
+    assert_eq!(
+        header["schema_version"],
+        db.schema_version().expect("schema version should read")
+    );
+
+    for (ani_id, ..) in &before {
+        db.delete_seen(ani_id).expect("delete should succeed");
+    }
+    assert!(db.list_seen().expect("list should succeed").is_empty());
+
+    assert_eq!(
+        import_backup(&db, &backup).expect("import should succeed"),
+        2
+    );
+    assert_eq!(snapshot(&db), before);
+}
+
+#[test]
+fn parse_backup_upgrades_entries_from_older_schema() {
+    let v2_backup = r#"{
+        "format": "anitrack-backup",
+        "format_version": 1,
+        "schema_version": 2,
+        "entries": [
+            {"ani_id": "show-1", "title": "Show One", "last_episode": "4", "last_seen_at": "2026-03-01T00:00:00+00:00"}
+        ]
+    }"#;
+
+    let entries = parse_backup(v2_backup).expect("v2 backup should parse");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].last_episode, "4");
+    assert_eq!(entries[0].intro_skip_seconds, 0);
+    assert!(entries[0].tags.is_empty());
+    assert_eq!(entries[0].kind, crate::db::EntryKind::Series);
+}
+
+#[test]
+fn parse_backup_rejects_newer_schema_and_unknown_documents() {
+    let newer = format!(
+        r#"{{"format": "anitrack-backup", "format_version": 1, "schema_version": {}, "entries": []}}"#,
+        crate::db::SCHEMA_VERSION + 1
+    );
+    let err = parse_backup(&newer).expect_err("newer schema should be rejected");
+    assert!(err.to_string().contains("newer than supported"));
+
+    assert!(parse_backup(r#"{"entries": []}"#).is_err());
+    assert!(parse_backup("not json").is_err());
+
+    let half_linked = r#"{
+        "format": "anitrack-backup",
+        "format_version": 1,
+        "schema_version": 11,
+        "entries": [
+            {"ani_id": "show-1", "title": "Show One", "last_episode": "4", "last_seen_at": "2026-03-01T00:00:00+00:00", "external_provider": "anilist"}
+        ]
+    }"#;
+    let err = parse_backup(half_linked).expect_err("a provider without an id should be rejected");
+    assert!(format!("{err:#}").contains("must be set together"));
+
+    let unknown_action = r#"{
+        "format": "anitrack-backup",
+        "format_version": 1,
+        "schema_version": 11,
+        "entries": [
+            {"ani_id": "show-1", "title": "Show One", "last_episode": "4", "last_seen_at": "2026-03-01T00:00:00+00:00", "default_action": "rewind"}
+        ]
+    }"#;
+    let err =
+        parse_backup(unknown_action).expect_err("an unknown default action should be rejected");
+    assert!(format!("{err:#}").contains("invalid `default_action`"));
+}
+
 #[cfg(unix)]
 fn create_fake_ani_cli(root: &Path) -> PathBuf {
     let script_path = root.join("fake-ani-cli.sh");
@@ -729,11 +3380,19 @@ mode="${ANITRACK_FAKE_MODE:-}"
 hist_dir="${ANI_CLI_HIST_DIR:-${XDG_STATE_HOME:-$HOME/.local/state}/ani-cli}"
 hist_file="${hist_dir}/ani-hsts"
 mkdir -p "${hist_dir}"
+if [ -n "${ANITRACK_FAKE_HIST_DIR_LOG:-}" ]; then
+  printf '%s' "${hist_dir}" > "${ANITRACK_FAKE_HIST_DIR_LOG}"
+fi
 
 case "${mode}" in
   start_success)
     printf '1\tshow-1\tShow One\n' >> "${hist_file}"
     ;;
+  start_binge)
+    for ep in 1 2 3; do
+      printf '%s\tshow-1\tShow One\n' "${ep}" >> "${hist_file}"
+    done
+    ;;
   replay_success|next_success|previous_success)
     line="$(tail -n 1 "${hist_file}" 2>/dev/null || true)"
     if [ -n "${line}" ]; then
@@ -751,6 +3410,9 @@ case "${mode}" in
   next_fail|previous_fail)
     exit 1
     ;;
+  hang)
+    exec sleep 30
+    ;;
 esac
 "#;
     fs::write(&script_path, script).expect("fake ani-cli should be written");
@@ -776,7 +3438,7 @@ fn integration_start_records_watch_progress_with_fake_ani_cli() {
     let _hist = ScopedEnvVar::set("ANI_CLI_HIST_DIR", hist_dir.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("start_success"));
 
-    run_start(&db).expect("start command should succeed");
+    run_start(&db, &LaunchOptions::default(), false).expect("start command should succeed");
 
     let last_seen = db
         .last_seen()
@@ -787,6 +3449,163 @@ fn integration_start_records_watch_progress_with_fake_ani_cli() {
     assert_eq!(last_seen.last_episode, "1");
 }
 
+#[cfg(unix)]
+#[test]
+fn integration_start_records_every_episode_a_run_watched() {
+    let _env_guard = env_lock_guard();
+    let sandbox = TestSandbox::new("start-binge");
+    let db = open_test_db(&sandbox.root);
+    let fake_ani_cli = create_fake_ani_cli(&sandbox.root);
+    let hist_dir = sandbox.root.join("hist");
+    fs::create_dir_all(&hist_dir).expect("hist directory should be created");
+
+    let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
+    let _hist = ScopedEnvVar::set("ANI_CLI_HIST_DIR", hist_dir.as_os_str());
+    let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("start_binge"));
+
+    set_track_watched_episodes(true);
+    let result = run_start(&db, &LaunchOptions::default(), false);
+    set_track_watched_episodes(false);
+    result.expect("start command should succeed");
+
+    let entry = db
+        .get_seen("show-1")
+        .expect("db query should succeed")
+        .expect("entry should be recorded");
+    assert_eq!(entry.last_episode, "3");
+    assert_eq!(
+        entry.watched_episodes.as_deref(),
+        Some(&["1", "2", "3"].map(String::from)[..])
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn integration_earlier_episode_keeps_furthest_progress_when_tracking_watched_episodes() {
+    let _env_guard = env_lock_guard();
+    let sandbox = TestSandbox::new("watched-max");
+    let db = open_test_db(&sandbox.root);
+    let fake_ani_cli = create_fake_ani_cli(&sandbox.root);
+    db.upsert_seen("show-1", "Show One", "5")
+        .expect("seed row should be inserted");
+
+    let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
+    let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("next_success"));
+
+    set_track_watched_episodes(true);
+    let result = run_continue_from(&db, &LaunchOptions::default(), "show-1", "2");
+    set_track_watched_episodes(false);
+    result.expect("continue-from should succeed");
+
+    let entry = db
+        .get_seen("show-1")
+        .expect("db query should succeed")
+        .expect("entry should exist");
+    assert_eq!(entry.last_episode, "5");
+    assert_eq!(
+        entry.watched_episodes.as_deref(),
+        Some(&["3".to_string()][..])
+    );
+
+    run_continue_from(&db, &LaunchOptions::default(), "show-1", "2")
+        .expect("continue-from should succeed");
+    let untracked = db
+        .get_seen("show-1")
+        .expect("db query should succeed")
+        .expect("entry should exist");
+    assert_eq!(untracked.last_episode, "3");
+}
+
+#[cfg(unix)]
+#[test]
+fn integration_search_reports_an_empty_history_file() {
+    let _env_guard = env_lock_guard();
+    let sandbox = TestSandbox::new("start-empty-hist");
+    let db = open_test_db(&sandbox.root);
+    let fake_ani_cli = create_fake_ani_cli(&sandbox.root);
+    let hist_dir = sandbox.root.join("hist");
+    fs::create_dir_all(&hist_dir).expect("hist directory should be created");
+    let hist_file = hist_dir.join("ani-hsts");
+    fs::write(&hist_file, "").expect("empty history should be written");
+
+    let read = read_hist_map(&hist_file);
+    assert!(read.ordered_entries.is_empty());
+    assert_eq!(read.skipped_lines, 0);
+    assert!(read.warnings.is_empty());
+
+    let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
+    let _hist = ScopedEnvVar::set("ANI_CLI_HIST_DIR", hist_dir.as_os_str());
+    // A mode the fake does not know: it runs and writes nothing.
+    let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("watch_elsewhere"));
+
+    let (message, changed_id) =
+        run_ani_cli_search(&db, &LaunchOptions::default()).expect("search should complete");
+    assert_eq!(changed_id, None);
+    assert!(
+        message.starts_with(&format!(
+            "No watch recorded: the ani-cli history at {} is empty after this run.",
+            hist_file.display()
+        )),
+        "{message}"
+    );
+    assert!(message.contains("ANI_CLI_HIST_DIR"), "{message}");
+    assert!(message.contains("--hist-dir"), "{message}");
+
+    fs::remove_file(&hist_file).expect("history should be removed");
+    let (message, _) =
+        run_ani_cli_search(&db, &LaunchOptions::default()).expect("search should complete");
+    assert!(
+        message.contains("does not exist after this run"),
+        "{message}"
+    );
+
+    fs::write(&hist_file, "1\tshow-1\tShow One\n").expect("history should be written");
+    let (message, _) =
+        run_ani_cli_search(&db, &LaunchOptions::default()).expect("search should complete");
+    assert!(
+        message.starts_with("No new history entry detected from this run."),
+        "{message}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn integration_next_plays_episode_one_after_an_offline_restart() {
+    let _env_guard = env_lock_guard();
+    let sandbox = TestSandbox::new("restart-next");
+    let db = open_test_db(&sandbox.root);
+    let fake_ani_cli = create_fake_ani_cli(&sandbox.root);
+    let hist_dir = sandbox.root.join("hist");
+    fs::create_dir_all(&hist_dir).expect("hist directory should be created");
+    db.upsert_seen("show-1", "Show One (12 episodes)", "7")
+        .expect("seed row should be inserted");
+    // Restart without a fetched episode list, as `restart` and `R` do offline.
+    db.upsert_seen("show-1", "Show One (12 episodes)", &restart_episode(None))
+        .expect("restart should be stored");
+    let restarted = db
+        .get_seen("show-1")
+        .expect("db query should succeed")
+        .expect("entry should exist");
+    assert!(entry_has_next_episode(&restarted, None));
+
+    let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
+    let _hist = ScopedEnvVar::set("ANI_CLI_HIST_DIR", hist_dir.as_os_str());
+    // Episode 0 is not in ani-cli's list, so Next selects episode 1 directly.
+    let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("select_success"));
+    let _select_override = ScopedEnvVar::set("ANI_TRACK_TEST_SELECT_NTH", OsStr::new("1"));
+    let _select_id = ScopedEnvVar::set("ANITRACK_FAKE_ANI_ID", OsStr::new("show-1"));
+    let _select_title =
+        ScopedEnvVar::set("ANITRACK_FAKE_TITLE", OsStr::new("Show One (12 episodes)"));
+    let _select_episode = ScopedEnvVar::set("ANITRACK_FAKE_EPISODE", OsStr::new("1"));
+    run_next(&db, &LaunchOptions::default()).expect("next command should complete");
+
+    let entry = db
+        .get_seen("show-1")
+        .expect("db query should succeed")
+        .expect("entry should exist");
+    assert_eq!(entry.last_episode, "1");
+}
+
 #[cfg(unix)]
 #[test]
 fn integration_next_updates_progress_when_fake_continue_succeeds() {
@@ -800,7 +3619,7 @@ fn integration_next_updates_progress_when_fake_continue_succeeds() {
     let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("next_success"));
 
-    run_next(&db).expect("next command should complete");
+    run_next(&db, &LaunchOptions::default()).expect("next command should complete");
 
     let last_seen = db
         .last_seen()
@@ -809,6 +3628,107 @@ fn integration_next_updates_progress_when_fake_continue_succeeds() {
     assert_eq!(last_seen.last_episode, "2");
 }
 
+#[cfg(unix)]
+#[test]
+fn sweep_removes_old_temp_history_dirs_of_dead_processes_only() {
+    let sandbox = TestSandbox::new("hist-sweep");
+    let mut child = std::process::Command::new("true")
+        .spawn()
+        .expect("true should spawn");
+    let dead_pid = child.id();
+    child.wait().expect("true should exit");
+
+    let make_dir = |name: String, age: std::time::Duration| {
+        let dir = sandbox.root.join(name);
+        fs::create_dir_all(&dir).expect("fixture dir should be created");
+        fs::File::open(&dir)
+            .and_then(|handle| handle.set_modified(std::time::SystemTime::now() - age))
+            .expect("fixture mtime should be set");
+        dir
+    };
+    let two_hours = std::time::Duration::from_secs(2 * 60 * 60);
+    let old_dead = make_dir(format!("anitrack-hist-{dead_pid}-1"), two_hours);
+    let fresh_dead = make_dir(
+        format!("anitrack-hist-{dead_pid}-2"),
+        std::time::Duration::ZERO,
+    );
+    let old_own = make_dir(format!("anitrack-hist-{}-3", std::process::id()), two_hours);
+    let old_unrelated = make_dir("anitrack-hist-notes".to_string(), two_hours);
+
+    assert_eq!(
+        sweep_stale_temp_hist_dirs(&sandbox.root, STALE_TEMP_HIST_AGE),
+        1
+    );
+    assert!(!old_dead.exists());
+    assert!(fresh_dead.exists());
+    assert!(old_own.exists());
+    assert!(old_unrelated.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn integration_next_removes_temp_history_dir_after_playback() {
+    let _env_guard = env_lock_guard();
+    let sandbox = TestSandbox::new("next-cleanup");
+    let db = open_test_db(&sandbox.root);
+    let fake_ani_cli = create_fake_ani_cli(&sandbox.root);
+    let hist_dir_log = sandbox.root.join("hist-dir.log");
+    db.upsert_seen("show-1", "Show One", "1")
+        .expect("seed row should be inserted");
+
+    let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
+    let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("next_success"));
+    let _log = ScopedEnvVar::set("ANITRACK_FAKE_HIST_DIR_LOG", hist_dir_log.as_os_str());
+
+    run_next(&db, &LaunchOptions::default()).expect("next command should complete");
+
+    let used_hist_dir = fs::read_to_string(&hist_dir_log).expect("fake should log hist dir");
+    assert!(
+        used_hist_dir.contains("anitrack-hist-"),
+        "continue should use a temp history dir: {used_hist_dir}"
+    );
+    assert!(
+        !Path::new(&used_hist_dir).exists(),
+        "temp history dir should be removed after playback"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn integration_background_continue_reports_progress_after_exit() {
+    let _env_guard = env_lock_guard();
+    let sandbox = TestSandbox::new("background-next");
+    let db = open_test_db(&sandbox.root);
+    let fake_ani_cli = create_fake_ani_cli(&sandbox.root);
+    db.upsert_seen("show-1", "Show One", "1")
+        .expect("seed row should be inserted");
+    let item = db
+        .last_seen()
+        .expect("db query should succeed")
+        .expect("entry should exist");
+
+    let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
+    let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("next_success"));
+
+    let mut playback = spawn_ani_cli_continue_detached(&item, &LaunchOptions::default())
+        .expect("background playback should spawn");
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let finish = loop {
+        if let Some(finish) = playback.try_finish().expect("poll should succeed") {
+            break finish;
+        }
+        assert!(
+            std::time::Instant::now() < deadline,
+            "background playback should exit"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+
+    assert!(finish.outcome.success);
+    assert_eq!(finish.outcome.final_episode.as_deref(), Some("2"));
+    assert!(finish.warnings.is_empty());
+}
+
 #[cfg(unix)]
 #[test]
 fn integration_next_keeps_progress_when_fake_continue_fails() {
@@ -822,7 +3742,7 @@ fn integration_next_keeps_progress_when_fake_continue_fails() {
     let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("next_fail"));
 
-    run_next(&db).expect("next command should not bubble fake failure");
+    run_next(&db, &LaunchOptions::default()).expect("next command should not bubble fake failure");
 
     let last_seen = db
         .last_seen()
@@ -831,6 +3751,44 @@ fn integration_next_keeps_progress_when_fake_continue_fails() {
     assert_eq!(last_seen.last_episode, "1");
 }
 
+#[cfg(unix)]
+#[test]
+fn integration_next_terminates_ani_cli_after_max_runtime() {
+    let _env_guard = env_lock_guard();
+    let sandbox = TestSandbox::new("next-timeout");
+    let db = open_test_db(&sandbox.root);
+    let fake_ani_cli = create_fake_ani_cli(&sandbox.root);
+    db.upsert_seen("show-1", "Show One", "1")
+        .expect("seed row should be inserted");
+    let item = db
+        .last_seen()
+        .expect("db query should succeed")
+        .expect("entry should exist");
+
+    let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
+    let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("hang"));
+    let launch = LaunchOptions {
+        max_runtime: Some(std::time::Duration::from_secs(1)),
+        ..LaunchOptions::default()
+    };
+
+    let started = std::time::Instant::now();
+    let outcome =
+        run_ani_cli_continue(&item, &item.last_episode, &launch).expect("launch should complete");
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert!(!outcome.success, "timed out playback should not succeed");
+    assert!(outcome.final_episode.is_none());
+    assert!(
+        outcome
+            .failure_detail
+            .as_deref()
+            .unwrap_or_default()
+            .contains("timed out after 1s"),
+        "unexpected failure detail: {:?}",
+        outcome.failure_detail
+    );
+}
+
 #[cfg(unix)]
 #[test]
 fn integration_replay_updates_progress_with_fake_continue() {
@@ -844,7 +3802,7 @@ fn integration_replay_updates_progress_with_fake_continue() {
     let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("replay_success"));
 
-    run_replay(&db).expect("replay command should complete");
+    run_replay(&db, &LaunchOptions::default()).expect("replay command should complete");
 
     let last_seen = db
         .last_seen()
@@ -879,7 +3837,8 @@ fn integration_select_updates_progress_with_override_without_network() {
     let _select_title = ScopedEnvVar::set("ANITRACK_FAKE_TITLE", OsStr::new("Show One"));
     let _select_episode = ScopedEnvVar::set("ANITRACK_FAKE_EPISODE", OsStr::new("2"));
 
-    let outcome = run_ani_cli_select(&item).expect("select action should run");
+    let outcome =
+        run_ani_cli_select(&item, &LaunchOptions::default()).expect("select action should run");
     assert!(outcome.success, "select action should report success");
     let updated_ep = outcome
         .final_episode
@@ -906,13 +3865,16 @@ fn integration_previous_updates_progress_when_fake_continue_succeeds() {
         title: "Show One".to_string(),
         last_episode: "3".to_string(),
         last_seen_at: "2026-02-27T00:00:00+00:00".to_string(),
+        first_seen_at: "2026-02-27T00:00:00+00:00".to_string(),
+        ..crate::db::SeenEntry::default()
     };
     let episodes = vec!["1".to_string(), "2".to_string(), "3".to_string()];
 
     let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("previous_success"));
 
-    let outcome = run_ani_cli_previous(&item, Some(&episodes)).expect("previous action should run");
+    let outcome = run_ani_cli_previous(&item, Some(&episodes), &LaunchOptions::default())
+        .expect("previous action should run");
     assert!(outcome.success, "previous action should report success");
     let updated_ep = outcome
         .final_episode
@@ -941,8 +3903,8 @@ fn integration_previous_keeps_progress_when_no_previous_available() {
         .expect("entry should exist");
     let episodes = vec!["0".to_string(), "1".to_string(), "2".to_string()];
 
-    let err =
-        run_ani_cli_previous(&item, Some(&episodes)).expect_err("no previous should return error");
+    let err = run_ani_cli_previous(&item, Some(&episodes), &LaunchOptions::default())
+        .expect_err("no previous should return error");
     assert!(
         err.to_string().contains("no previous episode available"),
         "unexpected error: {err}"
@@ -973,7 +3935,8 @@ fn integration_previous_keeps_progress_when_playback_fails() {
     let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("previous_fail"));
 
-    let outcome = run_ani_cli_previous(&item, Some(&episodes)).expect("previous action should run");
+    let outcome = run_ani_cli_previous(&item, Some(&episodes), &LaunchOptions::default())
+        .expect("previous action should run");
     assert!(!outcome.success, "previous action should report failure");
     assert!(outcome.final_episode.is_none());
     assert!(
@@ -1059,7 +4022,7 @@ fn integration_start_records_watch_progress_with_fake_ani_cli_windows() {
     let _hist = ScopedEnvVar::set("ANI_CLI_HIST_DIR", hist_dir.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("start_success"));
 
-    run_start(&db).expect("start command should succeed");
+    run_start(&db, &LaunchOptions::default(), false).expect("start command should succeed");
 
     let last_seen = db
         .last_seen()
@@ -1083,7 +4046,7 @@ fn integration_next_updates_progress_when_fake_continue_succeeds_windows() {
     let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("next_success"));
 
-    run_next(&db).expect("next command should complete");
+    run_next(&db, &LaunchOptions::default()).expect("next command should complete");
 
     let last_seen = db
         .last_seen()
@@ -1110,7 +4073,8 @@ fn integration_previous_reports_failure_detail_when_playback_fails_windows() {
     let _bin = ScopedEnvVar::set("ANI_TRACK_ANI_CLI_BIN", fake_ani_cli.as_os_str());
     let _mode = ScopedEnvVar::set("ANITRACK_FAKE_MODE", OsStr::new("previous_fail"));
 
-    let outcome = run_ani_cli_previous(&item, Some(&episodes)).expect("previous action should run");
+    let outcome = run_ani_cli_previous(&item, Some(&episodes), &LaunchOptions::default())
+        .expect("previous action should run");
     assert!(!outcome.success, "previous action should report failure");
     assert!(outcome.final_episode.is_none());
     assert!(
@@ -1129,3 +4093,173 @@ fn integration_previous_reports_failure_detail_when_playback_fails_windows() {
         .expect("entry should exist");
     assert_eq!(last_seen.last_episode, "3");
 }
+
+#[test]
+fn verify_entries_classifies_each_entry_in_order() {
+    let items = vec![
+        render_entry("by-id", "By Id", "1"),
+        render_entry("by-title", "By Title", "1"),
+        render_entry("missing", "Missing", "1"),
+    ];
+    let results = verify_entries(&items, 2, |item| match item.ani_id.as_str() {
+        "by-id" => SelectNthResolution {
+            index: Some(1),
+            attempts: 1,
+            matched_mode: Some("sub".to_string()),
+            ..SelectNthResolution::default()
+        },
+        "by-title" => SelectNthResolution {
+            index: Some(3),
+            title_match: Some("By Title (TV)".to_string()),
+            attempts: 2,
+            matched_mode: Some("dub".to_string()),
+            ..SelectNthResolution::default()
+        },
+        _ => SelectNthResolution {
+            attempts: 4,
+            warnings: vec!["show search request failed".to_string()],
+            ..SelectNthResolution::default()
+        },
+    });
+
+    let outcomes = results
+        .iter()
+        .map(|(outcome, _)| outcome.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outcomes,
+        [
+            VerifyOutcome::ById,
+            VerifyOutcome::ByTitle("By Title (TV)".to_string()),
+            VerifyOutcome::Failed("no match after 4 search attempt(s)".to_string()),
+        ]
+    );
+    assert_eq!(results[2].1, ["show search request failed"]);
+    // A concurrency of zero still runs the lookups.
+    assert_eq!(
+        verify_entries(&items, 0, |_| SelectNthResolution::default()).len(),
+        3
+    );
+}
+
+#[test]
+fn table_exports_share_columns_and_escape_their_separators() {
+    let mut movie = render_entry("movie-1", "Perfect Blue", "1");
+    movie.kind = crate::db::EntryKind::Movie;
+    movie.total_eps_override = Some(1);
+    let items = vec![
+        render_entry("show-1", "Frieren (28 episodes)", "07"),
+        render_entry("show-2", "Fate/Zero, \"Part | 2\"", "3"),
+        movie,
+    ];
+
+    let csv = export_csv(&items);
+    let csv_lines = csv.split("\r\n").collect::<Vec<_>>();
+    assert_eq!(csv_lines[0], "Title,Episode,Progress,Last Seen");
+    assert!(csv_lines[1].starts_with("Frieren,7,7/28,"));
+    assert!(csv_lines[2].starts_with("\"Fate/Zero, \"\"Part | 2\"\"\",3,-,"));
+    assert!(csv_lines[3].starts_with("Perfect Blue,1,1/1,"));
+    assert_eq!(csv_lines.len(), 5, "trailing CRLF after the last row");
+
+    let markdown = export_markdown(&items);
+    let md_lines = markdown.lines().collect::<Vec<_>>();
+    assert_eq!(md_lines[0], "| Title | Episode | Progress | Last Seen |");
+    assert_eq!(md_lines[1], "| --- | --- | --- | --- |");
+    assert!(md_lines[2].starts_with("| Frieren | 7 | 7/28 | "));
+    assert!(md_lines[3].starts_with("| Fate/Zero, \"Part \\| 2\" | 3 | - | "));
+    assert_eq!(md_lines.len(), 5);
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn export_history_round_trips_through_the_history_reader() {
+    let sandbox = TestSandbox::new("export-history");
+    let mut latest = render_entry("show-1", "Frieren (28 episodes)", "07");
+    latest.last_seen_at = "2026-03-02T00:00:00+00:00".to_string();
+    let mut oldest = render_entry("show-0", "Episode Zero Show", "0");
+    oldest.last_seen_at = "2026-01-01T00:00:00+00:00".to_string();
+    let mut tabbed = render_entry("show-2", "Tabbed\tTitle", "12.5");
+    tabbed.last_seen_at = "2026-02-01T00:00:00+00:00".to_string();
+    let items = vec![latest, oldest, tabbed];
+
+    let history = export_history(&items);
+    assert_eq!(
+        history,
+        "0\tshow-0\tEpisode Zero Show\n12.5\tshow-2\tTabbed Title\n07\tshow-1\tFrieren (28 episodes)\n"
+    );
+
+    let path = sandbox.root.join("ani-hsts");
+    write_history_file(&path, &history, false).expect("history should be written");
+    let read = read_hist_map(&path);
+    assert_eq!(read.skipped_lines, 0);
+    let round_tripped = read
+        .ordered_entries
+        .iter()
+        .map(|entry| (entry.ep.as_str(), entry.id.as_str(), entry.title.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        round_tripped,
+        [
+            ("0", "show-0", "Episode Zero Show"),
+            ("12.5", "show-2", "Tabbed Title"),
+            ("07", "show-1", "Frieren (28 episodes)"),
+        ]
+    );
+    assert_eq!(find_untracked_history(&[], &read.ordered_entries).len(), 3);
+    assert!(export_history(&[]).is_empty());
+
+    // An existing file, such as ani-cli's own history, is only replaced on request.
+    let err = write_history_file(&path, "1\tother\tOther\n", false)
+        .expect_err("an existing file should be refused");
+    assert!(err.to_string().contains("--overwrite"));
+    assert_eq!(
+        fs::read_to_string(&path).expect("history should remain"),
+        history
+    );
+    write_history_file(&path, "1\tother\tOther\n", true).expect("overwrite should succeed");
+    assert_eq!(
+        fs::read_to_string(&path).expect("history should be replaced"),
+        "1\tother\tOther\n"
+    );
+}
+
+#[test]
+fn progress_hook_argv_substitutes_each_argument_without_rescanning() {
+    let template = [
+        "anilist-sync",
+        "--id={ani_id}",
+        "{title}",
+        "ep:{episode}",
+        "{other}",
+        "{external_provider}:{external_id}",
+    ]
+    .map(String::from);
+    let mut item = render_entry("show-1", "Tricky; rm -rf {episode}", "11");
+    let argv = progress_hook_argv(&template, &item, "12");
+    assert_eq!(
+        argv,
+        [
+            "anilist-sync",
+            "--id=show-1",
+            // The title stays one argument and its braces are not filled in again.
+            "Tricky; rm -rf {episode}",
+            "ep:12",
+            "{other}",
+            // An unlinked entry fills the external placeholders with nothing.
+            ":",
+        ]
+    );
+
+    item.external_provider = Some("anilist".to_string());
+    item.external_id = Some("21".to_string());
+    let argv = progress_hook_argv(&template, &item, "12");
+    assert_eq!(argv[5], "anilist:21");
+}
+
+#[test]
+fn spawn_hook_reports_a_missing_program() {
+    let err = spawn_hook(&["anitrack-hook-that-does-not-exist".to_string()])
+        .expect_err("missing program should fail to start");
+    assert!(err.to_string().contains("failed to start"));
+    spawn_hook(&[]).expect("an empty hook is a no-op");
+}
@@ -1,5 +1,5 @@
 use std::env;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use serde_json::Value;
 
@@ -10,9 +10,41 @@ use crate::http::get_text_with_retries;
 #[derive(Debug, Clone, Default)]
 pub(crate) struct SelectNthResolution {
     pub(crate) index: Option<u32>,
+    /// Search result title when `index` came from title matching rather than an exact id match.
+    pub(crate) title_match: Option<String>,
     pub(crate) warnings: Vec<String>,
+    /// Search requests made before resolving or giving up.
+    pub(crate) attempts: u32,
+    /// Translation mode (`sub`/`dub`) whose search results produced `index`.
+    pub(crate) matched_mode: Option<String>,
+    /// Search query whose results produced `index`.
+    pub(crate) matched_query: Option<String>,
 }
 
+impl SelectNthResolution {
+    /// One-line explanation of how the index was (or was not) found.
+    pub(crate) fn summary(&self) -> String {
+        let attempts = format!("{} search attempt(s)", self.attempts);
+        match (&self.matched_mode, self.index) {
+            (Some(mode), Some(_)) => format!("matched in {mode} results after {attempts}"),
+            _ => format!("no match after {attempts}"),
+        }
+    }
+}
+
+/// Overall time allowed for resolving a show's search position, unless overridden by
+/// `ANI_TRACK_SELECT_BUDGET_SECS`.
+const DEFAULT_SELECT_BUDGET: Duration = Duration::from_secs(15);
+
+/// Results ani-cli itself lists per search; `-S` cannot pick a position past it.
+const ANI_CLI_PAGE_SIZE: u32 = 40;
+
+/// Results requested per show search, unless overridden by `ANI_TRACK_SEARCH_LIMIT`.
+const DEFAULT_SEARCH_LIMIT: u32 = ANI_CLI_PAGE_SIZE;
+
+/// Accepted range for `ANI_TRACK_SEARCH_LIMIT`.
+const SEARCH_LIMIT_RANGE: std::ops::RangeInclusive<u32> = 1..=100;
+
 #[derive(Debug, Clone, Default)]
 pub(crate) struct SearchEntriesFetchOutcome {
     pub(crate) entries: Option<Vec<SearchResultEntry>>,
@@ -26,49 +58,221 @@ pub(crate) fn resolve_select_nth_for_item_with_diagnostics(
     if let Some(override_index) = resolve_select_nth_test_override() {
         return SelectNthResolution {
             index: Some(override_index),
-            warnings: Vec::new(),
+            ..SelectNthResolution::default()
         };
     }
 
-    let cleaned_title = sanitize_title_for_search(&item.title);
-    let raw_title = item.title.trim().to_string();
-    let queries = if cleaned_title == raw_title {
-        vec![cleaned_title]
-    } else {
-        vec![cleaned_title, raw_title]
-    };
-
     let env_mode = env::var("ANI_CLI_MODE").unwrap_or_else(|_| "sub".to_string());
-    let mut modes = vec![env_mode, "sub".to_string(), "dub".to_string()];
-    modes.dedup();
-    let mut warnings = Vec::new();
-
-    for query in queries {
-        for mode in &modes {
-            let fetch_outcome = fetch_search_result_entries_with_diagnostics(&query, mode);
-            if let Some(warning) = fetch_outcome.warning {
-                warnings.push(warning);
-            }
-            let Some(entries) = fetch_outcome.entries else {
-                continue;
-            };
-            if let Some(index) = find_select_nth_index_by_id(&entries, &item.ani_id) {
-                return SelectNthResolution {
-                    index: Some(index),
-                    warnings,
-                };
-            }
-            if let Some(index) = find_select_nth_index_by_title(&entries, &item.title) {
-                return SelectNthResolution {
-                    index: Some(index),
-                    warnings,
-                };
-            }
+    let plan = select_search_plan(&item.title, &env_mode);
+    let (budget, budget_warning) = select_budget_from_env();
+    let (limit, limit_warning) = search_limit_from_env();
+
+    let mut resolution = resolve_select_nth_with(item, &plan, budget, |query, mode| {
+        fetch_search_result_entries_with_diagnostics(query, mode, limit)
+    });
+    resolution
+        .warnings
+        .splice(0..0, budget_warning.into_iter().chain(limit_warning));
+    resolution
+}
+
+/// The distinct `(query, mode)` searches worth trying, in order: the cleaned title before the raw
+/// one, and `mode` (ani-cli's current mode) before the remaining of `sub`/`dub`.
+pub(crate) fn select_search_plan(title: &str, mode: &str) -> Vec<(String, String)> {
+    let cleaned_title = sanitize_title_for_search(title);
+    let raw_title = title.trim().to_string();
+    let mut queries = vec![cleaned_title];
+    if !queries.contains(&raw_title) {
+        queries.push(raw_title);
+    }
+
+    let mut modes: Vec<String> = Vec::new();
+    for mode in [mode.trim().to_ascii_lowercase().as_str(), "sub", "dub"] {
+        if !mode.is_empty() && !modes.iter().any(|seen| seen == mode) {
+            modes.push(mode.to_string());
+        }
+    }
+
+    queries
+        .iter()
+        .flat_map(|query| modes.iter().map(|mode| (query.clone(), mode.clone())))
+        .collect()
+}
+
+/// The search time budget from `ANI_TRACK_SELECT_BUDGET_SECS`, with a warning when the value is
+/// invalid and the default is used instead.
+pub(crate) fn select_budget_from_env() -> (Duration, Option<String>) {
+    match env::var("ANI_TRACK_SELECT_BUDGET_SECS") {
+        Ok(raw) => match parse_select_budget(&raw) {
+            Some(budget) => (budget, None),
+            None => (
+                DEFAULT_SELECT_BUDGET,
+                Some(format!(
+                    "ignoring invalid ANI_TRACK_SELECT_BUDGET_SECS `{raw}` (expected whole seconds > 0)"
+                )),
+            ),
+        },
+        Err(_) => (DEFAULT_SELECT_BUDGET, None),
+    }
+}
+
+/// The number of results each show search requests, from `ANI_TRACK_SEARCH_LIMIT`, with a warning
+/// when the value is invalid and the default is used instead.
+pub(crate) fn search_limit_from_env() -> (u32, Option<String>) {
+    match env::var("ANI_TRACK_SEARCH_LIMIT") {
+        Ok(raw) => match parse_search_limit(&raw) {
+            Some(limit) => (limit, None),
+            None => (
+                DEFAULT_SEARCH_LIMIT,
+                Some(format!(
+                    "ignoring invalid ANI_TRACK_SEARCH_LIMIT `{raw}` (expected a whole number from {} to {})",
+                    SEARCH_LIMIT_RANGE.start(),
+                    SEARCH_LIMIT_RANGE.end()
+                )),
+            ),
+        },
+        Err(_) => (DEFAULT_SEARCH_LIMIT, None),
+    }
+}
+
+pub(crate) fn parse_search_limit(raw: &str) -> Option<u32> {
+    let limit = raw.trim().parse::<u32>().ok()?;
+    SEARCH_LIMIT_RANGE.contains(&limit).then_some(limit)
+}
+
+pub(crate) fn parse_select_budget(raw: &str) -> Option<Duration> {
+    let secs = raw.trim().parse::<u64>().ok()?;
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Runs `plan` until a search result matches `item` by id (preferred) or title. Once `budget`
+/// has elapsed no further searches are started; the first search always runs.
+pub(crate) fn resolve_select_nth_with<F>(
+    item: &SeenEntry,
+    plan: &[(String, String)],
+    budget: Duration,
+    mut fetch: F,
+) -> SelectNthResolution
+where
+    F: FnMut(&str, &str) -> SearchEntriesFetchOutcome,
+{
+    let started = Instant::now();
+    let mut resolution = SelectNthResolution::default();
+
+    for (query, mode) in plan {
+        if resolution.attempts > 0 && started.elapsed() >= budget {
+            resolution.warnings.push(format!(
+                "stopped show search after {} of {} attempt(s): {}s time budget used up (raise ANI_TRACK_SELECT_BUDGET_SECS to search longer)",
+                resolution.attempts,
+                plan.len(),
+                budget.as_secs()
+            ));
+            break;
+        }
+        resolution.attempts += 1;
+        let fetch_outcome = fetch(query, mode);
+        if let Some(warning) = fetch_outcome.warning {
+            resolution.warnings.push(warning);
         }
+        let Some(entries) = fetch_outcome.entries else {
+            continue;
+        };
+        let matched = match find_select_nth_index_by_id(&entries, &item.ani_id) {
+            Some(index) => Some((index, None)),
+            None => find_select_nth_index_by_title(&entries, &item.title).map(|index| {
+                let title = entries
+                    .get(index as usize - 1)
+                    .map(|entry| entry.title.clone());
+                (index, title)
+            }),
+        };
+        let Some((index, title_match)) = matched else {
+            continue;
+        };
+        // A larger `ANI_TRACK_SEARCH_LIMIT` can find the show further down than ani-cli lists.
+        if index > ANI_CLI_PAGE_SIZE {
+            resolution.warnings.push(format!(
+                "found at position {index} of the {mode} results for `{query}`, past the {ANI_CLI_PAGE_SIZE} results ani-cli lists, so `ani-cli -S` cannot pick it"
+            ));
+            continue;
+        }
+        resolution.index = Some(index);
+        resolution.title_match = title_match;
+        resolution.matched_mode = Some(mode.clone());
+        resolution.matched_query = Some(query.clone());
+        return resolution;
+    }
+    resolution
+}
+
+/// Whether a `-S` index resolved earlier still points at the same show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SelectNthCheck {
+    /// The result at the index is still the show.
+    Valid,
+    /// The search could not be re-run, so the index is kept as is; holds the warning.
+    Unverified(String),
+    /// The results shifted (or shrank past the index); holds why.
+    Stale(String),
+}
+
+/// Re-checks `resolved` against a fresh search right before launch, for when time has passed
+/// since it was resolved (e.g. behind a confirmation prompt) and allanime may have reordered its
+/// results. Skips the network when `resolved` did not come from a search.
+pub(crate) fn revalidate_select_nth_for_item(
+    item: &SeenEntry,
+    resolved: &SelectNthResolution,
+) -> SelectNthCheck {
+    #[cfg(test)]
+    if resolve_select_nth_test_override().is_some() {
+        return SelectNthCheck::Valid;
     }
-    SelectNthResolution {
-        index: None,
-        warnings,
+    let limit = search_limit_from_env().0;
+    revalidate_select_nth_with(item, resolved, |query, mode| {
+        fetch_search_result_entries_with_diagnostics(query, mode, limit)
+    })
+}
+
+/// Re-runs the search that produced `resolved` and checks the result at its index: it must carry
+/// the item's id, or for a title match the same title as before.
+pub(crate) fn revalidate_select_nth_with<F>(
+    item: &SeenEntry,
+    resolved: &SelectNthResolution,
+    mut fetch: F,
+) -> SelectNthCheck
+where
+    F: FnMut(&str, &str) -> SearchEntriesFetchOutcome,
+{
+    let (Some(index), Some(query), Some(mode)) = (
+        resolved.index,
+        &resolved.matched_query,
+        &resolved.matched_mode,
+    ) else {
+        return SelectNthCheck::Valid;
+    };
+    let fetch_outcome = fetch(query, mode);
+    let Some(entries) = fetch_outcome.entries else {
+        return SelectNthCheck::Unverified(fetch_outcome.warning.unwrap_or_else(|| {
+            format!("show search for query={query:?} mode={mode} returned no results")
+        }));
+    };
+    let Some(entry) = (index as usize)
+        .checked_sub(1)
+        .and_then(|idx| entries.get(idx))
+    else {
+        return SelectNthCheck::Stale(format!(
+            "search now returns {} result(s), fewer than position {index}",
+            entries.len()
+        ));
+    };
+    let still_matches = match &resolved.title_match {
+        None => entry.id == item.ani_id,
+        Some(title) => entry.title == *title,
+    };
+    if still_matches {
+        SelectNthCheck::Valid
+    } else {
+        SelectNthCheck::Stale(format!("position {index} is now {:?} instead", entry.title))
     }
 }
 
@@ -79,15 +283,17 @@ fn resolve_select_nth_test_override() -> Option<u32> {
     (parsed > 0).then_some(parsed)
 }
 
+/// Searches allanime for `query` in `mode`, asking for at most `limit` results.
 pub(crate) fn fetch_search_result_entries_with_diagnostics(
     query: &str,
     mode: &str,
+    limit: u32,
 ) -> SearchEntriesFetchOutcome {
     let gql = "query( $search: SearchInput $limit: Int $page: Int $translationType: VaildTranslationTypeEnumType $countryOrigin: VaildCountryOriginEnumType ) { shows( search: $search limit: $limit page: $page translationType: $translationType countryOrigin: $countryOrigin ) { edges { _id name availableEpisodes __typename } }}";
     let escaped_query = json_escape(query);
     let escaped_mode = json_escape(mode);
     let variables = format!(
-        "{{\"search\":{{\"allowAdult\":false,\"allowUnknown\":false,\"query\":\"{escaped_query}\"}},\"limit\":40,\"page\":1,\"translationType\":\"{escaped_mode}\",\"countryOrigin\":\"ALL\"}}"
+        "{{\"search\":{{\"allowAdult\":false,\"allowUnknown\":false,\"query\":\"{escaped_query}\"}},\"limit\":{limit},\"page\":1,\"translationType\":\"{escaped_mode}\",\"countryOrigin\":\"ALL\"}}"
     );
     let query_params = vec![
         ("variables".to_string(), variables),
@@ -7,17 +7,21 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 #[cfg(target_os = "linux")]
 use std::process::Command as ProcessCommand;
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use super::super::episode::parse_title_and_total_eps;
 use super::{HistEntry, HistFileSig};
 
 #[derive(Default)]
-pub(super) struct HistRead {
-    pub(super) entries: HashMap<String, HistEntry>,
-    pub(super) ordered_entries: Vec<HistEntry>,
-    pub(super) warnings: Vec<String>,
+pub(crate) struct HistRead {
+    pub(crate) entries: HashMap<String, HistEntry>,
+    pub(crate) ordered_entries: Vec<HistEntry>,
+    /// Non-empty lines that did not parse as history entries.
+    pub(crate) skipped_lines: usize,
+    pub(crate) warnings: Vec<String>,
 }
 
-pub(super) fn read_hist_map(path: &Path) -> HistRead {
+pub(crate) fn read_hist_map(path: &Path) -> HistRead {
     if !path.exists() {
         return HistRead::default();
     }
@@ -28,6 +32,7 @@ pub(super) fn read_hist_map(path: &Path) -> HistRead {
             return HistRead {
                 entries: HashMap::new(),
                 ordered_entries: Vec::new(),
+                skipped_lines: 0,
                 warnings: vec![format!(
                     "failed to read ani-cli history at {}: {}",
                     path.display(),
@@ -37,9 +42,19 @@ pub(super) fn read_hist_map(path: &Path) -> HistRead {
         }
     };
 
-    let (entries, ordered_entries, skipped_lines) = parse_hist_map(&raw);
+    let (entries, ordered_entries, skipped_lines) =
+        parse_hist_map(&raw, detect_title_first_from_env());
     let mut warnings = Vec::new();
-    if skipped_lines > 0 {
+    let total_lines = ordered_entries.len() + skipped_lines;
+    if history_format_looks_changed(total_lines, skipped_lines) {
+        warnings.push(format!(
+            "{skipped_lines} of {total_lines} line(s) in {} are not `episode<TAB>id<TAB>title`; \
+             ani-cli may have changed its history format, so progress cannot be detected. \
+             Set ANI_TRACK_HIST_TITLE_FIRST=1 if your ani-cli writes the title before the id, \
+             or record progress with `anitrack continue-from` or `anitrack import`",
+            path.display()
+        ));
+    } else if skipped_lines > 0 {
         warnings.push(format!(
             "ignored {skipped_lines} malformed line(s) in {}",
             path.display()
@@ -49,10 +64,32 @@ pub(super) fn read_hist_map(path: &Path) -> HistRead {
     HistRead {
         entries,
         ordered_entries,
+        skipped_lines,
         warnings,
     }
 }
 
+/// Why a run recorded nothing when the history has no entries at all afterwards: ani-cli either
+/// wrote no history file or left it empty, which usually means it logs somewhere else.
+pub(crate) fn empty_history_message(path: &Path, exists: bool) -> String {
+    let state = if exists { "is empty" } else { "does not exist" };
+    format!(
+        "No watch recorded: the ani-cli history at {} {state} after this run. ani-cli may be \
+         writing its history elsewhere; set ANI_CLI_HIST_DIR to that directory (check a candidate \
+         with `anitrack dump-history --hist-dir <DIR>`).",
+        path.display()
+    )
+}
+
+/// Non-empty history lines needed before a mostly unparseable file is blamed on a format change
+/// rather than a few stray lines.
+const FORMAT_PROBE_MIN_LINES: usize = 3;
+
+/// Whether more than half of at least `FORMAT_PROBE_MIN_LINES` non-empty lines failed to parse.
+pub(crate) fn history_format_looks_changed(total_lines: usize, skipped_lines: usize) -> bool {
+    total_lines >= FORMAT_PROBE_MIN_LINES && skipped_lines * 2 > total_lines
+}
+
 pub(crate) fn ani_cli_histfile() -> PathBuf {
     if let Ok(custom) = env::var("ANI_CLI_HIST_DIR") {
         return PathBuf::from(custom).join("ani-hsts");
@@ -70,12 +107,21 @@ pub(crate) fn ani_cli_histfile() -> PathBuf {
     state_home.join("ani-cli").join("ani-hsts")
 }
 
-pub(crate) fn parse_hist_map(raw: &str) -> (HashMap<String, HistEntry>, Vec<HistEntry>, usize) {
+/// Whether `ANI_TRACK_HIST_TITLE_FIRST` opts into detecting `ep<TAB>title<TAB>id` history lines.
+pub(crate) fn detect_title_first_from_env() -> bool {
+    env::var("ANI_TRACK_HIST_TITLE_FIRST")
+        .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"))
+}
+
+pub(crate) fn parse_hist_map(
+    raw: &str,
+    detect_title_first: bool,
+) -> (HashMap<String, HistEntry>, Vec<HistEntry>, usize) {
     let mut map = HashMap::new();
     let mut ordered_entries = Vec::new();
     let mut skipped_lines = 0;
     for line in raw.lines() {
-        match parse_hist_line(line) {
+        match parse_hist_line(line, detect_title_first) {
             Some(entry) => {
                 ordered_entries.push(entry.clone());
                 map.insert(entry.id.clone(), entry);
@@ -87,7 +133,13 @@ pub(crate) fn parse_hist_map(raw: &str) -> (HashMap<String, HistEntry>, Vec<Hist
     (map, ordered_entries, skipped_lines)
 }
 
-pub(crate) fn parse_hist_line(line: &str) -> Option<HistEntry> {
+/// Parses one history line as `ep<TAB>id<TAB>title` (or space-separated).
+///
+/// With `detect_title_first`, a tab-separated line whose second field does not look like an id
+/// while the third does is read as `ep<TAB>title<TAB>id`, the order some forks write. This is a
+/// heuristic: single-word titles made only of letters/digits look like ids, so such lines keep
+/// the standard order, and space-separated lines are never swapped.
+pub(crate) fn parse_hist_line(line: &str, detect_title_first: bool) -> Option<HistEntry> {
     let trimmed = line.trim();
     if trimmed.is_empty() {
         return None;
@@ -96,11 +148,14 @@ pub(crate) fn parse_hist_line(line: &str) -> Option<HistEntry> {
     if trimmed.contains('\t') {
         let mut parts = trimmed.splitn(3, '\t');
         let ep = parts.next()?.trim();
-        let id = parts.next()?.trim();
-        let title = parts.next()?.trim();
+        let mut id = parts.next()?.trim();
+        let mut title = parts.next()?.trim();
         if ep.is_empty() || id.is_empty() || title.is_empty() {
             return None;
         }
+        if detect_title_first && !looks_like_hist_id(id) && looks_like_hist_id(title) {
+            std::mem::swap(&mut id, &mut title);
+        }
         return Some(HistEntry {
             ep: ep.to_string(),
             id: id.to_string(),
@@ -123,6 +178,14 @@ pub(crate) fn parse_hist_line(line: &str) -> Option<HistEntry> {
     })
 }
 
+/// Show ids are single tokens of ASCII letters, digits, `-` or `_`.
+fn looks_like_hist_id(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
 pub(crate) fn append_history_warnings(message: &mut String, warnings: &[String]) {
     for warning in warnings {
         message.push_str("\nWarning: ");
@@ -130,9 +193,38 @@ pub(crate) fn append_history_warnings(message: &mut String, warnings: &[String])
     }
 }
 
+static REQUIRE_EPISODE_CHANGE: AtomicBool = AtomicBool::new(false);
+
+/// Whether a run only counts as a watch when it moves a show already in the history to another
+/// episode, for the rest of the process.
+pub(crate) fn set_require_episode_change(enabled: bool) {
+    REQUIRE_EPISODE_CHANGE.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn require_episode_change() -> bool {
+    REQUIRE_EPISODE_CHANGE.load(Ordering::Relaxed)
+}
+
+/// Whether `current` is a meaningful change from the show's line before the run: a show new to
+/// the history always is; otherwise its episode must differ, or with `require_episode_change`
+/// off, its title may differ instead.
+fn is_meaningful_change(
+    before: &HashMap<String, HistEntry>,
+    current: &HistEntry,
+    require_episode_change: bool,
+) -> bool {
+    match before.get(&current.id) {
+        None => true,
+        Some(prev) => {
+            prev.ep != current.ep || (!require_episode_change && prev.title != current.title)
+        }
+    }
+}
+
 pub(crate) fn detect_changed_latest(
     before: &HashMap<String, HistEntry>,
     after_ordered: &[HistEntry],
+    require_episode_change: bool,
 ) -> Option<HistEntry> {
     // Walk from the most recent history lines to pick the last meaningful change deterministically.
     let mut seen_ids = HashSet::new();
@@ -140,12 +232,8 @@ pub(crate) fn detect_changed_latest(
         if !seen_ids.insert(current.id.as_str()) {
             continue;
         }
-        match before.get(&current.id) {
-            None => return Some(current.clone()),
-            Some(prev) if prev.ep != current.ep || prev.title != current.title => {
-                return Some(current.clone());
-            }
-            _ => {}
+        if is_meaningful_change(before, current, require_episode_change) {
+            return Some(current.clone());
         }
     }
     None
@@ -170,37 +258,81 @@ pub(crate) fn added_entries(
     added
 }
 
+/// Episodes of show `id` on history lines added during a run, in file order and without
+/// repeats: a run that plays several episodes back to back can leave a line for each.
+pub(crate) fn added_episodes_for(
+    before_ordered: &[HistEntry],
+    after_ordered: &[HistEntry],
+    id: &str,
+) -> Vec<String> {
+    let mut episodes = Vec::new();
+    for entry in added_entries(before_ordered, after_ordered) {
+        if entry.id == id && !episodes.contains(&entry.ep) {
+            episodes.push(entry.ep);
+        }
+    }
+    episodes
+}
+
 pub(crate) fn detect_latest_added_entry(
     before: &HashMap<String, HistEntry>,
     before_ordered: &[HistEntry],
     after_ordered: &[HistEntry],
+    require_episode_change: bool,
 ) -> Option<HistEntry> {
     let added = added_entries(before_ordered, after_ordered);
     if added.is_empty() {
         return None;
     }
 
-    // Prefer the newest meaningful added line. If added lines are all duplicates,
-    // use the newest duplicate so same-episode replays still register.
-    for current in added.iter().rev() {
-        match before.get(&current.id) {
-            None => return Some(current.clone()),
-            Some(prev) if prev.ep != current.ep || prev.title != current.title => {
-                return Some(current.clone());
-            }
-            _ => {}
-        };
+    // Prefer the newest meaningful added line.
+    if let Some(current) = added
+        .iter()
+        .rev()
+        .find(|current| is_meaningful_change(before, current, require_episode_change))
+    {
+        return Some(current.clone());
+    }
+    // If added lines are all duplicates, use the newest duplicate so same-episode replays still
+    // register, unless a re-opened episode should not count.
+    if require_episode_change {
+        None
+    } else {
+        added.last().cloned()
     }
-    added.last().cloned()
 }
 
+/// The show and episode a run watched, judged from the history before and after it. With
+/// `require_episode_change`, a line that only repeats a show's episode (or only changes its
+/// title) is treated as an accidental re-open, not a watch.
 pub(crate) fn detect_latest_watch_event(
     before: &HashMap<String, HistEntry>,
     before_ordered: &[HistEntry],
     after_ordered: &[HistEntry],
+    require_episode_change: bool,
 ) -> Option<HistEntry> {
-    detect_latest_added_entry(before, before_ordered, after_ordered)
-        .or_else(|| detect_changed_latest(before, after_ordered))
+    detect_latest_added_entry(
+        before,
+        before_ordered,
+        after_ordered,
+        require_episode_change,
+    )
+    .or_else(|| detect_changed_latest(before, after_ordered, require_episode_change))
+}
+
+/// Whether a history line for the same show changed in a way consistent with switching between
+/// sub and dub: allanime uses one id for both, but ani-cli writes the mode's own episode count
+/// into the title, so only the `(N episodes)` suffix differs.
+pub(crate) fn looks_like_mode_switch(prev: &HistEntry, current: &HistEntry) -> bool {
+    if prev.id != current.id {
+        return false;
+    }
+    let (prev_title, prev_total) = parse_title_and_total_eps(&prev.title);
+    let (current_title, current_total) = parse_title_and_total_eps(&current.title);
+    prev_title == current_title
+        && prev_total.is_some()
+        && current_total.is_some()
+        && prev_total != current_total
 }
 
 pub(crate) fn read_histfile_sig(path: &Path) -> Option<HistFileSig> {
@@ -293,13 +425,46 @@ pub(crate) fn detect_log_matched_entry(
     None
 }
 
+/// Whether journalctl's stderr says the user cannot read the system journal, where ani-cli's
+/// `logger` lines end up. journalctl often still exits successfully in that case, with only a
+/// hint on stderr and no ani-cli lines on stdout.
+#[cfg(any(target_os = "linux", test))]
+pub(crate) fn journal_permission_denied(stderr: &str) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    [
+        "insufficient permissions",
+        "permission denied",
+        "not seeing messages from other users",
+    ]
+    .iter()
+    .any(|needle| stderr.contains(needle))
+}
+
+#[cfg(target_os = "linux")]
+static JOURNAL_PERMISSION_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// The journal permission warning, at most once per process so repeated TUI actions do not
+/// keep repeating it.
+#[cfg(target_os = "linux")]
+fn journal_permission_warning_once() -> Option<String> {
+    if JOURNAL_PERMISSION_WARNED.swap(true, Ordering::SeqCst) {
+        return None;
+    }
+    Some(
+        "journalctl fallback limited: your user cannot read the system journal. Add it to the `systemd-journal` group (`sudo usermod -aG systemd-journal $USER`, then log in again) or set ANI_TRACK_NO_JOURNALCTL=1 to skip this scan"
+            .to_string(),
+    )
+}
+
 #[cfg(target_os = "linux")]
 pub(super) fn detect_latest_watch_event_from_logs_with_diagnostics(
     start_ns: u128,
     end_ns: u128,
     after_ordered: &[HistEntry],
 ) -> (Option<HistEntry>, Option<String>) {
-    if after_ordered.is_empty() {
+    let disabled = env::var("ANI_TRACK_NO_JOURNALCTL")
+        .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"));
+    if after_ordered.is_empty() || disabled {
         return (None, None);
     }
 
@@ -326,8 +491,12 @@ pub(super) fn detect_latest_watch_event_from_logs_with_diagnostics(
             );
         }
     };
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let permission_denied = journal_permission_denied(&stderr);
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        if permission_denied {
+            return (None, journal_permission_warning_once());
+        }
         let detail = stderr.trim();
         let warning = if detail.is_empty() {
             format!(
@@ -360,6 +529,9 @@ pub(super) fn detect_latest_watch_event_from_logs_with_diagnostics(
             return (Some(entry), None);
         }
     }
+    if permission_denied {
+        return (None, journal_permission_warning_once());
+    }
     (None, None)
 }
 
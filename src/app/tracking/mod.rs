@@ -2,12 +2,28 @@ mod api;
 mod history;
 mod playback;
 mod process;
+mod sync;
+
+use std::time::Duration;
 
 #[cfg(test)]
 pub(crate) use api::*;
+pub(crate) use api::{
+    SelectNthCheck, SelectNthResolution, normalize_title_for_match,
+    resolve_select_nth_for_item_with_diagnostics, revalidate_select_nth_for_item,
+    select_budget_from_env,
+};
 #[cfg(test)]
 pub(crate) use history::*;
+pub(crate) use history::{
+    HistRead, ani_cli_histfile, detect_title_first_from_env, read_hist_map,
+    set_require_episode_change,
+};
 pub(crate) use playback::*;
+pub(crate) use process::{interrupt_requested, watch_for_interrupt};
+#[cfg(test)]
+pub(crate) use sync::{find_untracked_history, plan_history_sync};
+pub(crate) use sync::{sync_progress_from_history, untracked_history_entries};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct HistEntry {
@@ -22,6 +38,40 @@ pub(crate) struct HistFileSig {
     pub(crate) modified_ns: u128,
 }
 
+/// Options applied to every `ani-cli` launch.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LaunchOptions {
+    pub(crate) player: Option<String>,
+    /// Terminate ani-cli (and its player) when a launch runs longer than this.
+    pub(crate) max_runtime: Option<Duration>,
+    /// Extra ani-cli flags passed through verbatim, after anitrack's own flags.
+    pub(crate) extra_args: Vec<String>,
+    /// How Replay launches the current episode.
+    pub(crate) replay_mode: ReplayMode,
+}
+
+/// How Replay gets `ani-cli` to the current episode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ReplayMode {
+    /// Seed the history with the previous episode and let `ani-cli -c` continue onto the current
+    /// one (direct play is still used when there is no previous episode).
+    #[default]
+    Continue,
+    /// Always play the current episode directly with `-e`, selecting the show by its search
+    /// position.
+    Direct,
+}
+
+impl ReplayMode {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "continue" => Some(Self::Continue),
+            "direct" => Some(Self::Direct),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct PlaybackOutcome {
     pub(crate) success: bool,
@@ -2,35 +2,48 @@ use std::env;
 use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::ExitStatus;
-use std::process::{Command as ProcessCommand, Stdio};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::process::{Child, Command as ProcessCommand, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result, anyhow};
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 
+use super::super::color::warning_label;
 use super::super::episode::{
-    fetch_episode_labels_with_diagnostics, parse_title_and_total_eps, previous_seed_episode,
-    previous_target_episode, replay_seed_episode, sanitize_title_for_search,
+    entry_title_and_total, fetch_episode_labels_with_diagnostics, first_episode_after,
+    parse_title_and_total_eps, previous_seed_episode, previous_target_episode,
+    record_watched_episode, replay_seed_episode, sanitize_title_for_search, stored_title_and_total,
 };
+use super::super::hooks::run_progress_hook;
 use super::api::resolve_select_nth_for_item_with_diagnostics;
 use super::history::{
-    ani_cli_histfile, append_history_warnings, detect_latest_watch_event,
-    detect_latest_watch_event_from_logs_with_diagnostics, history_file_touched, read_hist_map,
-    read_histfile_sig, unix_now_ns,
+    added_episodes_for, ani_cli_histfile, append_history_warnings, detect_latest_watch_event,
+    detect_latest_watch_event_from_logs_with_diagnostics, empty_history_message,
+    history_file_touched, looks_like_mode_switch, read_hist_map, read_histfile_sig,
+    require_episode_change, unix_now_ns,
 };
-use super::process::{run_interactive_cmd, with_sigint_ignored};
-use super::{PlaybackOutcome, ReplayPlan};
+use super::process::{
+    ChildExit, poll_detached, run_interactive_cmd, spawn_detached, with_sigint_ignored,
+};
+use super::{LaunchOptions, PlaybackOutcome, ReplayMode, ReplayPlan};
 use crate::db::{Database, SeenEntry};
 
 fn emit_warnings(warnings: &[String]) {
     for warning in warnings {
-        eprintln!("Warning: {warning}");
+        eprintln!("{} {warning}", warning_label());
     }
 }
 
-fn playback_failure_detail(status: &ExitStatus) -> String {
+fn playback_failure_detail(exit: &ChildExit) -> String {
+    if let Some(limit) = exit.timed_out {
+        return format!(
+            "ani-cli timed out after {}s (max runtime) and was terminated",
+            limit.as_secs()
+        );
+    }
+
+    let status = &exit.status;
     let base = if let Some(code) = status.code() {
         format!("ani-cli exited with code {code}")
     } else {
@@ -55,7 +68,10 @@ fn playback_failure_detail(status: &ExitStatus) -> String {
     }
 }
 
-pub(crate) fn run_ani_cli_search(db: &Database) -> Result<(String, Option<String>)> {
+pub(crate) fn run_ani_cli_search(
+    db: &Database,
+    launch: &LaunchOptions,
+) -> Result<(String, Option<String>)> {
     let histfile = ani_cli_histfile();
     let before_sig = read_histfile_sig(&histfile);
     let before_read = read_hist_map(&histfile);
@@ -65,15 +81,10 @@ pub(crate) fn run_ani_cli_search(db: &Database) -> Result<(String, Option<String
     let log_window_start_ns = unix_now_ns();
 
     let ani_cli_bin = resolve_ani_cli_bin();
-    let status = match with_sigint_ignored(|| {
-        let mut cmd = ProcessCommand::new(&ani_cli_bin);
-        cmd.stdin(Stdio::inherit())
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit());
-        run_interactive_cmd(cmd)
-            .with_context(|| format!("failed to launch {}", ani_cli_bin.display()))
-    }) {
-        Ok(status) => status,
+    let mut cmd = ani_cli_command(&ani_cli_bin, launch);
+    apply_extra_args(&mut cmd, launch);
+    let exit = match run_playback_cmd(cmd, &ani_cli_bin, launch) {
+        Ok(exit) => exit,
         Err(err) => {
             let mut message = format!("ani-cli failed to start: {err}. Progress unchanged.");
             append_history_warnings(&mut message, &warnings);
@@ -84,36 +95,85 @@ pub(crate) fn run_ani_cli_search(db: &Database) -> Result<(String, Option<String
     let after_read = read_hist_map(&histfile);
     let after_sig = read_histfile_sig(&histfile);
     let log_window_end_ns = unix_now_ns();
+    // No entries, no unparseable lines, and no read error: the file is missing or blank.
+    let after_history_empty = after_read.ordered_entries.is_empty()
+        && after_read.skipped_lines == 0
+        && after_read.warnings.is_empty();
     warnings.extend(after_read.warnings);
     let after_ordered = after_read.ordered_entries;
     let mut changed_id = None;
+    let require_change = require_episode_change();
     let changed =
-        detect_latest_watch_event(&before, &before_ordered, &after_ordered).or_else(|| {
-            let (entry, log_warning) = detect_latest_watch_event_from_logs_with_diagnostics(
-                log_window_start_ns,
-                log_window_end_ns,
-                &after_ordered,
-            );
-            if let Some(log_warning) = log_warning {
-                warnings.push(log_warning);
-            }
-            entry
-        });
+        detect_latest_watch_event(&before, &before_ordered, &after_ordered, require_change)
+            .or_else(|| {
+                let (entry, log_warning) = detect_latest_watch_event_from_logs_with_diagnostics(
+                    log_window_start_ns,
+                    log_window_end_ns,
+                    &after_ordered,
+                );
+                if let Some(log_warning) = log_warning {
+                    warnings.push(log_warning);
+                }
+                entry.filter(|entry| {
+                    !require_change || before.get(&entry.id).is_none_or(|prev| prev.ep != entry.ep)
+                })
+            });
     let mut message = if let Some(changed) = changed {
-        db.upsert_seen(&changed.id, &changed.title, &changed.ep)?;
-        changed_id = Some(changed.id);
-        format!(
+        let (stored_title, total_eps_override) = stored_title_and_total(&changed.title);
+        db.record_seen(&changed.id, &stored_title, total_eps_override, &changed.ep)?;
+        // Every episode this run played counts as watched, not just the one progress lands on.
+        let mut watched = added_episodes_for(&before_ordered, &after_ordered, &changed.id);
+        if !watched.contains(&changed.ep) {
+            watched.push(changed.ep.clone());
+        }
+        for episode in &watched {
+            record_watched_episode(db, &changed.id, episode)?;
+        }
+        if let Some(item) = db.get_seen(&changed.id)?
+            && let Err(err) = run_progress_hook(&item, &changed.ep)
+        {
+            warnings.push(format!("{err:#}"));
+        }
+        let mut message = format!(
             "Recorded last seen: {} | episode {}",
             changed.title, changed.ep
-        )
+        );
+        if watched.len() > 1 {
+            message.push_str(&format!(
+                " ({} episodes this run: {})",
+                watched.len(),
+                watched.join(", ")
+            ));
+        }
+        if let Some(prev) = before.get(&changed.id)
+            && looks_like_mode_switch(prev, &changed)
+            && let (Some(prev_total), Some(total)) = (
+                parse_title_and_total_eps(&prev.title).1,
+                parse_title_and_total_eps(&changed.title).1,
+            )
+        {
+            message.push_str(&format!(
+                "\nNote: episode count changed ({prev_total} -> {total}); this looks like a sub/dub switch, so progress follows the mode you just watched."
+            ));
+        }
+        changed_id = Some(changed.id);
+        message
+    } else if require_change
+        && detect_latest_watch_event(&before, &before_ordered, &after_ordered, false).is_some()
+    {
+        "Only a re-open of an episode already in history was detected; progress unchanged (ANI_TRACK_REQUIRE_EPISODE_CHANGE is set).".to_string()
+    } else if after_history_empty {
+        empty_history_message(&histfile, histfile.exists())
     } else if history_file_touched(before_sig, after_sig) && before_ordered != after_ordered {
         "History changed but no parseable watch entry was detected from this run.".to_string()
     } else {
         "No new history entry detected from this run.".to_string()
     };
 
-    if !status.success() {
-        message = format!("{message}\nani-cli exited with status: {status}");
+    if exit.timed_out.is_some() {
+        message = format!("{message}\n{}", playback_failure_detail(&exit));
+    } else if !exit.status.success() {
+        message = format!("{message}\nani-cli exited with status: {}", exit.status);
     }
 
     append_history_warnings(&mut message, &warnings);
@@ -131,10 +191,102 @@ pub(crate) fn resolve_ani_cli_bin_from_env(env_value: Option<OsString>) -> PathB
     }
 }
 
-pub(crate) fn run_ani_cli_continue(
+/// The player ani-cli will launch and where that choice comes from, for display only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResolvedPlayer {
+    pub(crate) command: String,
+    pub(crate) source: &'static str,
+}
+
+pub(crate) fn resolve_player(launch: &LaunchOptions) -> ResolvedPlayer {
+    resolve_player_from(launch.player.as_deref(), env::var("ANI_CLI_PLAYER").ok())
+}
+
+/// Mirrors the precedence ani-cli sees: `--player` (exported as `ANI_CLI_PLAYER`), then an
+/// inherited `ANI_CLI_PLAYER`, then ani-cli's own platform default.
+pub(crate) fn resolve_player_from(flag: Option<&str>, env_value: Option<String>) -> ResolvedPlayer {
+    if let Some(player) = flag.filter(|value| !value.is_empty()) {
+        return ResolvedPlayer {
+            command: player.to_string(),
+            source: "--player",
+        };
+    }
+    if let Some(player) = env_value.filter(|value| !value.is_empty()) {
+        return ResolvedPlayer {
+            command: player,
+            source: "ANI_CLI_PLAYER",
+        };
+    }
+    ResolvedPlayer {
+        command: if cfg!(target_os = "macos") {
+            "iina"
+        } else {
+            "mpv"
+        }
+        .to_string(),
+        source: "ani-cli default",
+    }
+}
+
+/// Builds the base `ani-cli` command with launch options applied.
+///
+/// The player is passed through `ANI_CLI_PLAYER` rather than `-v`: ani-cli reads that variable
+/// for every player it supports, while `-v` only switches to vlc.
+pub(crate) fn ani_cli_command(ani_cli_bin: &Path, launch: &LaunchOptions) -> ProcessCommand {
+    let mut cmd = ProcessCommand::new(ani_cli_bin);
+    if let Some(player) = launch.player.as_deref() {
+        cmd.env("ANI_CLI_PLAYER", player);
+    }
+    cmd
+}
+
+/// Appends the user's extra ani-cli flags. Callers add them after anitrack's own flags and
+/// before the title, so ani-cli never reads them as part of the search query.
+fn apply_extra_args(cmd: &mut ProcessCommand, launch: &LaunchOptions) {
+    cmd.args(&launch.extra_args);
+}
+
+/// Reminder printed before foreground playback when a show has an intro skip set.
+///
+/// ani-cli has no way to pass extra arguments to the player, so the offset cannot be applied
+/// for the user; it is shown as a note to seek past instead.
+pub(crate) fn intro_skip_note(seconds: u32) -> Option<String> {
+    (seconds > 0).then(|| {
+        format!(
+            "Intro skip: {seconds}s (seek past it in the player; ani-cli cannot pass a start time)"
+        )
+    })
+}
+
+fn print_intro_skip_note(item: &SeenEntry) {
+    if let Some(note) = intro_skip_note(item.intro_skip_seconds) {
+        println!("{note}");
+    }
+}
+
+/// Runs a playback command in the foreground with the same signal and TTY handling as search,
+/// so Ctrl-C reaches ani-cli/player instead of tearing down anitrack mid-session.
+fn run_playback_cmd(
+    mut cmd: ProcessCommand,
+    ani_cli_bin: &Path,
+    launch: &LaunchOptions,
+) -> Result<ChildExit> {
+    cmd.stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    with_sigint_ignored(|| {
+        run_interactive_cmd(cmd, launch.max_runtime)
+            .with_context(|| format!("failed to launch {}", ani_cli_bin.display()))
+    })
+}
+
+/// Builds `ani-cli -c` against a temp history holding only this show, so continue cannot pick
+/// another entry from the user's history.
+fn continue_cmd(
     item: &SeenEntry,
     stored_episode: &str,
-) -> Result<PlaybackOutcome> {
+    launch: &LaunchOptions,
+) -> Result<(ProcessCommand, TempHistDir, PathBuf)> {
     let temp_hist_dir = TempHistDir::new()?;
     let histfile = temp_hist_dir.histfile_path();
     fs::write(
@@ -149,17 +301,43 @@ pub(crate) fn run_ani_cli_continue(
     })?;
 
     let ani_cli_bin = resolve_ani_cli_bin();
-    // Use plain .status() rather than run_interactive_cmd: ani-cli -c operates non-interactively
-    // using the seeded temp history to skip the search prompt, so TTY foreground transfer is not needed.
-    let status = ProcessCommand::new(&ani_cli_bin)
-        .arg("-c")
-        .env("ANI_CLI_HIST_DIR", temp_hist_dir.path())
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status()
-        .with_context(|| format!("failed to launch {}", ani_cli_bin.display()))?;
-    let success = status.success();
+    let mut cmd = ani_cli_command(&ani_cli_bin, launch);
+    cmd.arg("-c").env("ANI_CLI_HIST_DIR", temp_hist_dir.path());
+    apply_extra_args(&mut cmd, launch);
+    Ok((cmd, temp_hist_dir, ani_cli_bin))
+}
+
+pub(crate) fn run_ani_cli_continue(
+    item: &SeenEntry,
+    stored_episode: &str,
+    launch: &LaunchOptions,
+) -> Result<PlaybackOutcome> {
+    // Progress below episode 1 may sit before the first episode (after a restart), where
+    // `ani-cli -c` has nothing to continue from; play the first episode directly instead.
+    if stored_episode
+        .trim()
+        .parse::<f64>()
+        .is_ok_and(|episode| episode < 1.0)
+    {
+        let total_hint = entry_title_and_total(item).1;
+        let fetched = fetch_episode_labels_with_diagnostics(&item.ani_id, total_hint);
+        emit_warnings(&fetched.warnings);
+        if let Some(first) = first_episode_after(stored_episode, fetched.episode_list.as_deref()) {
+            let resolution = resolve_select_nth_for_item_with_diagnostics(item);
+            emit_warnings(&resolution.warnings);
+            return run_ani_cli_episode_with_global_tracking(
+                item,
+                &first,
+                resolution.index,
+                launch,
+            );
+        }
+    }
+    let (cmd, temp_hist_dir, ani_cli_bin) = continue_cmd(item, stored_episode, launch)?;
+    let histfile = temp_hist_dir.histfile_path();
+    print_intro_skip_note(item);
+    let exit = run_playback_cmd(cmd, &ani_cli_bin, launch)?;
+    let success = exit.status.success();
     let final_episode = if success {
         let hist_read = read_hist_map(&histfile);
         emit_warnings(&hist_read.warnings);
@@ -174,59 +352,133 @@ pub(crate) fn run_ani_cli_continue(
     Ok(PlaybackOutcome {
         success,
         final_episode,
-        failure_detail: (!success).then(|| playback_failure_detail(&status)),
+        failure_detail: (!success).then(|| playback_failure_detail(&exit)),
+    })
+}
+
+/// A detached `ani-cli -c` run for one show, started from the TUI so browsing can continue while
+/// the player is open. ani-cli gets no terminal, so its interactive menus are unavailable and it
+/// exits once playback ends.
+pub(crate) struct BackgroundPlayback {
+    pub(crate) item: SeenEntry,
+    child: Child,
+    started: Instant,
+    max_runtime: Option<std::time::Duration>,
+    temp_hist_dir: TempHistDir,
+}
+
+pub(crate) struct BackgroundFinish {
+    pub(crate) outcome: PlaybackOutcome,
+    pub(crate) warnings: Vec<String>,
+}
+
+pub(crate) fn spawn_ani_cli_continue_detached(
+    item: &SeenEntry,
+    launch: &LaunchOptions,
+) -> Result<BackgroundPlayback> {
+    let (cmd, temp_hist_dir, ani_cli_bin) = continue_cmd(item, &item.last_episode, launch)?;
+    let child = spawn_detached(cmd)
+        .with_context(|| format!("failed to launch {}", ani_cli_bin.display()))?;
+    Ok(BackgroundPlayback {
+        item: item.clone(),
+        child,
+        started: Instant::now(),
+        max_runtime: launch.max_runtime,
+        temp_hist_dir,
     })
 }
 
+impl BackgroundPlayback {
+    pub(crate) fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Returns the outcome once ani-cli has exited (reading progress from its temp history the
+    /// same way a foreground Next does), or `None` while it is still running.
+    pub(crate) fn try_finish(&mut self) -> Result<Option<BackgroundFinish>> {
+        let Some(exit) = poll_detached(&mut self.child, self.started, self.max_runtime)? else {
+            return Ok(None);
+        };
+        let success = exit.status.success();
+        let mut warnings = Vec::new();
+        let final_episode = if success {
+            let hist_read = read_hist_map(&self.temp_hist_dir.histfile_path());
+            warnings = hist_read.warnings;
+            hist_read
+                .entries
+                .get(&self.item.ani_id)
+                .map(|entry| entry.ep.clone())
+        } else {
+            None
+        };
+        Ok(Some(BackgroundFinish {
+            outcome: PlaybackOutcome {
+                success,
+                final_episode,
+                failure_detail: (!success).then(|| playback_failure_detail(&exit)),
+            },
+            warnings,
+        }))
+    }
+}
+
 pub(crate) fn run_ani_cli_episode(
-    title: &str,
+    item: &SeenEntry,
     select_nth: Option<u32>,
     episode: &str,
-) -> Result<ExitStatus> {
+    launch: &LaunchOptions,
+) -> Result<ChildExit> {
     let ani_cli_bin = resolve_ani_cli_bin();
-    let mut cmd = ProcessCommand::new(&ani_cli_bin);
+    let cmd = episode_command(&ani_cli_bin, item, select_nth, episode, launch);
+    print_intro_skip_note(item);
+    run_playback_cmd(cmd, &ani_cli_bin, launch)
+}
+
+/// Builds `ani-cli [-S N] [extra flags] <title> -e <episode>` for one show.
+pub(crate) fn episode_command(
+    ani_cli_bin: &Path,
+    item: &SeenEntry,
+    select_nth: Option<u32>,
+    episode: &str,
+    launch: &LaunchOptions,
+) -> ProcessCommand {
+    let mut cmd = ani_cli_command(ani_cli_bin, launch);
     if let Some(index) = select_nth {
         cmd.arg("-S").arg(index.to_string());
     }
-    let status = cmd
-        .arg(title)
+    apply_extra_args(&mut cmd, launch);
+    cmd.arg(sanitize_title_for_search(&item.title))
         .arg("-e")
-        .arg(episode)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status()
-        .with_context(|| format!("failed to launch {}", ani_cli_bin.display()))?;
-    Ok(status)
+        .arg(episode);
+    cmd
 }
 
-pub(crate) fn run_ani_cli_title(title: &str, select_nth: Option<u32>) -> Result<ExitStatus> {
+pub(crate) fn run_ani_cli_title(
+    title: &str,
+    select_nth: Option<u32>,
+    launch: &LaunchOptions,
+) -> Result<ChildExit> {
     let ani_cli_bin = resolve_ani_cli_bin();
-    let mut cmd = ProcessCommand::new(&ani_cli_bin);
+    let mut cmd = ani_cli_command(&ani_cli_bin, launch);
     if let Some(index) = select_nth {
         cmd.arg("-S").arg(index.to_string());
     }
-    let status = cmd
-        .arg(title)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status()
-        .with_context(|| format!("failed to launch {}", ani_cli_bin.display()))?;
-    Ok(status)
+    apply_extra_args(&mut cmd, launch);
+    cmd.arg(title);
+    run_playback_cmd(cmd, &ani_cli_bin, launch)
 }
 
 fn run_with_global_tracking(
     item: &SeenEntry,
-    run_cmd: impl FnOnce() -> Result<ExitStatus>,
+    run_cmd: impl FnOnce() -> Result<ChildExit>,
 ) -> Result<PlaybackOutcome> {
     let histfile = ani_cli_histfile();
     let before_read = read_hist_map(&histfile);
     emit_warnings(&before_read.warnings);
     let before = before_read.entries;
 
-    let status = run_cmd()?;
-    let success = status.success();
+    let exit = run_cmd()?;
+    let success = exit.status.success();
     let final_episode = if success {
         let after_read = read_hist_map(&histfile);
         emit_warnings(&after_read.warnings);
@@ -242,7 +494,7 @@ fn run_with_global_tracking(
     Ok(PlaybackOutcome {
         success,
         final_episode,
-        failure_detail: (!success).then(|| playback_failure_detail(&status)),
+        failure_detail: (!success).then(|| playback_failure_detail(&exit)),
     })
 }
 
@@ -250,35 +502,54 @@ pub(crate) fn run_ani_cli_episode_with_global_tracking(
     item: &SeenEntry,
     episode: &str,
     select_nth: Option<u32>,
+    launch: &LaunchOptions,
 ) -> Result<PlaybackOutcome> {
-    let title = sanitize_title_for_search(&item.title);
-    run_with_global_tracking(item, || run_ani_cli_episode(&title, select_nth, episode))
+    run_with_global_tracking(item, || {
+        run_ani_cli_episode(item, select_nth, episode, launch)
+    })
 }
 
-pub(crate) fn run_ani_cli_select(item: &SeenEntry) -> Result<PlaybackOutcome> {
+pub(crate) fn run_ani_cli_select(
+    item: &SeenEntry,
+    launch: &LaunchOptions,
+) -> Result<PlaybackOutcome> {
     let resolution = resolve_select_nth_for_item_with_diagnostics(item);
     emit_warnings(&resolution.warnings);
     let select_nth = resolution.index.ok_or_else(|| {
-        let mut message = "failed to resolve current show for episode selection".to_string();
+        let mut message = format!(
+            "failed to resolve current show for episode selection ({})",
+            resolution.summary()
+        );
         for warning in resolution.warnings {
             message.push_str("\nWarning: ");
             message.push_str(&warning);
         }
         anyhow!(message)
     })?;
+    run_ani_cli_select_at(item, select_nth, launch)
+}
+
+/// Runs the select flow with an already resolved `-S` index.
+pub(crate) fn run_ani_cli_select_at(
+    item: &SeenEntry,
+    select_nth: u32,
+    launch: &LaunchOptions,
+) -> Result<PlaybackOutcome> {
     let title = sanitize_title_for_search(&item.title);
-    run_with_global_tracking(item, || run_ani_cli_title(&title, Some(select_nth)))
+    run_with_global_tracking(item, || run_ani_cli_title(&title, Some(select_nth), launch))
 }
 
 pub(crate) fn run_ani_cli_replay(
     item: &SeenEntry,
     episode_list: Option<&[String]>,
+    launch: &LaunchOptions,
 ) -> Result<PlaybackOutcome> {
     // Avoid external metadata fetches when numeric fallback already determines replay plan.
-    let should_fetch_episodes =
-        episode_list.is_none() && replay_seed_episode(&item.last_episode, None).is_none();
+    let should_fetch_episodes = launch.replay_mode == ReplayMode::Continue
+        && episode_list.is_none()
+        && replay_seed_episode(&item.last_episode, None).is_none();
     let fetched_episodes = if should_fetch_episodes {
-        let total_hint = parse_title_and_total_eps(&item.title).1;
+        let total_hint = entry_title_and_total(item).1;
         let outcome = fetch_episode_labels_with_diagnostics(&item.ani_id, total_hint);
         emit_warnings(&outcome.warnings);
         outcome.episode_list
@@ -288,30 +559,40 @@ pub(crate) fn run_ani_cli_replay(
     let resolved_episode_list = episode_list.or(fetched_episodes.as_deref());
 
     let mut select_warnings = Vec::new();
-    let plan = build_replay_plan(item, resolved_episode_list, |current_item| {
-        let resolution = resolve_select_nth_for_item_with_diagnostics(current_item);
-        select_warnings = resolution.warnings;
-        resolution.index
-    });
+    let plan = build_replay_plan(
+        item,
+        resolved_episode_list,
+        launch.replay_mode,
+        |current_item| {
+            let resolution = resolve_select_nth_for_item_with_diagnostics(current_item);
+            select_warnings = resolution.warnings;
+            resolution.index
+        },
+    );
     emit_warnings(&select_warnings);
     match plan {
-        ReplayPlan::Continue { seed_episode } => run_ani_cli_continue(item, &seed_episode),
+        ReplayPlan::Continue { seed_episode } => run_ani_cli_continue(item, &seed_episode, launch),
         ReplayPlan::Episode {
             episode,
             select_nth,
-        } => run_ani_cli_episode_with_global_tracking(item, &episode, select_nth),
+        } => run_ani_cli_episode_with_global_tracking(item, &episode, select_nth, launch),
     }
 }
 
 pub(crate) fn build_replay_plan<F>(
     item: &SeenEntry,
     episode_list: Option<&[String]>,
+    mode: ReplayMode,
     resolve_select_nth: F,
 ) -> ReplayPlan
 where
     F: FnOnce(&SeenEntry) -> Option<u32>,
 {
-    if let Some(seed_episode) = replay_seed_episode(&item.last_episode, episode_list) {
+    let seed = match mode {
+        ReplayMode::Continue => replay_seed_episode(&item.last_episode, episode_list),
+        ReplayMode::Direct => None,
+    };
+    if let Some(seed_episode) = seed {
         ReplayPlan::Continue { seed_episode }
     } else {
         // Episode 0 / first-entry replay can otherwise open ambiguous show search in ani-cli.
@@ -325,9 +606,10 @@ where
 pub(crate) fn run_ani_cli_previous(
     item: &SeenEntry,
     episode_list: Option<&[String]>,
+    launch: &LaunchOptions,
 ) -> Result<PlaybackOutcome> {
     let fetched_episodes = if episode_list.is_none() {
-        let total_hint = parse_title_and_total_eps(&item.title).1;
+        let total_hint = entry_title_and_total(item).1;
         let outcome = fetch_episode_labels_with_diagnostics(&item.ani_id, total_hint);
         emit_warnings(&outcome.warnings);
         outcome.episode_list
@@ -339,19 +621,22 @@ pub(crate) fn run_ani_cli_previous(
     let target_episode = previous_target_episode(&item.last_episode, resolved_episode_list)
         .ok_or_else(|| anyhow!("no previous episode available"))?;
     if let Some(seed_episode) = previous_seed_episode(&item.last_episode, resolved_episode_list) {
-        run_ani_cli_continue(item, &seed_episode)
+        run_ani_cli_continue(item, &seed_episode, launch)
     } else {
         let resolution = resolve_select_nth_for_item_with_diagnostics(item);
         emit_warnings(&resolution.warnings);
         let select_nth = resolution.index.ok_or_else(|| {
-            let mut message = "failed to resolve current show for previous action".to_string();
+            let mut message = format!(
+                "failed to resolve current show for previous action ({})",
+                resolution.summary()
+            );
             for warning in resolution.warnings {
                 message.push_str("\nWarning: ");
                 message.push_str(&warning);
             }
             anyhow!(message)
         })?;
-        run_ani_cli_episode_with_global_tracking(item, &target_episode, Some(select_nth))
+        run_ani_cli_episode_with_global_tracking(item, &target_episode, Some(select_nth), launch)
     }
 }
 
@@ -366,6 +651,69 @@ pub(crate) fn make_temp_hist_dir() -> Result<PathBuf> {
     Ok(dir)
 }
 
+/// Temp history dirs older than this are treated as left behind by a crashed run.
+pub(crate) const STALE_TEMP_HIST_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Removes `anitrack-hist-<pid>-<ts>` dirs under `temp_root` that were last modified more than
+/// `max_age` ago, skipping this process's own dirs and dirs of processes that are still running.
+/// Outside Unix the owner cannot be checked, so nothing is removed. Returns how many were
+/// removed; errors are ignored since this is best effort.
+pub(crate) fn sweep_stale_temp_hist_dirs(temp_root: &Path, max_age: Duration) -> usize {
+    let Ok(entries) = fs::read_dir(temp_root) else {
+        return 0;
+    };
+    let own_pid = std::process::id();
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(pid) = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("anitrack-hist-"))
+            .and_then(|rest| rest.split_once('-'))
+            .and_then(|(pid, ts)| ts.parse::<u128>().ok().and(pid.parse::<u32>().ok()))
+        else {
+            continue;
+        };
+        if pid == own_pid || process_is_running(pid) {
+            continue;
+        }
+        let stale = entry
+            .metadata()
+            .ok()
+            .filter(|meta| meta.is_dir())
+            .and_then(|meta| meta.modified().ok())
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > max_age);
+        if stale && fs::remove_dir_all(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Whether `pid` names a live process. Unknown answers count as running, so the sweep only
+/// removes dirs it is sure about.
+#[cfg(unix)]
+fn process_is_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return true;
+    };
+    if pid <= 0 {
+        return true;
+    }
+    // Signal 0 only checks for existence; EPERM means the process exists under another user.
+    let alive = unsafe { libc::kill(pid, 0) == 0 };
+    alive || std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+/// There is no portable liveness check here, so every owner counts as running and the sweep
+/// leaves all dirs alone rather than deleting one a live ani-cli still uses.
+#[cfg(not(unix))]
+fn process_is_running(_pid: u32) -> bool {
+    true
+}
+
 #[derive(Debug)]
 pub(crate) struct TempHistDir {
     path: PathBuf,
@@ -1,4 +1,7 @@
-use std::process::{Command as ProcessCommand, ExitStatus};
+use std::process::{Child, Command as ProcessCommand, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[cfg(unix)]
 use anyhow::anyhow;
@@ -75,6 +78,47 @@ impl Drop for TerminalForegroundGuard {
     }
 }
 
+/// How long a timed-out child gets to exit after the terminate signal before it is killed.
+const TERMINATE_GRACE: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChildExit {
+    pub(crate) status: ExitStatus,
+    /// The max runtime that was exceeded, if the child had to be terminated.
+    pub(crate) timed_out: Option<Duration>,
+}
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn record_interrupt(_signum: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Turns SIGINT into a flag polled via `interrupt_requested` so long-running loops can exit cleanly.
+#[cfg(unix)]
+pub(crate) fn watch_for_interrupt() -> Result<()> {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = record_interrupt as *const () as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        action.sa_flags = 0;
+        if libc::sigaction(libc::SIGINT, &action, std::ptr::null_mut()) != 0 {
+            return Err(anyhow!("failed to install SIGINT handler"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn watch_for_interrupt() -> Result<()> {
+    Ok(())
+}
+
+pub(crate) fn interrupt_requested() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
 #[cfg(unix)]
 pub(crate) fn with_sigint_ignored<F, R>(f: F) -> Result<R>
 where
@@ -93,11 +137,15 @@ where
 }
 
 #[cfg(unix)]
-pub(crate) fn run_interactive_cmd(mut cmd: ProcessCommand) -> Result<ExitStatus> {
+pub(crate) fn run_interactive_cmd(
+    mut cmd: ProcessCommand,
+    max_runtime: Option<Duration>,
+) -> Result<ChildExit> {
     let stdin_fd = libc::STDIN_FILENO;
     let parent_pgrp = unsafe { libc::tcgetpgrp(stdin_fd) };
     if parent_pgrp == -1 {
-        return cmd.status().context("failed to launch ani-cli");
+        let mut child = cmd.spawn().context("failed to launch ani-cli")?;
+        return wait_with_max_runtime(&mut child, max_runtime, None);
     }
 
     let _sigttou_guard = ScopedSigaction::ignore(libc::SIGTTOU)?;
@@ -118,10 +166,120 @@ pub(crate) fn run_interactive_cmd(mut cmd: ProcessCommand) -> Result<ExitStatus>
     let mut child = cmd.spawn().context("failed to spawn ani-cli")?;
     let child_pgid = child.id() as libc::pid_t;
     terminal_guard.handoff_to_child(child_pgid);
-    child.wait().context("failed waiting on ani-cli")
+    wait_with_max_runtime(&mut child, max_runtime, Some(child_pgid))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn run_interactive_cmd(
+    mut cmd: ProcessCommand,
+    max_runtime: Option<Duration>,
+) -> Result<ChildExit> {
+    let mut child = cmd.spawn().context("failed to launch ani-cli")?;
+    wait_with_max_runtime(&mut child, max_runtime, None)
+}
+
+/// Spawns a command without the controlling terminal: stdio is discarded and, on Unix, the child
+/// starts its own session so terminal signals and job control never reach it.
+pub(super) fn spawn_detached(mut cmd: ProcessCommand) -> Result<Child> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    unsafe {
+        cmd.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    cmd.spawn().context("failed to launch ani-cli")
+}
+
+/// Non-blocking check on a detached child. Returns `None` while it is still running, and
+/// terminates it once it has been running longer than `max_runtime`.
+pub(super) fn poll_detached(
+    child: &mut Child,
+    started: Instant,
+    max_runtime: Option<Duration>,
+) -> Result<Option<ChildExit>> {
+    if let Some(status) = child.try_wait().context("failed waiting on ani-cli")? {
+        return Ok(Some(ChildExit {
+            status,
+            timed_out: None,
+        }));
+    }
+    let Some(limit) = max_runtime.filter(|limit| started.elapsed() >= *limit) else {
+        return Ok(None);
+    };
+
+    // The detached child leads its own session, so its pid is also its process group.
+    let process_group = cfg!(unix).then_some(child.id() as i32);
+    terminate_child(child, process_group);
+    let status = child.wait().context("failed waiting on ani-cli")?;
+    Ok(Some(ChildExit {
+        status,
+        timed_out: Some(limit),
+    }))
+}
+
+fn wait_with_max_runtime(
+    child: &mut Child,
+    max_runtime: Option<Duration>,
+    process_group: Option<i32>,
+) -> Result<ChildExit> {
+    let Some(limit) = max_runtime else {
+        let status = child.wait().context("failed waiting on ani-cli")?;
+        return Ok(ChildExit {
+            status,
+            timed_out: None,
+        });
+    };
+
+    let deadline = Instant::now() + limit;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait().context("failed waiting on ani-cli")? {
+            return Ok(ChildExit {
+                status,
+                timed_out: None,
+            });
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    terminate_child(child, process_group);
+    let status = child.wait().context("failed waiting on ani-cli")?;
+    Ok(ChildExit {
+        status,
+        timed_out: Some(limit),
+    })
+}
+
+/// Asks the child (and the player it spawned, via its process group) to exit, then kills it if
+/// it is still running after `TERMINATE_GRACE`.
+#[cfg(unix)]
+fn terminate_child(child: &mut Child, process_group: Option<i32>) {
+    let child_pid = child.id() as libc::pid_t;
+    let signal_child = |signum: libc::c_int| unsafe {
+        match process_group {
+            Some(pgid) => libc::killpg(pgid, signum),
+            None => libc::kill(child_pid, signum),
+        }
+    };
+    signal_child(libc::SIGTERM);
+
+    let grace_deadline = Instant::now() + TERMINATE_GRACE;
+    while Instant::now() < grace_deadline {
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    signal_child(libc::SIGKILL);
 }
 
 #[cfg(not(unix))]
-pub(crate) fn run_interactive_cmd(mut cmd: ProcessCommand) -> Result<ExitStatus> {
-    cmd.status().context("failed to launch ani-cli")
+fn terminate_child(child: &mut Child, process_group: Option<i32>) {
+    let _ = (process_group, TERMINATE_GRACE);
+    let _ = child.kill();
 }
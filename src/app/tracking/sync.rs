@@ -0,0 +1,84 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+
+use super::super::episode::compare_episode_labels;
+use super::HistEntry;
+use super::history::{ani_cli_histfile, append_history_warnings, read_hist_map};
+use crate::db::{Database, SeenEntry};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HistorySyncUpdate {
+    pub(crate) ani_id: String,
+    pub(crate) title: String,
+    pub(crate) episode: String,
+}
+
+/// Picks tracked shows whose `ani-cli` history episode is ahead of the stored one.
+pub(crate) fn plan_history_sync(
+    tracked: &[SeenEntry],
+    history: &HashMap<String, HistEntry>,
+) -> Vec<HistorySyncUpdate> {
+    tracked
+        .iter()
+        .filter_map(|item| {
+            let entry = history.get(&item.ani_id)?;
+            if compare_episode_labels(&entry.ep, &item.last_episode) != Ordering::Greater {
+                return None;
+            }
+            Some(HistorySyncUpdate {
+                ani_id: item.ani_id.clone(),
+                title: item.title.clone(),
+                episode: entry.ep.clone(),
+            })
+        })
+        .collect()
+}
+
+/// History entries whose show is not tracked, one per show (its latest line), in history order.
+pub(crate) fn find_untracked_history(
+    tracked: &[SeenEntry],
+    ordered_history: &[HistEntry],
+) -> Vec<HistEntry> {
+    let tracked_ids = tracked
+        .iter()
+        .map(|item| item.ani_id.as_str())
+        .collect::<HashSet<_>>();
+    let mut seen_ids = HashSet::new();
+    let mut untracked = ordered_history
+        .iter()
+        .rev()
+        .filter(|entry| !tracked_ids.contains(entry.id.as_str()))
+        .filter(|entry| seen_ids.insert(entry.id.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    untracked.reverse();
+    untracked
+}
+
+/// Reads the global `ani-cli` history and returns shows it contains that are not tracked yet,
+/// along with any history read warnings.
+pub(crate) fn untracked_history_entries(db: &Database) -> Result<(Vec<HistEntry>, Vec<String>)> {
+    let hist_read = read_hist_map(&ani_cli_histfile());
+    let tracked = db.list_seen()?;
+    let untracked = find_untracked_history(&tracked, &hist_read.ordered_entries);
+    Ok((untracked, hist_read.warnings))
+}
+
+/// Advances tracked progress from the global `ani-cli` history without launching anything.
+pub(crate) fn sync_progress_from_history(db: &Database) -> Result<String> {
+    let hist_read = read_hist_map(&ani_cli_histfile());
+    let tracked = db.list_seen()?;
+    let updates = plan_history_sync(&tracked, &hist_read.entries);
+    for update in &updates {
+        db.upsert_seen(&update.ani_id, &update.title, &update.episode)?;
+    }
+
+    let mut message = match updates.len() {
+        0 => "No tracked shows are behind ani-cli history.".to_string(),
+        count => format!("Synced {count} show(s) from ani-cli history."),
+    };
+    append_history_warnings(&mut message, &hist_read.warnings);
+    Ok(message)
+}
@@ -1,41 +1,80 @@
 use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command as ProcessCommand;
 use std::sync::mpsc;
+use std::time::Instant;
 
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow, bail};
 use ratatui::widgets::TableState;
 
-use crate::db::{Database, SeenEntry};
+use crate::db::{Database, ListOrder, SeenEntry};
 
-use super::super::episode::{fetch_episode_labels_with_diagnostics, parse_title_and_total_eps};
+use super::super::episode::{
+    AiringStatus, EpisodeList, entry_title_and_total, fetch_episode_labels_with_diagnostics,
+    last_episode_after_play, record_watched_episode,
+};
+use super::super::hooks::run_progress_hook;
 use super::super::tracking::{
-    PlaybackOutcome, playback_failure_message, run_ani_cli_continue, run_ani_cli_previous,
-    run_ani_cli_replay, run_ani_cli_select,
+    BackgroundFinish, LaunchOptions, PlaybackOutcome, SelectNthCheck, playback_failure_message,
+    resolve_select_nth_for_item_with_diagnostics, revalidate_select_nth_for_item,
+    run_ani_cli_continue, run_ani_cli_episode_with_global_tracking, run_ani_cli_previous,
+    run_ani_cli_replay, run_ani_cli_select, run_ani_cli_select_at, select_budget_from_env,
+};
+use super::{
+    EpisodeListFetchResult, EpisodeListState, PendingResolve, PendingSelect, SELECT_RESOLVE_GRACE,
+    SelectLookup, TuiAction,
 };
-use super::{EpisodeListFetchResult, EpisodeListState, TuiAction};
 
-pub(super) fn refresh_items(
+/// Reloads `items` from the database in `order`, keeping `preferred_id` selected when it is
+/// still listed. If the database cannot be read (e.g. another anitrack instance holds a lock), the
+/// current list is kept and `status` reports the failure, so the session survives it.
+pub(crate) fn refresh_items(
     db: &Database,
+    order: ListOrder,
     items: &mut Vec<SeenEntry>,
     table_state: &mut TableState,
     preferred_id: Option<&str>,
-) -> Result<()> {
-    *items = db.list_seen()?;
+    status: &mut String,
+) {
+    match db.list_seen_sorted(order) {
+        Ok(reloaded) => *items = reloaded,
+        Err(err) => {
+            *status = status_error(&format!(
+                "Failed to reload library: {err:#}; showing cached list"
+            ));
+        }
+    }
     if items.is_empty() {
         table_state.select(None);
-        return Ok(());
+        return;
     }
 
     if let Some(id) = preferred_id
         && let Some(idx) = items.iter().position(|item| item.ani_id == id)
     {
         table_state.select(Some(idx));
-        return Ok(());
+        return;
     }
 
     match table_state.selected() {
         Some(selected) => table_state.select(Some(selected.min(items.len() - 1))),
         None => table_state.select(Some(0)),
     }
+}
+
+/// Runs `editor` (program and arguments) on `path` with the terminal attached and waits for it.
+pub(super) fn open_in_editor(editor: &[String], path: &Path) -> Result<()> {
+    let (program, args) = editor
+        .split_first()
+        .ok_or_else(|| anyhow!("no editor command"))?;
+    let status = ProcessCommand::new(program)
+        .args(args)
+        .arg(path)
+        .status()
+        .with_context(|| format!("failed to launch `{program}`"))?;
+    if !status.success() {
+        bail!("`{program}` exited with {status}");
+    }
     Ok(())
 }
 
@@ -47,20 +86,38 @@ pub(super) fn status_error(msg: &str) -> String {
     format!("ERROR: {msg}")
 }
 
+/// Status message for a finished launch, and whether playback succeeded and was recorded.
+pub(super) struct ActionReport {
+    pub(super) message: String,
+    pub(super) played: bool,
+}
+
 fn apply_outcome(
     db: &Database,
     item: &SeenEntry,
     outcome: PlaybackOutcome,
     success_msg: impl FnOnce(&str) -> String,
-) -> Result<String> {
+) -> Result<ActionReport> {
     if outcome.success {
-        let updated_ep = outcome
+        let played_ep = outcome
             .final_episode
             .unwrap_or_else(|| item.last_episode.clone());
+        let updated_ep = last_episode_after_play(&item.last_episode, &played_ep);
         db.upsert_seen(&item.ani_id, &item.title, &updated_ep)?;
-        Ok(success_msg(&updated_ep))
+        record_watched_episode(db, &item.ani_id, &played_ep)?;
+        let mut message = success_msg(&updated_ep);
+        if let Err(err) = run_progress_hook(item, &updated_ep) {
+            message.push_str(&format!(" | Warning: {err:#}"));
+        }
+        Ok(ActionReport {
+            message,
+            played: true,
+        })
     } else {
-        Ok(playback_failure_message(&outcome))
+        Ok(ActionReport {
+            message: playback_failure_message(&outcome),
+            played: false,
+        })
     }
 }
 
@@ -69,28 +126,33 @@ pub(super) fn run_selected_action(
     item: &SeenEntry,
     action: TuiAction,
     episode_list: Option<&[String]>,
-) -> Result<String> {
+    select_nth: Option<u32>,
+    launch: &LaunchOptions,
+) -> Result<ActionReport> {
     match action {
         TuiAction::Next => {
-            let outcome = run_ani_cli_continue(item, &item.last_episode)?;
+            let outcome = run_ani_cli_continue(item, &item.last_episode, launch)?;
             apply_outcome(db, item, outcome, |ep| {
                 format!("Updated progress: {} -> episode {ep}", item.title)
             })
         }
         TuiAction::Replay => {
-            let outcome = run_ani_cli_replay(item, episode_list)?;
+            let outcome = run_ani_cli_replay(item, episode_list, launch)?;
             apply_outcome(db, item, outcome, |ep| {
                 format!("Replay finished: {} now on episode {ep}", item.title)
             })
         }
         TuiAction::Previous => {
-            let outcome = run_ani_cli_previous(item, episode_list)?;
+            let outcome = run_ani_cli_previous(item, episode_list, launch)?;
             apply_outcome(db, item, outcome, |ep| {
                 format!("Previous finished: {} now on episode {ep}", item.title)
             })
         }
         TuiAction::Select => {
-            let outcome = run_ani_cli_select(item)?;
+            let outcome = match select_nth {
+                Some(index) => run_ani_cli_select_at(item, index, launch)?,
+                None => run_ani_cli_select(item, launch)?,
+            };
             apply_outcome(db, item, outcome, |ep| {
                 format!("Select finished: {} now on episode {ep}", item.title)
             })
@@ -98,6 +160,90 @@ pub(super) fn run_selected_action(
     }
 }
 
+pub(super) fn run_episode_action(
+    db: &Database,
+    item: &SeenEntry,
+    episode: &str,
+    select_nth: Option<u32>,
+    launch: &LaunchOptions,
+) -> Result<ActionReport> {
+    let outcome = run_ani_cli_episode_with_global_tracking(item, episode, select_nth, launch)?;
+    apply_outcome(db, item, outcome, |ep| {
+        format!("Episode finished: {} now on episode {ep}", item.title)
+    })
+}
+
+pub(super) fn apply_background_finish(
+    db: &Database,
+    item: &SeenEntry,
+    finish: BackgroundFinish,
+) -> Result<String> {
+    let mut msg = apply_outcome(db, item, finish.outcome, |ep| {
+        format!(
+            "Background playback finished: {} now on episode {ep}",
+            item.title
+        )
+    })?
+    .message;
+    for warning in finish.warnings {
+        msg.push_str(" | Warning: ");
+        msg.push_str(&warning);
+    }
+    Ok(msg)
+}
+
+/// Starts resolving `item`'s search position on a background thread, so the TUI stays drawn (and
+/// cancelable) during the network lookup. `episode` is the picked episode to play once resolved.
+pub(super) fn spawn_select_resolution(item: &SeenEntry, episode: Option<String>) -> PendingResolve {
+    let (tx, rx) = mpsc::channel();
+    let thread_item = item.clone();
+    std::thread::spawn(move || {
+        let _ = tx.send(SelectLookup::Resolved(
+            resolve_select_nth_for_item_with_diagnostics(&thread_item),
+        ));
+    });
+    PendingResolve {
+        ani_id: item.ani_id.clone(),
+        title: item.title.clone(),
+        episode,
+        deadline: Instant::now() + select_budget_from_env().0 + SELECT_RESOLVE_GRACE,
+        rx,
+    }
+}
+
+/// Re-checks a confirmed Select position against a fresh search on a background thread, since
+/// the prompt may have been open for a while; the search runs again if the results moved.
+pub(super) fn spawn_select_recheck(item: &SeenEntry, confirmed: PendingSelect) -> PendingResolve {
+    let (tx, rx) = mpsc::channel();
+    let thread_item = item.clone();
+    let index = confirmed.select_nth;
+    let resolution = confirmed.resolution;
+    std::thread::spawn(move || {
+        let lookup = match revalidate_select_nth_for_item(&thread_item, &resolution) {
+            SelectNthCheck::Valid => SelectLookup::Rechecked {
+                index,
+                warning: None,
+            },
+            SelectNthCheck::Unverified(reason) => SelectLookup::Rechecked {
+                index,
+                warning: Some(reason),
+            },
+            SelectNthCheck::Stale(reason) => SelectLookup::Changed {
+                reason,
+                resolution: resolve_select_nth_for_item_with_diagnostics(&thread_item),
+            },
+        };
+        let _ = tx.send(lookup);
+    });
+    PendingResolve {
+        ani_id: item.ani_id.clone(),
+        title: item.title.clone(),
+        episode: confirmed.episode,
+        deadline: Instant::now() + select_budget_from_env().0 + SELECT_RESOLVE_GRACE,
+        rx,
+    }
+}
+
 pub(super) fn ensure_selected_episode_list(
     items: &[SeenEntry],
     table_state: &TableState,
@@ -113,27 +259,75 @@ pub(super) fn ensure_selected_episode_list(
     if episode_lists_by_id.contains_key(&item.ani_id) {
         return;
     }
+    spawn_episode_list_fetch(item, episode_lists_by_id, tx);
+}
+
+/// Starts fetches for visible rows that have no episode list yet, keeping at most `concurrency`
+/// fetches in flight (the selected row's fetch counts toward the limit but is never held back).
+pub(super) fn prefetch_visible_episode_lists(
+    visible: &[SeenEntry],
+    episode_lists_by_id: &mut HashMap<String, EpisodeListState>,
+    tx: &mpsc::Sender<EpisodeListFetchResult>,
+    concurrency: usize,
+) {
+    let mut in_flight = episode_lists_by_id
+        .values()
+        .filter(|state| state.is_loading())
+        .count();
+    for item in visible {
+        if in_flight >= concurrency {
+            return;
+        }
+        if episode_lists_by_id.contains_key(&item.ani_id) {
+            continue;
+        }
+        spawn_episode_list_fetch(item, episode_lists_by_id, tx);
+        in_flight += 1;
+    }
+}
 
+fn spawn_episode_list_fetch(
+    item: &SeenEntry,
+    episode_lists_by_id: &mut HashMap<String, EpisodeListState>,
+    tx: &mpsc::Sender<EpisodeListFetchResult>,
+) {
     episode_lists_by_id.insert(item.ani_id.clone(), EpisodeListState::Loading);
     let ani_id = item.ani_id.clone();
-    let total_hint = parse_title_and_total_eps(&item.title).1;
+    let total_hint = entry_title_and_total(item).1;
     let tx = tx.clone();
     std::thread::spawn(move || {
         let outcome = fetch_episode_labels_with_diagnostics(&ani_id, total_hint);
         let warning = (!outcome.warnings.is_empty()).then(|| outcome.warnings.join(" | "));
         let _ = tx.send(EpisodeListFetchResult {
             ani_id,
-            episode_list: outcome.episode_list,
+            episode_list: outcome.episode_list.map(EpisodeList::new),
+            airing_status: outcome.airing_status,
             warning,
         });
     });
 }
 
+/// Drops fetched episode lists (keeping in-flight fetches) so they are fetched again, e.g. after
+/// an airing show gets a new episode. Returns how many were dropped.
+pub(super) fn clear_episode_lists(
+    episode_lists_by_id: &mut HashMap<String, EpisodeListState>,
+) -> usize {
+    let before = episode_lists_by_id.len();
+    episode_lists_by_id.retain(|_, state| state.is_loading());
+    before - episode_lists_by_id.len()
+}
+
+/// Stores finished fetches and returns the airing statuses they reported, for the caller to
+/// persist.
 pub(super) fn drain_episode_fetch_results(
     rx: &mpsc::Receiver<EpisodeListFetchResult>,
     episode_lists_by_id: &mut HashMap<String, EpisodeListState>,
-) {
+) -> Vec<(String, AiringStatus)> {
+    let mut statuses = Vec::new();
     while let Ok(result) = rx.try_recv() {
+        if let Some(status) = result.airing_status {
+            statuses.push((result.ani_id.clone(), status));
+        }
         episode_lists_by_id.insert(
             result.ani_id,
             EpisodeListState::Ready {
@@ -142,4 +336,25 @@ pub(super) fn drain_episode_fetch_results(
             },
         );
     }
+    statuses
+}
+
+/// Persists airing statuses that differ from what `items` holds, updating `items` to match.
+/// Failures only lose the badge, so they are ignored.
+pub(super) fn store_airing_statuses(
+    db: &Database,
+    items: &mut [SeenEntry],
+    statuses: &[(String, AiringStatus)],
+) {
+    for (ani_id, status) in statuses {
+        let Some(item) = items.iter_mut().find(|item| &item.ani_id == ani_id) else {
+            continue;
+        };
+        if item.airing_status.as_deref() == Some(status.as_str()) {
+            continue;
+        }
+        if db.set_airing_status(ani_id, Some(status.as_str())).is_ok() {
+            item.airing_status = Some(status.as_str().to_string());
+        }
+    }
 }
@@ -1,29 +1,55 @@
 mod actions;
 mod render;
 mod session;
+mod state;
+mod theme;
 
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::io;
+use std::path::PathBuf;
 use std::sync::mpsc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Rect;
 use ratatui::widgets::TableState;
 
-use crate::db::Database;
+use crate::cli::EpisodeFormat;
+use crate::db::{Database, ListOrder, ListSort, RATING_RANGE, SeenEntry, SortTieBreak};
 
-use super::episode::{has_next_episode, has_previous_episode, parse_title_and_total_eps, truncate};
-use super::tracking::run_ani_cli_search;
+use super::color::warning_label;
+use super::episode::{
+    AiringStatus, EpisodeList, PreviousConfirm, compare_episode_labels, display_episode_label,
+    entry_has_next_episode, entry_title_and_total, episode_progress_position, first_episode_after,
+    has_previous_episode, is_movie, next_episode_label, parse_episode_u32, previous_crossing_note,
+    previous_target_episode, restart_episode, truncate,
+};
+use super::tracking::{
+    BackgroundPlayback, LaunchOptions, SelectNthResolution, ani_cli_histfile,
+    normalize_title_for_match, run_ani_cli_search, spawn_ani_cli_continue_detached,
+    sync_progress_from_history,
+};
+use super::watch_later::WatchLater;
 
+pub(crate) use self::actions::refresh_items;
 use self::actions::{
-    drain_episode_fetch_results, ensure_selected_episode_list, refresh_items, run_selected_action,
-    status_error, status_info,
+    apply_background_finish, clear_episode_lists, drain_episode_fetch_results,
+    ensure_selected_episode_list, open_in_editor, prefetch_visible_episode_lists,
+    run_episode_action, run_selected_action, spawn_select_recheck, spawn_select_resolution,
+    status_error, status_info, store_airing_statuses,
 };
-use self::render::draw_tui;
+#[cfg(test)]
+pub(crate) use self::render::{HintMode, hint_text, popup_rect_for_text, timeline_rows};
+use self::render::{TuiModals, TuiView, draw_tui, visible_library_rows};
 use self::session::TuiSession;
+pub(crate) use self::state::TuiUiState;
+pub(crate) use self::theme::TuiTheme;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum TuiAction {
@@ -43,6 +69,26 @@ impl TuiAction {
         }
     }
 
+    /// Name stored as an entry's default action.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Next => "next",
+            Self::Replay => "replay",
+            Self::Previous => "previous",
+            Self::Select => "select",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "next" => Some(Self::Next),
+            "replay" => Some(Self::Replay),
+            "previous" => Some(Self::Previous),
+            "select" => Some(Self::Select),
+            _ => None,
+        }
+    }
+
     pub(crate) fn move_left(self) -> Self {
         match self {
             Self::Next => Self::Next,
@@ -62,12 +108,252 @@ impl TuiAction {
     }
 }
 
+/// A column of the Library table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LibraryColumn {
+    Title,
+    Total,
+    LastEp,
+    Rating,
+    LastSeen,
+}
+
+impl LibraryColumn {
+    /// Every column, in the default order.
+    pub(crate) const ALL: [Self; 5] = [
+        Self::Title,
+        Self::Total,
+        Self::LastEp,
+        Self::Rating,
+        Self::LastSeen,
+    ];
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "title" => Some(Self::Title),
+            "total" => Some(Self::Total),
+            "last_ep" => Some(Self::LastEp),
+            "rating" => Some(Self::Rating),
+            "last_seen" => Some(Self::LastSeen),
+            _ => None,
+        }
+    }
+}
+
+/// How long quick-find keeps adding to the typed prefix; a key after a longer pause starts a new one.
+const QUICK_FIND_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The `f` quick-find prompt: the title prefix typed so far, and the row to return to on Esc.
+#[derive(Debug)]
+struct QuickFind {
+    prefix: String,
+    last_typed: Instant,
+    origin: Option<usize>,
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct PendingDelete {
     pub(super) ani_id: String,
     pub(super) title: String,
 }
 
+#[derive(Debug, Clone)]
+pub(super) struct PendingSelect {
+    pub(super) ani_id: String,
+    pub(super) matched_title: String,
+    pub(super) select_nth: u32,
+    /// How the match was found, e.g. which translation mode and how many searches it took.
+    pub(super) detail: String,
+    /// Exact episode picked in the episode picker; `None` opens ani-cli's own menu.
+    pub(super) episode: Option<String>,
+    /// The resolution behind `select_nth`, re-checked right before launch.
+    pub(super) resolution: SelectNthResolution,
+}
+
+impl PendingSelect {
+    /// Confirmation for a title-only match; `None` when `resolution` is an exact id match (or no
+    /// match), which needs no confirmation.
+    fn for_title_match(
+        ani_id: &str,
+        resolution: SelectNthResolution,
+        episode: Option<String>,
+    ) -> Option<Self> {
+        Some(Self {
+            ani_id: ani_id.to_string(),
+            matched_title: resolution.title_match.clone()?,
+            select_nth: resolution.index?,
+            detail: resolution.summary(),
+            episode,
+            resolution,
+        })
+    }
+}
+
+/// How long past the search budget a Select lookup may run before the TUI gives up on it; the
+/// budget only stops new searches, not one already waiting on the network.
+pub(super) const SELECT_RESOLVE_GRACE: Duration = Duration::from_secs(10);
+
+/// A Select lookup running in the background; the launch waits for its result.
+#[derive(Debug)]
+pub(super) struct PendingResolve {
+    pub(super) ani_id: String,
+    pub(super) title: String,
+    /// Exact episode picked in the episode picker; `None` opens ani-cli's own menu.
+    pub(super) episode: Option<String>,
+    pub(super) deadline: Instant,
+    pub(super) rx: mpsc::Receiver<SelectLookup>,
+}
+
+/// What a background Select lookup sends back.
+#[derive(Debug)]
+pub(super) enum SelectLookup {
+    /// A fresh search position; a title-only match still needs confirming.
+    Resolved(SelectNthResolution),
+    /// A confirmed position that still holds; the warning says why it could not be re-checked.
+    Rechecked { index: u32, warning: Option<String> },
+    /// A confirmed position the results moved away from, and the search run again.
+    Changed {
+        reason: String,
+        resolution: SelectNthResolution,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct EpisodePicker {
+    pub(super) ani_id: String,
+    pub(super) title: String,
+    pub(super) last_episode: String,
+    /// Position of the stored episode in `episodes`, counting from 1.
+    watched_upto: Option<u32>,
+    pub(super) episodes: Vec<String>,
+    pub(super) query: String,
+    pub(super) selected: usize,
+}
+
+impl EpisodePicker {
+    pub(super) fn new(item: &SeenEntry, episodes: &EpisodeList) -> Self {
+        let labels = episodes.labels();
+        let watched_upto = episodes.ordinal_of(&item.last_episode);
+        // Start on the episode after the stored one, which is the usual pick.
+        let selected = watched_upto
+            .map(|ordinal| (ordinal as usize).min(labels.len().saturating_sub(1)))
+            .unwrap_or(0);
+        Self {
+            ani_id: item.ani_id.clone(),
+            title: item.title.clone(),
+            last_episode: item.last_episode.clone(),
+            watched_upto,
+            episodes: labels.to_vec(),
+            query: String::new(),
+            selected,
+        }
+    }
+
+    /// Episodes whose label (stored or zero-padding-stripped) contains the typed query, with
+    /// their index in the full list, in list order, so `15` narrows to 15, 115, 150-159, and so
+    /// on.
+    pub(super) fn matching(&self) -> impl Iterator<Item = (usize, &str)> {
+        let query = self.query.trim();
+        self.episodes
+            .iter()
+            .map(String::as_str)
+            .enumerate()
+            .filter(move |(_, episode)| {
+                query.is_empty()
+                    || episode.contains(query)
+                    || display_episode_label(episode).contains(query)
+            })
+    }
+
+    pub(super) fn filtered(&self) -> Vec<&str> {
+        self.matching().map(|(_, episode)| episode).collect()
+    }
+
+    /// Whether the episode at `idx` in the full list is at or before the stored one. Without the
+    /// stored episode in the list, labels are compared instead.
+    pub(super) fn is_watched(&self, idx: usize, episode: &str) -> bool {
+        match self.watched_upto {
+            Some(ordinal) => idx < ordinal as usize,
+            None => compare_episode_labels(episode, &self.last_episode) != Ordering::Greater,
+        }
+    }
+
+    pub(super) fn selected_episode(&self) -> Option<&str> {
+        self.filtered().get(self.selected).copied()
+    }
+
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    fn move_down(&mut self) {
+        let len = self.filtered().len();
+        self.selected = (self.selected + 1).min(len.saturating_sub(1));
+    }
+
+    /// Updates the filter and re-selects: an exact label match wins, then the previously
+    /// selected episode if it is still listed, then the first match.
+    pub(super) fn set_query(&mut self, query: String) {
+        let previous = self.selected_episode().map(str::to_string);
+        self.query = query;
+        let filtered = self.filtered();
+        let trimmed = self.query.trim();
+        let exact = filtered
+            .iter()
+            .position(|episode| *episode == trimmed || display_episode_label(episode) == trimmed);
+        let kept = previous
+            .as_deref()
+            .and_then(|previous| filtered.iter().position(|episode| *episode == previous));
+        self.selected = exact.or(kept).unwrap_or(0);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PendingIntroSkip {
+    pub(super) ani_id: String,
+    pub(super) title: String,
+    pub(super) input: String,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PendingRating {
+    pub(super) ani_id: String,
+    pub(super) title: String,
+    pub(super) input: String,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PendingTags {
+    pub(super) ani_id: String,
+    pub(super) title: String,
+    pub(super) input: String,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PendingPrevious {
+    pub(super) ani_id: String,
+    pub(super) title: String,
+    /// What Previous will play, e.g. `episode 0 (prologue)`.
+    pub(super) target: String,
+}
+
+impl PendingPrevious {
+    /// Confirmation for a Previous that crosses a boundary `level` asks about; `None` when it
+    /// can play straight away.
+    pub(super) fn for_crossing(
+        item: &SeenEntry,
+        episode_list: Option<&[String]>,
+        level: PreviousConfirm,
+    ) -> Option<Self> {
+        let target = previous_target_episode(&item.last_episode, episode_list)?;
+        Some(Self {
+            ani_id: item.ani_id.clone(),
+            title: item.title.clone(),
+            target: previous_crossing_note(&item.last_episode, &target, level)?,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct PendingNotice {
     pub(super) message: String,
@@ -76,7 +362,9 @@ pub(super) struct PendingNotice {
 #[derive(Debug, Clone)]
 pub(super) struct EpisodeListFetchResult {
     pub(super) ani_id: String,
-    pub(super) episode_list: Option<Vec<String>>,
+    /// Indexed on the fetch thread, so long lists never stall the render loop.
+    pub(super) episode_list: Option<EpisodeList>,
+    pub(super) airing_status: Option<AiringStatus>,
     pub(super) warning: Option<String>,
 }
 
@@ -84,18 +372,23 @@ pub(super) struct EpisodeListFetchResult {
 pub(super) enum EpisodeListState {
     Loading,
     Ready {
-        episode_list: Option<Vec<String>>,
+        episode_list: Option<EpisodeList>,
         warning: Option<String>,
     },
 }
 
 impl EpisodeListState {
     pub(super) fn episode_list(&self) -> Option<&[String]> {
+        self.indexed().map(EpisodeList::labels)
+    }
+
+    /// The fetched list with its ordinal index, for progress lookups.
+    pub(super) fn indexed(&self) -> Option<&EpisodeList> {
         match self {
             Self::Ready {
                 episode_list: Some(episodes),
                 ..
-            } => Some(episodes.as_slice()),
+            } => Some(episodes),
             Self::Loading
             | Self::Ready {
                 episode_list: None, ..
@@ -118,18 +411,94 @@ impl EpisodeListState {
     }
 }
 
-pub(crate) fn run_tui(db: &Database) -> Result<()> {
-    let mut session = TuiSession::enter()?;
+/// Display and fetch settings for a TUI session.
+#[derive(Debug, Clone)]
+pub(crate) struct TuiOptions {
+    /// Episode lists fetched in parallel for visible rows; 0 disables prefetching.
+    pub(crate) prefetch_concurrency: usize,
+    pub(crate) episode_format: EpisodeFormat,
+    /// Title-case display titles that are entirely lowercase or uppercase; stored titles are kept.
+    pub(crate) title_case: bool,
+    /// Stay on the normal screen instead of switching to the alternate screen.
+    pub(crate) plain: bool,
+    /// Where the last selection is remembered between runs; `None` starts on the first row.
+    pub(crate) state_file: Option<PathBuf>,
+    /// Which Previous steps (back to episode 0, or to a special) ask before playing.
+    pub(crate) confirm_previous: PreviousConfirm,
+    /// Wrap the selected row's title over up to two lines; other rows are truncated to one.
+    pub(crate) wrap_selected_title: bool,
+    /// After a successful Next or Replay, select the row that followed the played show.
+    pub(crate) advance_selection_after_play: bool,
+    /// mpv's watch-later directory, read for resume positions; `None` skips the lookup.
+    pub(crate) mpv_watch_later_dir: Option<PathBuf>,
+    /// Order of entries that tie on the active sort; `None` keeps the remembered one (or the
+    /// default).
+    pub(crate) sort_tie_break: Option<SortTieBreak>,
+    /// Append the fetched-list position to the Last Ep column when it differs from the label.
+    pub(crate) show_episode_ordinal: bool,
+    /// Library columns to show, in order; always includes the title.
+    pub(crate) columns: Vec<LibraryColumn>,
+    /// How often to checkpoint the WAL into the main database file; `None` leaves it to SQLite.
+    pub(crate) wal_checkpoint_interval: Option<Duration>,
+    /// Dim rows last watched longer ago than this; `None` flags nothing.
+    pub(crate) stale_after: Option<Duration>,
+    /// Ask before `q` quits while something is in progress; otherwise `q` quits at once.
+    pub(crate) confirm_quit: bool,
+}
+
+pub(crate) fn run_tui(db: &Database, launch: &LaunchOptions, options: &TuiOptions) -> Result<()> {
+    let prefetch_concurrency = options.prefetch_concurrency;
+    let theme = TuiTheme::from_env()?;
+    let mut session = TuiSession::enter(!options.plain)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))
         .context("failed to initialize terminal backend")?;
     terminal.clear()?;
 
-    let mut items = db.list_seen()?;
+    let mut ui_state = options
+        .state_file
+        .as_deref()
+        .map(TuiUiState::load)
+        .unwrap_or_default();
+    let mut list_order = ListOrder {
+        sort: ui_state.sort.unwrap_or_default(),
+        tie_break: options
+            .sort_tie_break
+            .or(ui_state.tie_break)
+            .unwrap_or_default(),
+    };
+    let mut items = db.list_seen_sorted(list_order)?;
     let mut table_state = TableState::default();
     table_state.select((!items.is_empty()).then_some(0));
+    if let Some(idx) = ui_state
+        .selected_ani_id
+        .as_deref()
+        .and_then(|id| items.iter().position(|item| item.ani_id == id))
+    {
+        table_state.select(Some(idx));
+    }
     let mut action = TuiAction::Next;
+    // The action chosen on rows without their own default; rows with one only override it while
+    // they are selected.
+    let mut global_action = action;
+    let mut action_row_id = None::<String>;
     let mut pending_delete = None::<PendingDelete>;
+    let mut pending_select = None::<PendingSelect>;
+    let mut episode_picker = None::<EpisodePicker>;
+    let mut pending_intro_skip = None::<PendingIntroSkip>;
+    let mut pending_tags = None::<PendingTags>;
+    let mut pending_rating = None::<PendingRating>;
+    let mut pending_previous = None::<PendingPrevious>;
     let mut pending_notice = None::<PendingNotice>;
+    let mut pending_resolve = None::<PendingResolve>;
+    let mut watch_later = options.mpv_watch_later_dir.clone().map(WatchLater::new);
+    let mut last_checkpoint = Instant::now();
+    let mut pending_jump = None::<String>;
+    let mut quick_find = None::<QuickFind>;
+    let mut timeline_offset = None::<usize>;
+    let mut show_full_id = false;
+    let mut show_next_episode = false;
+    let mut background = None::<BackgroundPlayback>;
+    let mut pending_quit = false;
     let mut episode_lists_by_id: HashMap<String, EpisodeListState> = HashMap::new();
     let (episode_fetch_tx, episode_fetch_rx) = mpsc::channel::<EpisodeListFetchResult>();
     let mut status = if items.is_empty() {
@@ -139,23 +508,235 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
     };
 
     loop {
-        drain_episode_fetch_results(&episode_fetch_rx, &mut episode_lists_by_id);
+        let airing_statuses =
+            drain_episode_fetch_results(&episode_fetch_rx, &mut episode_lists_by_id);
+        store_airing_statuses(db, &mut items, &airing_statuses);
+        if let Some(playback) = background.as_mut() {
+            match playback.try_finish() {
+                Ok(Some(finish)) => {
+                    let item = playback.item.clone();
+                    background = None;
+                    status = match apply_background_finish(db, &item, finish) {
+                        Ok(msg) => status_info(&msg),
+                        Err(err) => status_error(&format!(
+                            "Background playback failed for {}: {err}",
+                            item.title
+                        )),
+                    };
+                    let selected_id = table_state
+                        .selected()
+                        .and_then(|idx| items.get(idx))
+                        .map(|entry| entry.ani_id.clone());
+                    refresh_items(
+                        db,
+                        list_order,
+                        &mut items,
+                        &mut table_state,
+                        selected_id.as_deref(),
+                        &mut status,
+                    );
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    background = None;
+                    status = status_error(&format!("Background playback failed: {err}"));
+                }
+            }
+            // With the background run over there is no progress left to lose, so drop a pending quit
+            // confirmation.
+            pending_quit &= background.is_some();
+        }
+        if let Some(resolving) = pending_resolve.as_ref() {
+            let received = match resolving.rx.try_recv() {
+                Ok(resolution) => Some(resolution),
+                Err(mpsc::TryRecvError::Empty) if Instant::now() < resolving.deadline => None,
+                Err(mpsc::TryRecvError::Empty) => {
+                    status = status_error(&format!(
+                        "Select timed out resolving {}; try again or raise ANI_TRACK_SELECT_BUDGET_SECS.",
+                        resolving.title
+                    ));
+                    pending_resolve = None;
+                    None
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    status = status_error(&format!(
+                        "Select failed resolving {}: lookup stopped unexpectedly.",
+                        resolving.title
+                    ));
+                    pending_resolve = None;
+                    None
+                }
+            };
+            if let Some(lookup) = received
+                && let Some(resolving) = pending_resolve.take()
+            {
+                let Some(item) = items
+                    .iter()
+                    .find(|item| item.ani_id == resolving.ani_id)
+                    .cloned()
+                else {
+                    status = status_error("Select failed: entry no longer exists.");
+                    continue;
+                };
+                // Title fallback can land on a different series when allanime ordering drifts,
+                // so a title-only match is confirmed before it plays.
+                let play = match lookup {
+                    SelectLookup::Rechecked { index, warning } => Some((index, warning)),
+                    SelectLookup::Resolved(resolution) => match resolution.index {
+                        None => {
+                            status =
+                                status_error(&select_resolution_failure(&item.title, &resolution));
+                            None
+                        }
+                        Some(index) => match PendingSelect::for_title_match(
+                            &item.ani_id,
+                            resolution,
+                            resolving.episode.clone(),
+                        ) {
+                            Some(confirm) => {
+                                pending_select = Some(confirm);
+                                status =
+                                    status_info("Confirm select: Enter to play, Esc to cancel.");
+                                None
+                            }
+                            None => Some((index, None)),
+                        },
+                    },
+                    SelectLookup::Changed { reason, resolution } => match resolution.index {
+                        None => {
+                            status = status_error(&format!(
+                                "Select canceled: search results changed ({reason}) and {} could not be found again ({}).",
+                                item.title,
+                                resolution.summary()
+                            ));
+                            None
+                        }
+                        Some(index) => match PendingSelect::for_title_match(
+                            &item.ani_id,
+                            resolution,
+                            resolving.episode.clone(),
+                        ) {
+                            Some(confirm) => {
+                                pending_select = Some(confirm);
+                                status = status_info(&format!(
+                                    "Search results changed ({reason}); confirm the new match: Enter to play, Esc to cancel."
+                                ));
+                                None
+                            }
+                            None => Some((index, None)),
+                        },
+                    },
+                };
+                if let Some((index, warning)) = play {
+                    session.suspend()?;
+                    let result = match resolving.episode.as_deref() {
+                        Some(episode) => {
+                            run_episode_action(db, &item, episode, Some(index), launch)
+                        }
+                        None => run_selected_action(
+                            db,
+                            &item,
+                            TuiAction::Select,
+                            None,
+                            Some(index),
+                            launch,
+                        ),
+                    };
+                    session.resume()?;
+                    terminal.clear()?;
+
+                    status = match result {
+                        Ok(report) => match warning {
+                            Some(warning) => {
+                                status_info(&format!("{} | Warning: {warning}", report.message))
+                            }
+                            None => status_info(&report.message),
+                        },
+                        Err(err) => {
+                            status_error(&format!("Action failed for {}: {err}", item.title))
+                        }
+                    };
+                    refresh_items(
+                        db,
+                        list_order,
+                        &mut items,
+                        &mut table_state,
+                        Some(&item.ani_id),
+                        &mut status,
+                    );
+                }
+            }
+        }
         ensure_selected_episode_list(
             &items,
             &table_state,
             &mut episode_lists_by_id,
             &episode_fetch_tx,
         );
+        if prefetch_concurrency > 0 && !items.is_empty() {
+            let first = table_state.offset().min(items.len() - 1);
+            let visible_rows = visible_library_rows(terminal.size()?.height);
+            let last = (first + visible_rows).min(items.len());
+            prefetch_visible_episode_lists(
+                &items[first..last],
+                &mut episode_lists_by_id,
+                &episode_fetch_tx,
+                prefetch_concurrency,
+            );
+        }
+        let selected_item = table_state.selected().and_then(|idx| items.get(idx));
+        let selected_id = selected_item.map(|item| item.ani_id.as_str());
+        if action_row_id.as_deref() != selected_id {
+            action = action_for_row(selected_item, global_action);
+            action_row_id = selected_id.map(str::to_string);
+        }
+        if let Some(saved) = watch_later.as_mut() {
+            saved.refresh_if_stale();
+        }
+        if let Some(interval) = options.wal_checkpoint_interval
+            && last_checkpoint.elapsed() >= interval
+        {
+            // Passive, so it never waits on other connections; a failure (e.g. on a read-only
+            // fallback) only means the main file catches up at SQLite's own next checkpoint.
+            let _ = db.checkpoint_wal();
+            last_checkpoint = Instant::now();
+        }
+        let stale_ids = stale_entry_ids(&items, options.stale_after, Utc::now(), |item| {
+            episode_lists_by_id
+                .get(&item.ani_id)
+                .and_then(EpisodeListState::episode_list)
+        });
         terminal.draw(|frame| {
             draw_tui(
                 frame,
-                &items,
                 &mut table_state,
-                action,
-                &status,
-                pending_delete.as_ref(),
-                pending_notice.as_ref(),
-                &episode_lists_by_id,
+                TuiView {
+                    items: &items,
+                    action,
+                    status: &status,
+                    modals: TuiModals {
+                        pending_delete: pending_delete.as_ref(),
+                        pending_select: pending_select.as_ref(),
+                        episode_picker: episode_picker.as_ref(),
+                        pending_intro_skip: pending_intro_skip.as_ref(),
+                        pending_tags: pending_tags.as_ref(),
+                        pending_rating: pending_rating.as_ref(),
+                        pending_previous: pending_previous.as_ref(),
+                        pending_notice: pending_notice.as_ref(),
+                        pending_quit,
+                        jumping: pending_jump.is_some(),
+                        finding: quick_find.is_some(),
+                    },
+                    episode_lists_by_id: &episode_lists_by_id,
+                    show_full_id,
+                    show_next_episode,
+                    background: background.as_ref(),
+                    watch_later: watch_later.as_ref(),
+                    stale_ids: &stale_ids,
+                    timeline_offset,
+                    options,
+                    theme: &theme,
+                },
             )
         })?;
 
@@ -163,8 +744,15 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
             continue;
         }
 
-        let Event::Key(key) = event::read()? else {
-            continue;
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            Event::Resize(width, height) => {
+                // Resize and clear the buffers now, so the next frame (drawn right away, and sizing
+                // any open modal from the new area) does not diff against the old layout.
+                terminal.resize(Rect::new(0, 0, width, height))?;
+                continue;
+            }
+            _ => continue,
         };
         if key.kind != KeyEventKind::Press {
             continue;
@@ -175,6 +763,27 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
             continue;
         }
 
+        if pending_resolve.is_some() {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+                // The lookup thread finishes on its own; its result is dropped with the receiver.
+                pending_resolve = None;
+                status = status_info("Select canceled.");
+            }
+            continue;
+        }
+
+        if pending_quit {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => break,
+                KeyCode::Esc | KeyCode::Char('n') => {
+                    pending_quit = false;
+                    status = status_info("Quit canceled.");
+                }
+                _ => {}
+            }
+            continue;
+        }
+
         if let Some(dialog) = pending_delete.as_ref() {
             match key.code {
                 KeyCode::Char('y') | KeyCode::Enter => {
@@ -185,11 +794,25 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
                         Ok(true) => {
                             status =
                                 status_info(&format!("Deleted tracked entry: {deleting_title}"));
-                            refresh_items(db, &mut items, &mut table_state, None)?;
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                None,
+                                &mut status,
+                            );
                         }
                         Ok(false) => {
                             status = status_error("Delete failed: entry no longer exists.");
-                            refresh_items(db, &mut items, &mut table_state, None)?;
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                None,
+                                &mut status,
+                            );
                         }
                         Err(err) => status = status_error(&format!("Delete failed: {err}")),
                     }
@@ -203,18 +826,435 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
             continue;
         }
 
+        if pending_select.is_some() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    let Some(dialog) = pending_select.take() else {
+                        continue;
+                    };
+                    let Some(item) = items
+                        .iter()
+                        .find(|item| item.ani_id == dialog.ani_id)
+                        .cloned()
+                    else {
+                        status = status_error("Select failed: entry no longer exists.");
+                        continue;
+                    };
+
+                    // The prompt may have been open for a while; make sure `-S` still picks
+                    // the confirmed show before handing it to ani-cli.
+                    status = resolving_status(&item.title);
+                    pending_resolve = Some(spawn_select_recheck(&item, dialog));
+                }
+                KeyCode::Esc | KeyCode::Char('n') => {
+                    pending_select = None;
+                    status = status_info("Select canceled.");
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(dialog) = pending_previous.as_ref() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    let Some(item) = items
+                        .iter()
+                        .find(|item| item.ani_id == dialog.ani_id)
+                        .cloned()
+                    else {
+                        pending_previous = None;
+                        status = status_error("Previous failed: entry no longer exists.");
+                        continue;
+                    };
+                    pending_previous = None;
+                    let episode_list = episode_lists_by_id
+                        .get(&item.ani_id)
+                        .and_then(EpisodeListState::episode_list);
+
+                    session.suspend()?;
+                    let result = run_selected_action(
+                        db,
+                        &item,
+                        TuiAction::Previous,
+                        episode_list,
+                        None,
+                        launch,
+                    );
+                    session.resume()?;
+                    terminal.clear()?;
+
+                    status = match result {
+                        Ok(report) => status_info(&report.message),
+                        Err(err) => {
+                            status_error(&format!("Action failed for {}: {err}", item.title))
+                        }
+                    };
+                    refresh_items(
+                        db,
+                        list_order,
+                        &mut items,
+                        &mut table_state,
+                        Some(&item.ani_id),
+                        &mut status,
+                    );
+                }
+                KeyCode::Esc | KeyCode::Char('n') => {
+                    pending_previous = None;
+                    status = status_info("Previous canceled.");
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(picker) = episode_picker.as_mut() {
+            match key.code {
+                KeyCode::Up => picker.move_up(),
+                KeyCode::Down => picker.move_down(),
+                KeyCode::Backspace => {
+                    let mut query = picker.query.clone();
+                    query.pop();
+                    picker.set_query(query);
+                }
+                KeyCode::Char(ch) => {
+                    let query = format!("{}{ch}", picker.query);
+                    picker.set_query(query);
+                }
+                KeyCode::Esc => {
+                    episode_picker = None;
+                    status = status_info("Episode selection canceled.");
+                }
+                KeyCode::Enter => {
+                    let Some(episode) = picker.selected_episode().map(str::to_string) else {
+                        status = status_error("No episode matches the filter.");
+                        continue;
+                    };
+                    let ani_id = picker.ani_id.clone();
+                    episode_picker = None;
+                    let Some(item) = items.iter().find(|item| item.ani_id == ani_id).cloned()
+                    else {
+                        status = status_error("Select failed: entry no longer exists.");
+                        continue;
+                    };
+
+                    status = resolving_status(&item.title);
+                    pending_resolve = Some(spawn_select_resolution(&item, Some(episode)));
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(dialog) = pending_intro_skip.as_mut() {
+            match key.code {
+                KeyCode::Char(ch) if ch.is_ascii_digit() && dialog.input.len() < 4 => {
+                    dialog.input.push(ch);
+                }
+                KeyCode::Backspace => {
+                    dialog.input.pop();
+                }
+                KeyCode::Enter => {
+                    let seconds = dialog.input.parse::<u32>().unwrap_or(0);
+                    let ani_id = dialog.ani_id.clone();
+                    let title = dialog.title.clone();
+                    pending_intro_skip = None;
+                    match db.set_intro_skip(&ani_id, seconds) {
+                        Ok(true) => {
+                            status = if seconds == 0 {
+                                status_info(&format!("Intro skip cleared: {title}"))
+                            } else {
+                                status_info(&format!("Intro skip set to {seconds}s: {title}"))
+                            };
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                Some(&ani_id),
+                                &mut status,
+                            );
+                        }
+                        Ok(false) => {
+                            status = status_error("Intro skip failed: entry no longer exists.");
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                None,
+                                &mut status,
+                            );
+                        }
+                        Err(err) => status = status_error(&format!("Intro skip failed: {err}")),
+                    }
+                }
+                KeyCode::Esc => {
+                    pending_intro_skip = None;
+                    status = status_info("Intro skip unchanged.");
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(dialog) = pending_rating.as_mut() {
+            match key.code {
+                KeyCode::Char(ch) if ch.is_ascii_digit() && dialog.input.len() < 2 => {
+                    dialog.input.push(ch);
+                }
+                KeyCode::Backspace => {
+                    dialog.input.pop();
+                }
+                KeyCode::Left | KeyCode::Right => {
+                    let delta = if key.code == KeyCode::Left { -1 } else { 1 };
+                    dialog.input = adjust_rating_input(&dialog.input, delta);
+                }
+                KeyCode::Enter => {
+                    let rating = match parse_rating_input(&dialog.input) {
+                        Ok(rating) => rating,
+                        Err(err) => {
+                            status = status_error(&format!("{err:#}"));
+                            continue;
+                        }
+                    };
+                    let ani_id = dialog.ani_id.clone();
+                    let title = dialog.title.clone();
+                    pending_rating = None;
+                    match db.set_rating(&ani_id, rating) {
+                        Ok(true) => {
+                            status = match rating {
+                                Some(rating) => status_info(&format!("Rated {rating}/10: {title}")),
+                                None => status_info(&format!("Rating cleared: {title}")),
+                            };
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                Some(&ani_id),
+                                &mut status,
+                            );
+                        }
+                        Ok(false) => {
+                            status = status_error("Rating failed: entry no longer exists.");
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                None,
+                                &mut status,
+                            );
+                        }
+                        Err(err) => status = status_error(&format!("Rating failed: {err}")),
+                    }
+                }
+                KeyCode::Esc => {
+                    pending_rating = None;
+                    status = status_info("Rating unchanged.");
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(dialog) = pending_tags.as_mut() {
+            match key.code {
+                KeyCode::Char(ch) if !ch.is_control() && dialog.input.chars().count() < 120 => {
+                    dialog.input.push(ch);
+                }
+                KeyCode::Backspace => {
+                    dialog.input.pop();
+                }
+                KeyCode::Enter => {
+                    let ani_id = dialog.ani_id.clone();
+                    let title = dialog.title.clone();
+                    let input = dialog.input.clone();
+                    pending_tags = None;
+                    match db.set_tags(&ani_id, &[input]) {
+                        Ok(true) => {
+                            status = status_info(&format!("Tags updated: {title}"));
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                Some(&ani_id),
+                                &mut status,
+                            );
+                        }
+                        Ok(false) => {
+                            status = status_error("Tags failed: entry no longer exists.");
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                None,
+                                &mut status,
+                            );
+                        }
+                        Err(err) => status = status_error(&format!("Tags failed: {err}")),
+                    }
+                }
+                KeyCode::Esc => {
+                    pending_tags = None;
+                    status = status_info("Tags unchanged.");
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(input) = pending_jump.as_mut() {
+            match key.code {
+                KeyCode::Char(digit) if digit.is_ascii_digit() => {
+                    input.push(digit);
+                    status = status_info(&jump_prompt(input));
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    status = status_info(&jump_prompt(input));
+                }
+                KeyCode::Enter => {
+                    match jump_row_index(input, items.len()) {
+                        Some(idx) => {
+                            table_state.select(Some(idx));
+                            status = status_info(&format!(
+                                "Jumped to row {}: {}",
+                                idx + 1,
+                                items[idx].title
+                            ));
+                        }
+                        None => status = status_info("Jump canceled."),
+                    }
+                    pending_jump = None;
+                }
+                KeyCode::Esc => {
+                    pending_jump = None;
+                    status = status_info("Jump canceled.");
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(find) = quick_find.as_mut() {
+            match key.code {
+                KeyCode::Char(ch) => {
+                    if find.last_typed.elapsed() > QUICK_FIND_TIMEOUT {
+                        find.prefix.clear();
+                    }
+                    find.prefix.push(ch);
+                    find.last_typed = Instant::now();
+                    // A fresh first letter moves past the current row, so repeating it cycles
+                    // through the titles that start with it; a longer prefix may stay put.
+                    let selected = table_state.selected().unwrap_or(0);
+                    let start = if find.prefix.chars().count() == 1 {
+                        selected + 1
+                    } else {
+                        selected
+                    };
+                    match quick_find_index(&items, &find.prefix, start) {
+                        Some(idx) => {
+                            table_state.select(Some(idx));
+                            status = status_info(&find_prompt(&find.prefix));
+                        }
+                        None => {
+                            status = status_info(&format!(
+                                "{}  no title starts with `{}`",
+                                find_prompt(&find.prefix),
+                                find.prefix
+                            ));
+                        }
+                    }
+                }
+                KeyCode::Backspace => {
+                    find.prefix.pop();
+                    find.last_typed = Instant::now();
+                    if let Some(idx) =
+                        quick_find_index(&items, &find.prefix, table_state.selected().unwrap_or(0))
+                    {
+                        table_state.select(Some(idx));
+                    }
+                    status = status_info(&find_prompt(&find.prefix));
+                }
+                KeyCode::Enter => {
+                    quick_find = None;
+                    status = match table_state.selected().and_then(|idx| items.get(idx)) {
+                        Some(item) => status_info(&format!("Found: {}", item.title)),
+                        None => status_info("Find canceled."),
+                    };
+                }
+                KeyCode::Esc => {
+                    table_state.select(find.origin);
+                    quick_find = None;
+                    status = status_info("Find canceled.");
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        // `q` quits from the Library and the Timeline alike.
+        if key.code == KeyCode::Char('q') {
+            // Quitting drops the background run's temp history, so its progress would be lost.
+            if quit_needs_confirmation(options.confirm_quit, background.is_some()) {
+                pending_quit = true;
+                continue;
+            }
+            break;
+        }
+
+        if let Some(offset) = timeline_offset.as_mut() {
+            match key.code {
+                KeyCode::Char('w') | KeyCode::Esc => {
+                    timeline_offset = None;
+                    status = status_info("Back to the library.");
+                }
+                KeyCode::Up => *offset = offset.saturating_sub(1),
+                KeyCode::Down => *offset = (*offset + 1).min(items.len().saturating_sub(1)),
+                _ => {}
+            }
+            continue;
+        }
+
         match key.code {
-            KeyCode::Char('q') => break,
+            KeyCode::Char('w') => {
+                timeline_offset = Some(0);
+                status = status_info("Timeline: every show by when you last watched it.");
+            }
+            KeyCode::Char('f') if !items.is_empty() => {
+                status = status_info(&find_prompt(""));
+                quick_find = Some(QuickFind {
+                    prefix: String::new(),
+                    last_typed: Instant::now(),
+                    origin: table_state.selected(),
+                });
+            }
+            KeyCode::Char(digit) if digit.is_ascii_digit() && !items.is_empty() => {
+                let input = digit.to_string();
+                status = status_info(&jump_prompt(&input));
+                pending_jump = Some(input);
+            }
             KeyCode::Char('s') => {
                 session.suspend()?;
-                let result = run_ani_cli_search(db);
+                let result = run_ani_cli_search(db, launch);
                 session.resume()?;
                 terminal.clear()?;
 
                 match result {
                     Ok((msg, changed_id)) => {
                         status = status_info(&msg);
-                        refresh_items(db, &mut items, &mut table_state, changed_id.as_deref())?;
+                        refresh_items(
+                            db,
+                            list_order,
+                            &mut items,
+                            &mut table_state,
+                            changed_id.as_deref(),
+                            &mut status,
+                        );
                     }
                     Err(err) => status = status_error(&format!("Search failed: {err}")),
                 }
@@ -232,8 +1272,311 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
                     table_state.select(Some(next));
                 }
             }
-            KeyCode::Left => action = action.move_left(),
-            KeyCode::Right => action = action.move_right(),
+            KeyCode::Left | KeyCode::Right | KeyCode::BackTab | KeyCode::Tab => {
+                action = if matches!(key.code, KeyCode::Left | KeyCode::BackTab) {
+                    action.move_left()
+                } else {
+                    action.move_right()
+                };
+                let row_default = table_state
+                    .selected()
+                    .and_then(|idx| items.get(idx))
+                    .and_then(|item| item.default_action.as_deref());
+                if row_default.is_none() {
+                    global_action = action;
+                }
+            }
+            KeyCode::Char('p') => {
+                if let Some(item) = table_state.selected().and_then(|idx| items.get(idx)) {
+                    let ani_id = item.ani_id.clone();
+                    let title = item.title.clone();
+                    let clearing = item.default_action.as_deref() == Some(action.as_str());
+                    let stored = (!clearing).then(|| action.as_str());
+                    match db.set_default_action(&ani_id, stored) {
+                        Ok(true) => {
+                            status = status_info(&if clearing {
+                                format!("Default action cleared: {title}")
+                            } else {
+                                format!("Default action for {title}: {}", action.label())
+                            });
+                            if clearing {
+                                global_action = action;
+                            }
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                Some(&ani_id),
+                                &mut status,
+                            );
+                        }
+                        Ok(false) => {
+                            status = status_error("Default action failed: entry no longer exists.");
+                            refresh_items(
+                                db,
+                                list_order,
+                                &mut items,
+                                &mut table_state,
+                                None,
+                                &mut status,
+                            );
+                        }
+                        Err(err) => status = status_error(&format!("Default action failed: {err}")),
+                    }
+                }
+            }
+            KeyCode::Char('n') => {
+                let next = next_unwatched_index(&items, table_state.selected(), |item| {
+                    episode_lists_by_id
+                        .get(&item.ani_id)
+                        .and_then(EpisodeListState::episode_list)
+                });
+                match next {
+                    Some(idx) => {
+                        table_state.select(Some(idx));
+                        status = status_info(&format!(
+                            "Next unwatched: {} (episode {}).",
+                            items[idx].title, items[idx].last_episode
+                        ));
+                    }
+                    None => status = status_info("No shows with unwatched episodes."),
+                }
+            }
+            KeyCode::Char('a') => {
+                show_full_id = !show_full_id;
+                status = status_info(if show_full_id {
+                    "Showing full Ani ID."
+                } else {
+                    "Showing short Ani ID."
+                });
+            }
+            KeyCode::Char('v') => {
+                show_next_episode = !show_next_episode;
+                status = status_info(if show_next_episode {
+                    "Episode column shows what Next will play."
+                } else {
+                    "Episode column shows the last watched episode."
+                });
+            }
+            KeyCode::Char('u') => {
+                let selected_id = table_state
+                    .selected()
+                    .and_then(|idx| items.get(idx))
+                    .map(|item| item.ani_id.clone());
+                match sync_progress_from_history(db) {
+                    Ok(msg) => {
+                        status = status_info(&msg.replace('\n', " | "));
+                        refresh_items(
+                            db,
+                            list_order,
+                            &mut items,
+                            &mut table_state,
+                            selected_id.as_deref(),
+                            &mut status,
+                        );
+                    }
+                    Err(err) => status = status_error(&format!("Sync failed: {err}")),
+                }
+            }
+            KeyCode::Char('r') => {
+                let cleared = clear_episode_lists(&mut episode_lists_by_id);
+                status = status_info(&format!(
+                    "Cleared {cleared} cached episode list(s); refetching."
+                ));
+            }
+            KeyCode::Char('R') => {
+                let Some(item) = table_state.selected().and_then(|idx| items.get(idx)) else {
+                    continue;
+                };
+                let episode_state = episode_lists_by_id.get(&item.ani_id);
+                if episode_state.is_none_or(EpisodeListState::is_loading) {
+                    status = status_info("Episode list is still loading; try restarting again.");
+                    continue;
+                }
+                let episode_list = episode_state.and_then(EpisodeListState::episode_list);
+                let restart = restart_episode(episode_list);
+                let first =
+                    first_episode_after(&restart, episode_list).unwrap_or_else(|| "1".to_string());
+                let ani_id = item.ani_id.clone();
+                status = match db.upsert_seen(&item.ani_id, &item.title, &restart) {
+                    Ok(()) => status_info(&format!(
+                        "Restarted {}: Next plays episode {first}.",
+                        item.title
+                    )),
+                    Err(err) => status_error(&format!("Restart failed: {err}")),
+                };
+                refresh_items(
+                    db,
+                    list_order,
+                    &mut items,
+                    &mut table_state,
+                    Some(&ani_id),
+                    &mut status,
+                );
+            }
+            KeyCode::Char('o') => {
+                list_order.sort = match list_order.sort {
+                    ListSort::Watched => ListSort::Added,
+                    ListSort::Added => ListSort::Rating,
+                    ListSort::Rating => ListSort::Watched,
+                };
+                let selected_id = table_state
+                    .selected()
+                    .and_then(|idx| items.get(idx))
+                    .map(|item| item.ani_id.clone());
+                status = status_info(match list_order.sort {
+                    ListSort::Watched => "Sorted by last watched.",
+                    ListSort::Added => "Sorted by recently added.",
+                    ListSort::Rating => "Sorted by rating.",
+                });
+                refresh_items(
+                    db,
+                    list_order,
+                    &mut items,
+                    &mut table_state,
+                    selected_id.as_deref(),
+                    &mut status,
+                );
+            }
+            KeyCode::Char('e') => {
+                let editor = match editor_argv(env::var("EDITOR").ok()) {
+                    Ok(editor) => editor,
+                    Err(err) => {
+                        status = status_error(&err.to_string());
+                        continue;
+                    }
+                };
+                let histfile = ani_cli_histfile();
+                let selected_id = table_state
+                    .selected()
+                    .and_then(|idx| items.get(idx))
+                    .map(|item| item.ani_id.clone());
+
+                session.suspend()?;
+                let edited = open_in_editor(&editor, &histfile);
+                session.resume()?;
+                terminal.clear()?;
+
+                if let Err(err) = edited {
+                    status = status_error(&format!("Editor failed: {err:#}"));
+                    continue;
+                }
+                match sync_progress_from_history(db) {
+                    Ok(msg) => {
+                        status = status_info(&format!(
+                            "Edited {} | {}",
+                            histfile.display(),
+                            msg.replace('\n', " | ")
+                        ));
+                        refresh_items(
+                            db,
+                            list_order,
+                            &mut items,
+                            &mut table_state,
+                            selected_id.as_deref(),
+                            &mut status,
+                        );
+                    }
+                    Err(err) => status = status_error(&format!("Sync failed: {err}")),
+                }
+            }
+            KeyCode::Char('i') => {
+                let Some(selected_item) = table_state.selected().and_then(|idx| items.get(idx))
+                else {
+                    status = status_error("Intro skip failed: no entry selected.");
+                    continue;
+                };
+                pending_intro_skip = Some(PendingIntroSkip {
+                    ani_id: selected_item.ani_id.clone(),
+                    title: selected_item.title.clone(),
+                    input: match selected_item.intro_skip_seconds {
+                        0 => String::new(),
+                        seconds => seconds.to_string(),
+                    },
+                });
+                status =
+                    status_info("Enter intro skip seconds (0 or empty clears), Esc to cancel.");
+            }
+            KeyCode::Char('b') => {
+                if let Some(playback) = background.as_ref() {
+                    status = status_error(&format!(
+                        "Already playing in background: {} (pid {}).",
+                        playback.item.title,
+                        playback.pid()
+                    ));
+                    continue;
+                }
+                if !matches!(action, TuiAction::Next) {
+                    status = status_error(
+                        "Background playback only supports Next; other actions need the terminal.",
+                    );
+                    continue;
+                }
+                let Some(selected_item) = table_state.selected().and_then(|idx| items.get(idx))
+                else {
+                    status = status_error("Background playback failed: no entry selected.");
+                    continue;
+                };
+                let episode_list = episode_lists_by_id
+                    .get(&selected_item.ani_id)
+                    .and_then(EpisodeListState::episode_list);
+                if !entry_has_next_episode(selected_item, episode_list) {
+                    status = status_info("No next episode available.");
+                    continue;
+                }
+                if let Some(first) = first_episode_after(&selected_item.last_episode, episode_list)
+                {
+                    status = status_info(&format!(
+                        "Background playback cannot start a restarted show; press Enter to play episode {first}."
+                    ));
+                    continue;
+                }
+                match spawn_ani_cli_continue_detached(selected_item, launch) {
+                    Ok(playback) => {
+                        status = status_info(&format!(
+                            "Playing in background: {} (pid {}).",
+                            selected_item.title,
+                            playback.pid()
+                        ));
+                        background = Some(playback);
+                    }
+                    Err(err) => {
+                        status = status_error(&format!("Background playback failed: {err}"))
+                    }
+                }
+            }
+            KeyCode::Char('t') => {
+                let Some(selected_item) = table_state.selected().and_then(|idx| items.get(idx))
+                else {
+                    status = status_error("Tags failed: no entry selected.");
+                    continue;
+                };
+                pending_tags = Some(PendingTags {
+                    ani_id: selected_item.ani_id.clone(),
+                    title: selected_item.title.clone(),
+                    input: selected_item.tags.join(", "),
+                });
+                status = status_info("Enter comma-separated tags (empty clears), Esc to cancel.");
+            }
+            KeyCode::Char('*') => {
+                let Some(selected_item) = table_state.selected().and_then(|idx| items.get(idx))
+                else {
+                    status = status_error("Rating failed: no entry selected.");
+                    continue;
+                };
+                pending_rating = Some(PendingRating {
+                    ani_id: selected_item.ani_id.clone(),
+                    title: selected_item.title.clone(),
+                    input: selected_item
+                        .rating
+                        .map(|rating| rating.to_string())
+                        .unwrap_or_default(),
+                });
+                status = status_info(
+                    "Enter a rating 1-10 or use Left/Right (empty clears), Esc to cancel.",
+                );
+            }
             KeyCode::Char('d') => {
                 let Some(selected) = table_state.selected() else {
                     status = status_error("Delete failed: no entry selected.");
@@ -250,7 +1593,26 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
                 });
                 status = status_info("Confirm delete: y/Enter to delete, n/Esc to cancel.");
             }
-            KeyCode::Enter => {
+            KeyCode::Enter | KeyCode::Char('c') | KeyCode::Char('l') => {
+                // `c` plays Next on the continue-watching card's show and `l` replays the last
+                // watched show, whatever is selected and whichever action is active.
+                let action = if key.code == KeyCode::Char('c') {
+                    let Some((idx, _)) = continue_watching(&items, &episode_lists_by_id) else {
+                        status = status_info("Nothing to continue.");
+                        continue;
+                    };
+                    table_state.select(Some(idx));
+                    TuiAction::Next
+                } else if key.code == KeyCode::Char('l') {
+                    let Some(idx) = last_watched_index(&items) else {
+                        status = status_info("Nothing to replay.");
+                        continue;
+                    };
+                    table_state.select(Some(idx));
+                    TuiAction::Replay
+                } else {
+                    action
+                };
                 let Some(selected) = table_state.selected() else {
                     continue;
                 };
@@ -263,8 +1625,17 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
                     .and_then(EpisodeListState::episode_list);
 
                 if matches!(action, TuiAction::Next) {
-                    let total_eps = parse_title_and_total_eps(&selected_item.title).1;
-                    if !has_next_episode(&selected_item.last_episode, total_eps, episode_list) {
+                    if is_movie(selected_item) {
+                        pending_notice = Some(PendingNotice {
+                            message: format!(
+                                "This is a movie, so there is no next episode.\n\n{}\n\nUse Replay to watch it again. Press any key to continue.",
+                                truncate(&selected_item.title, 50)
+                            ),
+                        });
+                        status = status_info("Movies have no next episode; use Replay.");
+                        continue;
+                    }
+                    if !entry_has_next_episode(selected_item, episode_list) {
                         pending_notice = Some(PendingNotice {
                             message: format!(
                                 "No more episodes available.\n\n{}\n\nPress any key to continue.",
@@ -289,16 +1660,57 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
                     continue;
                 }
 
+                if matches!(action, TuiAction::Previous)
+                    && let Some(confirm) = PendingPrevious::for_crossing(
+                        selected_item,
+                        episode_list,
+                        options.confirm_previous,
+                    )
+                {
+                    pending_previous = Some(confirm);
+                    status = status_info("Confirm previous: y/Enter to play, n/Esc to cancel.");
+                    continue;
+                }
+
+                if matches!(action, TuiAction::Select)
+                    && let Some(episodes) = episode_lists_by_id
+                        .get(&selected_item.ani_id)
+                        .and_then(EpisodeListState::indexed)
+                {
+                    episode_picker = Some(EpisodePicker::new(selected_item, episodes));
+                    status = status_info(
+                        "Pick an episode: type to filter, Enter to play, Esc to cancel.",
+                    );
+                    continue;
+                }
+
+                if matches!(action, TuiAction::Select) {
+                    status = resolving_status(&selected_item.title);
+                    pending_resolve = Some(spawn_select_resolution(selected_item, None));
+                    continue;
+                }
+
                 let selected_id = items[selected].ani_id.clone();
                 let selected_title = items[selected].title.clone();
+                let advance = options.advance_selection_after_play
+                    && matches!(action, TuiAction::Next | TuiAction::Replay);
+                let mut preferred_id = selected_id.clone();
 
                 session.suspend()?;
-                let result = run_selected_action(db, &items[selected], action, episode_list);
+                let result =
+                    run_selected_action(db, &items[selected], action, episode_list, None, launch);
                 session.resume()?;
                 terminal.clear()?;
 
                 match result {
-                    Ok(msg) => status = status_info(&msg),
+                    Ok(report) => {
+                        status = status_info(&report.message);
+                        if let Some(next_id) =
+                            selection_after_play(&items, selected, advance && report.played)
+                        {
+                            preferred_id = next_id.to_string();
+                        }
+                    }
                     Err(err) => {
                         let no_previous = matches!(action, TuiAction::Previous)
                             && err.chain().any(|cause| {
@@ -319,7 +1731,14 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
                     }
                 }
 
-                refresh_items(db, &mut items, &mut table_state, Some(&selected_id))?;
+                refresh_items(
+                    db,
+                    list_order,
+                    &mut items,
+                    &mut table_state,
+                    Some(&preferred_id),
+                    &mut status,
+                );
             }
             _ => {}
         }
@@ -327,5 +1746,272 @@ pub(crate) fn run_tui(db: &Database) -> Result<()> {
 
     terminal.show_cursor()?;
     session.leave()?;
+
+    if let Some(path) = options.state_file.as_deref() {
+        ui_state.selected_ani_id = table_state
+            .selected()
+            .and_then(|idx| items.get(idx))
+            .map(|item| item.ani_id.clone());
+        ui_state.sort = Some(list_order.sort);
+        ui_state.tie_break = Some(list_order.tie_break);
+        if let Err(err) = ui_state.save(path) {
+            eprintln!("{} {err:#}", warning_label());
+        }
+    }
     Ok(())
 }
+
+/// Finds the next row after `selected` (wrapping once, ending back on `selected`) whose show has an
+/// episode past its tracked progress. Cached episode lists are used where available; otherwise the
+/// title's episode count decides.
+pub(crate) fn next_unwatched_index<'a>(
+    items: &'a [SeenEntry],
+    selected: Option<usize>,
+    episode_list_for: impl Fn(&'a SeenEntry) -> Option<&'a [String]>,
+) -> Option<usize> {
+    if items.is_empty() {
+        return None;
+    }
+    let start = selected.unwrap_or(items.len() - 1);
+    (1..=items.len())
+        .map(|offset| (start + offset) % items.len())
+        .find(|&idx| entry_has_next_episode(&items[idx], episode_list_for(&items[idx])))
+}
+
+/// The row of the most recently watched show, whatever the current sort.
+pub(crate) fn last_watched_index(items: &[SeenEntry]) -> Option<usize> {
+    items
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.last_seen_at.cmp(&b.last_seen_at))
+        .map(|(idx, _)| idx)
+}
+
+/// Renders the main view (no popups) into an off-screen buffer of the given size, so layout can
+/// be checked without a terminal.
+#[cfg(test)]
+pub(crate) fn render_main_view(
+    items: &[SeenEntry],
+    selected: Option<usize>,
+    status: &str,
+    options: &TuiOptions,
+    size: (u16, u16),
+) -> Result<ratatui::buffer::Buffer> {
+    let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(size.0, size.1))?;
+    let mut table_state = TableState::default();
+    table_state.select(selected);
+    let action = action_for_row(selected.and_then(|idx| items.get(idx)), TuiAction::Next);
+    terminal.draw(|frame| {
+        draw_tui(
+            frame,
+            &mut table_state,
+            TuiView {
+                items,
+                action,
+                status,
+                modals: TuiModals::default(),
+                episode_lists_by_id: &HashMap::new(),
+                show_full_id: false,
+                show_next_episode: false,
+                background: None,
+                watch_later: None,
+                stale_ids: &HashSet::new(),
+                timeline_offset: None,
+                options,
+                theme: &TuiTheme::default(),
+            },
+        )
+    })?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+/// Splits `$EDITOR` into a program and its arguments (e.g. `code --wait`).
+pub(crate) fn editor_argv(editor: Option<String>) -> Result<Vec<String>> {
+    let argv = editor
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if argv.is_empty() {
+        bail!("$EDITOR is not set; set it (e.g. EDITOR=nano) to edit the ani-cli history");
+    }
+    Ok(argv)
+}
+
+/// The action to preselect when `item` becomes the selected row: its stored default, or the
+/// global action when it has none (or an unrecognized one).
+pub(crate) fn action_for_row(item: Option<&SeenEntry>, global_action: TuiAction) -> TuiAction {
+    item.and_then(|item| item.default_action.as_deref())
+        .and_then(TuiAction::parse)
+        .unwrap_or(global_action)
+}
+
+/// Maps typed quick-jump digits to a 0-based row, treating the input as a 1-based row number
+/// clamped to the table. Returns `None` for empty input or an empty table.
+pub(crate) fn jump_row_index(input: &str, len: usize) -> Option<usize> {
+    if input.is_empty() || len == 0 {
+        return None;
+    }
+    // Inputs too long to parse are still a row number past the end, so they clamp to the last row.
+    let row = input.parse::<usize>().unwrap_or(usize::MAX);
+    Some(row.clamp(1, len) - 1)
+}
+
+/// Whether `q` asks before quitting: only with `confirm_quit` enabled, and only while background
+/// playback runs, whose progress quitting would lose. With nothing in progress it quits at once.
+pub(crate) fn quit_needs_confirmation(confirm_quit: bool, background_running: bool) -> bool {
+    confirm_quit && background_running
+}
+
+/// Ids of entries last watched more than `stale_after` before `now`, leaving out entries whose
+/// timestamp does not parse and shows watched to the end (judged like `n` does), which have
+/// nothing left to continue.
+pub(crate) fn stale_entry_ids<'a>(
+    items: &'a [SeenEntry],
+    stale_after: Option<Duration>,
+    now: DateTime<Utc>,
+    episode_list_for: impl Fn(&'a SeenEntry) -> Option<&'a [String]>,
+) -> HashSet<String> {
+    let Some(cutoff) = stale_after
+        .and_then(|age| chrono::Duration::from_std(age).ok())
+        .and_then(|age| now.checked_sub_signed(age))
+    else {
+        return HashSet::new();
+    };
+    items
+        .iter()
+        .filter(|item| entry_has_next_episode(item, episode_list_for(item)))
+        .filter(|item| {
+            DateTime::parse_from_rfc3339(&item.last_seen_at).is_ok_and(|seen| seen < cutoff)
+        })
+        .map(|item| item.ani_id.clone())
+        .collect()
+}
+
+/// The first row from `start` on (wrapping around) whose title starts with `prefix`, compared
+/// ignoring case, punctuation, and spacing. `None` for an empty prefix or no match.
+pub(crate) fn quick_find_index(items: &[SeenEntry], prefix: &str, start: usize) -> Option<usize> {
+    let prefix = normalize_title_for_match(prefix);
+    if prefix.is_empty() || items.is_empty() {
+        return None;
+    }
+    (0..items.len())
+        .map(|offset| (start + offset) % items.len())
+        .find(|&idx| normalize_title_for_match(&items[idx].title).starts_with(&prefix))
+}
+
+/// The "continue watching" card: the row of the most recently watched entry, when it has a next
+/// episode, and the episode Next would play if it can be named.
+pub(super) fn continue_watching(
+    items: &[SeenEntry],
+    episode_lists_by_id: &HashMap<String, EpisodeListState>,
+) -> Option<(usize, Option<String>)> {
+    let (idx, item) = items
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.last_seen_at.cmp(&b.last_seen_at))?;
+    let episode_list = episode_lists_by_id
+        .get(&item.ani_id)
+        .and_then(EpisodeListState::episode_list);
+    entry_has_next_episode(item, episode_list)
+        .then(|| (idx, next_episode_label(item, episode_list)))
+}
+
+/// Library episode column for `item`: the stored (furthest watched) episode, or with
+/// `show_next` the episode Next will play, `done` when caught up, or `-` when it cannot be named.
+/// With `show_ordinal`, a stored episode whose position in the fetched list differs from its label
+/// gets that position appended (`25 (27)`), matching the progress gauge; without a list it is
+/// shown alone.
+pub(crate) fn episode_column_text(
+    item: &SeenEntry,
+    episode_list: Option<&EpisodeList>,
+    show_next: bool,
+    show_ordinal: bool,
+) -> String {
+    if !show_next {
+        let label = display_episode_label(&item.last_episode);
+        let ordinal = episode_list
+            .filter(|_| show_ordinal && !is_movie(item))
+            .and_then(|episodes| {
+                let total = entry_title_and_total(item).1?;
+                episode_progress_position(&item.last_episode, total, Some(episodes))
+            })
+            .filter(|&position| parse_episode_u32(&item.last_episode) != Some(position));
+        return match ordinal {
+            Some(position) => format!("{label} ({position})"),
+            None => label,
+        };
+    }
+    let episode_list = episode_list.map(EpisodeList::labels);
+    if !entry_has_next_episode(item, episode_list) {
+        return "done".to_string();
+    }
+    next_episode_label(item, episode_list)
+        .map(|label| display_episode_label(&label))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// The show to select once the row at `played_idx` has played: the row that followed it in
+/// `items` (the order before the refresh re-sorts it), or `None` to keep the played show. The
+/// id is looked up again after the refresh, so it holds even when the played show moves.
+pub(crate) fn selection_after_play(
+    items: &[SeenEntry],
+    played_idx: usize,
+    advance: bool,
+) -> Option<&str> {
+    if !advance {
+        return None;
+    }
+    items.get(played_idx + 1).map(|item| item.ani_id.as_str())
+}
+
+/// Reads the rating prompt: empty clears the rating, anything else must be within
+/// `RATING_RANGE`.
+pub(crate) fn parse_rating_input(input: &str) -> Result<Option<u8>> {
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+    match input.trim().parse::<u8>() {
+        Ok(rating) if RATING_RANGE.contains(&rating) => Ok(Some(rating)),
+        _ => bail!("Rating must be a whole number from 1 to 10."),
+    }
+}
+
+/// Steps the rating prompt by `delta`, clamped to `RATING_RANGE`. From an empty or invalid
+/// prompt, Right starts at the lowest rating and Left at the highest.
+pub(crate) fn adjust_rating_input(input: &str, delta: i8) -> String {
+    let (low, high) = (*RATING_RANGE.start(), *RATING_RANGE.end());
+    let rating = match parse_rating_input(input) {
+        Ok(Some(rating)) => rating.saturating_add_signed(delta).clamp(low, high),
+        _ if delta < 0 => high,
+        _ => low,
+    };
+    rating.to_string()
+}
+
+fn find_prompt(prefix: &str) -> String {
+    format!("Find: {prefix}_  (type a title's start, Enter keep, Esc cancel)")
+}
+
+fn jump_prompt(input: &str) -> String {
+    format!("Jump to row: {input}_  (Enter jump, Esc cancel)")
+}
+
+fn resolving_status(title: &str) -> String {
+    status_info(&format!(
+        "Resolving episode selection for {title}... (Esc to cancel)"
+    ))
+}
+
+fn select_resolution_failure(title: &str, resolution: &SelectNthResolution) -> String {
+    let mut message = format!(
+        "Action failed for {title}: failed to resolve current show for episode selection ({})",
+        resolution.summary()
+    );
+    for warning in &resolution.warnings {
+        message.push_str(" | Warning: ");
+        message.push_str(warning);
+    }
+    message
+}
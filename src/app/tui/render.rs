@@ -1,33 +1,97 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use chrono::{DateTime, Local, TimeZone};
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::{Line, Span};
+use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{
-    Block, BorderType, Borders, Cell, Clear, Gauge, Padding, Paragraph, Row, Table, TableState,
-    Wrap,
+    Block, BorderType, Borders, Cell, Clear, Gauge, List, ListItem, ListState, Padding, Paragraph,
+    Row, Table, TableState, Wrap,
 };
+use unicode_width::UnicodeWidthStr;
 
 use crate::db::SeenEntry;
 
 use super::super::episode::{
-    build_progress_gauge, format_episode_progress_text, format_last_seen_display_tui,
-    parse_title_and_total_eps, truncate,
+    AiringStatus, ProgressSource, build_coverage_gauge, build_progress_gauge, display_ani_id,
+    display_episode_label, entry_title_and_total, episode_list_ends_before,
+    format_episode_progress_text, format_last_seen_display_tui, is_movie, title_case_display,
+    truncate, wrap_title,
+};
+use super::super::external_provider_label;
+use super::super::tracking::BackgroundPlayback;
+use super::super::watch_later::{WatchLater, format_resume_position};
+use super::theme::TuiTheme;
+use super::{
+    EpisodeListState, EpisodePicker, LibraryColumn, PendingDelete, PendingIntroSkip, PendingNotice,
+    PendingPrevious, PendingRating, PendingSelect, PendingTags, TuiAction, TuiOptions,
+    continue_watching, episode_column_text,
 };
-use super::{EpisodeListState, PendingDelete, PendingNotice, TuiAction};
 
-#[allow(clippy::too_many_arguments)]
-pub(super) fn draw_tui(
-    frame: &mut Frame,
-    items: &[SeenEntry],
-    table_state: &mut TableState,
-    action: TuiAction,
-    status: &str,
-    pending_delete: Option<&PendingDelete>,
-    pending_notice: Option<&PendingNotice>,
-    episode_lists_by_id: &HashMap<String, EpisodeListState>,
-) {
+/// The open prompt or dialog, if any. At most one is set while the TUI runs.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct TuiModals<'a> {
+    pub(super) pending_delete: Option<&'a PendingDelete>,
+    pub(super) pending_select: Option<&'a PendingSelect>,
+    pub(super) episode_picker: Option<&'a EpisodePicker>,
+    pub(super) pending_intro_skip: Option<&'a PendingIntroSkip>,
+    pub(super) pending_tags: Option<&'a PendingTags>,
+    pub(super) pending_rating: Option<&'a PendingRating>,
+    pub(super) pending_previous: Option<&'a PendingPrevious>,
+    pub(super) pending_notice: Option<&'a PendingNotice>,
+    pub(super) pending_quit: bool,
+    pub(super) jumping: bool,
+    pub(super) finding: bool,
+}
+
+/// Everything one frame draws besides the table selection, which rendering updates.
+#[derive(Clone, Copy)]
+pub(super) struct TuiView<'a> {
+    pub(super) items: &'a [SeenEntry],
+    pub(super) action: TuiAction,
+    pub(super) status: &'a str,
+    pub(super) modals: TuiModals<'a>,
+    pub(super) episode_lists_by_id: &'a HashMap<String, EpisodeListState>,
+    pub(super) show_full_id: bool,
+    pub(super) show_next_episode: bool,
+    pub(super) background: Option<&'a BackgroundPlayback>,
+    pub(super) watch_later: Option<&'a WatchLater>,
+    pub(super) stale_ids: &'a HashSet<String>,
+    pub(super) timeline_offset: Option<usize>,
+    pub(super) options: &'a TuiOptions,
+    pub(super) theme: &'a TuiTheme,
+}
+
+pub(super) fn draw_tui(frame: &mut Frame, table_state: &mut TableState, view: TuiView) {
+    let TuiView {
+        items,
+        action,
+        status,
+        modals:
+            TuiModals {
+                pending_delete,
+                pending_select,
+                episode_picker,
+                pending_intro_skip,
+                pending_tags,
+                pending_rating,
+                pending_previous,
+                pending_notice,
+                pending_quit,
+                jumping,
+                finding,
+            },
+        episode_lists_by_id,
+        show_full_id,
+        show_next_episode,
+        background,
+        watch_later,
+        stale_ids,
+        timeline_offset,
+        options,
+        theme,
+    } = view;
     let bg = Block::default().style(Style::default().bg(Color::Black));
     frame.render_widget(bg, frame.area());
 
@@ -48,7 +112,7 @@ pub(super) fn draw_tui(
         selected_idx.to_string()
     };
     let mode_text = action.label();
-    let header = Paragraph::new(Line::from(vec![
+    let mut header_spans = vec![
         Span::styled(
             "ANITRACK",
             Style::default()
@@ -67,9 +131,34 @@ pub(super) fn draw_tui(
         ),
         Span::styled("   ", Style::default()),
         Span::styled(mode_text, Style::default().fg(Color::Yellow)),
-    ]))
-    .alignment(Alignment::Center)
-    .block(panel_block("Dashboard"));
+    ];
+    if let Some((idx, next_label)) = continue_watching(items, episode_lists_by_id) {
+        let next = next_label
+            .map(|label| format!(" · ep {}", display_episode_label(&label)))
+            .unwrap_or_default();
+        header_spans.push(Span::styled("   ", Style::default()));
+        header_spans.push(Span::styled(
+            format!(
+                "continue: {}{next} [c]",
+                truncate(&display_title_and_total(&items[idx], options).0, 28)
+            ),
+            Style::default().fg(Color::Rgb(255, 200, 120)),
+        ));
+    }
+    if let Some(playback) = background {
+        header_spans.push(Span::styled("   ", Style::default()));
+        header_spans.push(Span::styled(
+            format!(
+                "▶ background: {} (pid {})",
+                truncate(&display_title_and_total(&playback.item, options).0, 24),
+                playback.pid()
+            ),
+            Style::default().fg(Color::Rgb(130, 220, 150)),
+        ));
+    }
+    let header = Paragraph::new(Line::from(header_spans))
+        .alignment(Alignment::Center)
+        .block(panel_block("Dashboard"));
     frame.render_widget(header, chunks[0]);
 
     let body_chunks = Layout::default()
@@ -81,72 +170,185 @@ pub(super) fn draw_tui(
         .constraints([Constraint::Min(8), Constraint::Length(3)])
         .split(body_chunks[1]);
 
+    let title_width = library_title_width(
+        body_chunks[0].width.saturating_sub(2),
+        theme.highlight_symbol.width() as u16,
+        &options.columns,
+    );
     let rows: Vec<Row> = items
         .iter()
-        .map(|item| {
-            let (display_title, total_eps) = parse_title_and_total_eps(&item.title);
-            Row::new(vec![
-                Cell::from(display_title),
-                Cell::from(
-                    total_eps
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|| "-".to_string()),
-                ),
-                Cell::from(item.last_episode.clone()),
-                Cell::from(format_last_seen_display_tui(&item.last_seen_at)),
-            ])
+        .enumerate()
+        .map(|(idx, item)| {
+            let (display_title, total_eps) = display_title_and_total(item, options);
+            let title_lines = if !options.wrap_selected_title {
+                vec![display_title]
+            } else if table_state.selected() == Some(idx) {
+                wrap_title(&display_title, title_width, 2)
+            } else {
+                vec![truncate(&display_title, title_width)]
+            };
+            let height = title_lines.len().max(1) as u16;
+            let total_text = if is_movie(item) {
+                "Movie".to_string()
+            } else {
+                total_eps
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            let mut title_cell = Some(Cell::from(Text::from_iter(title_lines)));
+            Row::new(options.columns.iter().map(|column| {
+                match column {
+                    LibraryColumn::Title => title_cell.take().unwrap_or_default(),
+                    LibraryColumn::Total => Cell::from(total_text.clone()),
+                    LibraryColumn::LastEp => Cell::from(episode_column_text(
+                        item,
+                        episode_lists_by_id
+                            .get(&item.ani_id)
+                            .and_then(EpisodeListState::indexed),
+                        show_next_episode,
+                        options.show_episode_ordinal,
+                    )),
+                    LibraryColumn::Rating => Cell::from(
+                        item.rating
+                            .map(|rating| rating.to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                    LibraryColumn::LastSeen => {
+                        Cell::from(format_last_seen_display_tui(&item.last_seen_at))
+                    }
+                }
+            }))
+            .height(height)
+            .style(if stale_ids.contains(&item.ani_id) {
+                Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC)
+            } else {
+                Style::default()
+            })
         })
         .collect();
 
-    let table = Table::new(
-        rows,
-        [
-            Constraint::Percentage(46),
-            Constraint::Length(10),
-            Constraint::Length(10),
-            Constraint::Length(33),
-        ],
-    )
-    .header(
-        Row::new(vec!["Title", "Total Eps", "Last Ep", "Last Seen"]).style(
-            Style::default()
-                .fg(Color::Rgb(110, 170, 255))
-                .add_modifier(Modifier::BOLD),
-        ),
-    )
-    .block(panel_block("Library"))
-    .row_highlight_style(
-        Style::default()
-            .bg(Color::Rgb(110, 170, 255))
-            .fg(Color::Black)
-            .add_modifier(Modifier::BOLD),
-    )
-    .highlight_symbol("▸ ");
-    frame.render_stateful_widget(table, body_chunks[0], table_state);
+    let table = Table::new(rows, library_column_widths(&options.columns))
+        .header(
+            Row::new(options.columns.iter().map(|column| match column {
+                LibraryColumn::Title => "Title",
+                LibraryColumn::Total => "Total Eps",
+                LibraryColumn::LastEp if show_next_episode => "Next Ep",
+                LibraryColumn::LastEp => "Last Ep",
+                LibraryColumn::Rating => "Rating",
+                LibraryColumn::LastSeen => "Last Seen",
+            }))
+            .style(
+                Style::default()
+                    .fg(Color::Rgb(110, 170, 255))
+                    .add_modifier(Modifier::BOLD),
+            ),
+        )
+        .block(panel_block("Library"))
+        .row_highlight_style(theme.highlight_style())
+        .highlight_symbol(theme.highlight_symbol.as_str());
+    match timeline_offset {
+        Some(offset) => draw_timeline(frame, body_chunks[0], items, offset, options),
+        None => frame.render_stateful_widget(table, body_chunks[0], table_state),
+    }
 
     let (selection_text, gauge) = match table_state.selected().and_then(|idx| items.get(idx)) {
         Some(item) => {
-            let (title, total_eps) = parse_title_and_total_eps(&item.title);
+            let (title, total_eps) = display_title_and_total(item, options);
             let total_eps_text = total_eps
                 .map(|v| v.to_string())
                 .unwrap_or_else(|| "-".to_string());
             let episode_state = episode_lists_by_id.get(&item.ani_id);
-            let episode_list = episode_state.and_then(EpisodeListState::episode_list);
+            let episode_list = episode_state.and_then(EpisodeListState::indexed);
+            let movie = is_movie(item);
             let episode_progress_text = total_eps
-                .map(|total| format_episode_progress_text(&item.last_episode, total, episode_list))
-                .unwrap_or_else(|| format!("{} of {}", item.last_episode, total_eps_text));
-            let gauge = total_eps
-                .and_then(|total| build_progress_gauge(&item.last_episode, total, episode_list));
+                .filter(|_| !movie)
+                .map(|total| {
+                    format_episode_progress_text(
+                        &item.last_episode,
+                        total,
+                        episode_list,
+                        options.episode_format,
+                    )
+                })
+                .unwrap_or_else(|| {
+                    if movie {
+                        "Movie".to_string()
+                    } else {
+                        format!(
+                            "{} of {}",
+                            display_episode_label(&item.last_episode),
+                            total_eps_text
+                        )
+                    }
+                });
+            let gauge = total_eps.filter(|_| !movie).and_then(|total| {
+                if let Some(watched) = &item.watched_episodes
+                    && let Some((ratio, label)) = build_coverage_gauge(watched, total)
+                {
+                    return Some((ratio, label, ProgressSource::WatchedEpisodes));
+                }
+                build_progress_gauge(&item.last_episode, total, episode_list).map(
+                    |(ratio, label)| {
+                        let source = ProgressSource::of(&item.last_episode, episode_list);
+                        (ratio, label, source)
+                    },
+                )
+            });
+            let episode_progress_text =
+                match item.airing_status.as_deref().and_then(AiringStatus::parse) {
+                    Some(airing) if !movie => {
+                        format!("{episode_progress_text} · {}", airing.label())
+                    }
+                    _ => episode_progress_text,
+                };
             let mut selection_text = format!(
                 "Title\n{}\n\nEpisode\n{}\n\nAni ID\n{}\n\nLast Seen\n{}",
                 truncate(&title, 40),
                 episode_progress_text,
-                truncate(&item.ani_id, 28),
+                truncate(&display_ani_id(&item.ani_id, show_full_id), 28),
                 format_last_seen_display_tui(&item.last_seen_at),
             );
+            if let Some(position) = watch_later.and_then(|saved| saved.resume_position(item)) {
+                selection_text.push_str(&format!(
+                    "\n\nIn Progress\nEpisode {} paused at {}",
+                    display_episode_label(&item.last_episode),
+                    format_resume_position(position)
+                ));
+            }
+            if !item.tags.is_empty() {
+                selection_text.push_str(&format!(
+                    "\n\nTags\n{}",
+                    truncate(&item.tags.join(", "), 40)
+                ));
+            }
+            if let Some(rating) = item.rating {
+                selection_text.push_str(&format!("\n\nRating\n{rating}/10"));
+            }
+            if let (Some(provider), Some(id)) = (&item.external_provider, &item.external_id) {
+                selection_text.push_str(&format!(
+                    "\n\nLinked\n{} {}",
+                    external_provider_label(provider),
+                    truncate(id, 24)
+                ));
+            }
+            if item.intro_skip_seconds > 0 {
+                selection_text.push_str(&format!("\n\nIntro Skip\n{}s", item.intro_skip_seconds));
+            }
+            if let Some(default_action) = item.default_action.as_deref().and_then(TuiAction::parse)
+            {
+                selection_text.push_str(&format!("\n\nDefault Action\n{}", default_action.label()));
+            }
             if episode_state.is_some_and(EpisodeListState::is_loading) {
                 selection_text.push_str("\n\nEpisodes\nLoading...");
             }
+            if let Some(end) = episode_list.and_then(|episodes| {
+                episode_list_ends_before(&item.last_episode, episodes.labels())
+            }) {
+                selection_text.push_str(&format!(
+                    "\n\nEpisodes\nList ends at {}; it may be incomplete",
+                    display_episode_label(end)
+                ));
+            }
             if let Some(warning) = episode_state.and_then(EpisodeListState::warning) {
                 selection_text.push_str("\n\nMetadata warning\n");
                 selection_text.push_str(&truncate(warning, 90));
@@ -163,9 +365,9 @@ pub(super) fn draw_tui(
         .block(panel_block("Selected"))
         .alignment(Alignment::Left);
     frame.render_widget(selection, details_chunks[0]);
-    if let Some((ratio, label)) = gauge {
+    if let Some((ratio, label, source)) = gauge {
         let progress = Gauge::default()
-            .block(panel_block("Progress"))
+            .block(panel_block(format!("Progress ({})", source.label())))
             .gauge_style(
                 Style::default()
                     .fg(Color::Rgb(130, 190, 255))
@@ -177,7 +379,29 @@ pub(super) fn draw_tui(
         frame.render_widget(progress, details_chunks[1]);
     }
 
-    let action_line = action_selector_line(action);
+    // Same precedence as the key handling, so the hints describe whichever prompt gets the keys.
+    let hint_mode = if pending_notice.is_some() {
+        HintMode::Notice
+    } else if pending_quit || pending_delete.is_some() || pending_previous.is_some() {
+        HintMode::Confirm
+    } else if pending_select.is_some() {
+        HintMode::ConfirmPlay
+    } else if episode_picker.is_some() {
+        HintMode::Picker
+    } else if pending_intro_skip.is_some() || pending_tags.is_some() {
+        HintMode::TextPrompt
+    } else if pending_rating.is_some() {
+        HintMode::RatingPrompt
+    } else if jumping {
+        HintMode::Jump
+    } else if finding {
+        HintMode::Find
+    } else if timeline_offset.is_some() {
+        HintMode::Timeline
+    } else {
+        HintMode::Main
+    };
+    let action_line = action_selector_line(action, hint_mode);
     let command_bar = Paragraph::new(action_line)
         .alignment(Alignment::Center)
         .block(panel_block("Controls"));
@@ -188,7 +412,20 @@ pub(super) fn draw_tui(
         .block(panel_block("Status"));
     frame.render_widget(status_widget, chunks[3]);
 
-    if let Some(confirm) = pending_delete {
+    if let Some(playback) = background.filter(|_| pending_quit) {
+        let popup_text = format!(
+            "Still playing in background:\n\n{}\n\nQuitting now leaves ani-cli running, but its progress will not be recorded.\n\n[y / Enter] Quit   [n / Esc] Cancel",
+            truncate(&playback.item.title, 56)
+        );
+        let popup_area = popup_rect_for_text(frame.area(), &popup_text);
+        render_popup_shadow(frame, popup_area);
+        frame.render_widget(Clear, popup_area);
+        let popup = Paragraph::new(popup_text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(modal_block("Confirm Quit"));
+        frame.render_widget(popup, popup_area);
+    } else if let Some(confirm) = pending_delete {
         let popup_text = format!(
             "Delete tracked entry?\n\n{}\n\nThis cannot be undone.\n\n[y / Enter] Delete   [n / Esc] Cancel",
             truncate(&confirm.title, 56)
@@ -201,6 +438,84 @@ pub(super) fn draw_tui(
             .wrap(Wrap { trim: true })
             .block(modal_block("Confirm Delete"));
         frame.render_widget(popup, popup_area);
+    } else if let Some(confirm) = pending_select {
+        let episode_text = confirm
+            .episode
+            .as_deref()
+            .map(|episode| format!(", episode {}", display_episode_label(episode)))
+            .unwrap_or_default();
+        let popup_text = format!(
+            "Matched '{}' at position {}{episode_text}\n({})\n\nThis was a title match, not an exact id match.\n\n[Enter] Play   [Esc] Cancel",
+            truncate(&confirm.matched_title, 48),
+            confirm.select_nth,
+            confirm.detail
+        );
+        let popup_area = popup_rect_for_text(frame.area(), &popup_text);
+        render_popup_shadow(frame, popup_area);
+        frame.render_widget(Clear, popup_area);
+        let popup = Paragraph::new(popup_text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(modal_block("Confirm Select"));
+        frame.render_widget(popup, popup_area);
+    } else if let Some(confirm) = pending_previous {
+        let popup_text = format!(
+            "Previous will play {}.\n\n{}\n\nContinue?\n\n[y / Enter] Play   [n / Esc] Cancel",
+            confirm.target,
+            truncate(&confirm.title, 56)
+        );
+        let popup_area = popup_rect_for_text(frame.area(), &popup_text);
+        render_popup_shadow(frame, popup_area);
+        frame.render_widget(Clear, popup_area);
+        let popup = Paragraph::new(popup_text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(modal_block("Confirm Previous"));
+        frame.render_widget(popup, popup_area);
+    } else if let Some(picker) = episode_picker {
+        render_episode_picker(frame, picker, theme);
+    } else if let Some(prompt) = pending_intro_skip {
+        let popup_text = format!(
+            "Skip intro for\n\n{}\n\nSeconds: {}_\n\n[Enter] Save   [Esc] Cancel",
+            truncate(&prompt.title, 56),
+            prompt.input
+        );
+        let popup_area = popup_rect_for_text(frame.area(), &popup_text);
+        render_popup_shadow(frame, popup_area);
+        frame.render_widget(Clear, popup_area);
+        let popup = Paragraph::new(popup_text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(modal_block("Intro Skip"));
+        frame.render_widget(popup, popup_area);
+    } else if let Some(prompt) = pending_tags {
+        let popup_text = format!(
+            "Tags for\n\n{}\n\nTags: {}_\n\nComma-separated, empty clears\n[Enter] Save   [Esc] Cancel",
+            truncate(&prompt.title, 56),
+            prompt.input
+        );
+        let popup_area = popup_rect_for_text(frame.area(), &popup_text);
+        render_popup_shadow(frame, popup_area);
+        frame.render_widget(Clear, popup_area);
+        let popup = Paragraph::new(popup_text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(modal_block("Tags"));
+        frame.render_widget(popup, popup_area);
+    } else if let Some(prompt) = pending_rating {
+        let popup_text = format!(
+            "Rate\n\n{}\n\nRating (1-10): {}_\n\nLeft/Right adjust, empty clears\n[Enter] Save   [Esc] Cancel",
+            truncate(&prompt.title, 56),
+            prompt.input
+        );
+        let popup_area = popup_rect_for_text(frame.area(), &popup_text);
+        render_popup_shadow(frame, popup_area);
+        frame.render_widget(Clear, popup_area);
+        let popup = Paragraph::new(popup_text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(modal_block("Rating"));
+        frame.render_widget(popup, popup_area);
     } else if let Some(notice) = pending_notice {
         let popup_area = popup_rect_for_text(frame.area(), &notice.message);
         render_popup_shadow(frame, popup_area);
@@ -213,7 +528,197 @@ pub(super) fn draw_tui(
     }
 }
 
-fn panel_block(title: &'static str) -> Block<'static> {
+fn render_episode_picker(frame: &mut Frame, picker: &EpisodePicker, theme: &TuiTheme) {
+    let area = frame.area();
+    let height = area.height.saturating_sub(4).clamp(8, 24);
+    let popup_area = centered_fixed_rect(52, height, area);
+    render_popup_shadow(frame, popup_area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = modal_block("Select Episode");
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let filtered = picker.matching().collect::<Vec<_>>();
+    let header = Paragraph::new(format!(
+        "{}\nFilter: {}_  ({} of {})",
+        truncate(&picker.title, 44),
+        picker.query,
+        filtered.len(),
+        picker.episodes.len()
+    ))
+    .style(Style::default().fg(Color::Rgb(230, 235, 242)));
+    frame.render_widget(header, chunks[0]);
+
+    let list_items: Vec<ListItem> = filtered
+        .iter()
+        .map(|&(idx, episode)| {
+            let (marker, style) = if picker.is_watched(idx, episode) {
+                ("✓ ", Style::default().fg(Color::Rgb(125, 135, 150)))
+            } else {
+                ("  ", Style::default().fg(Color::Rgb(230, 235, 242)))
+            };
+            ListItem::new(format!(
+                "{marker}Episode {}",
+                display_episode_label(episode)
+            ))
+            .style(style)
+        })
+        .collect();
+    let list = List::new(list_items)
+        .highlight_style(theme.highlight_style())
+        .highlight_symbol(theme.highlight_symbol.as_str());
+    let mut list_state = ListState::default();
+    list_state.select((!filtered.is_empty()).then_some(picker.selected));
+    frame.render_stateful_widget(list, chunks[1], &mut list_state);
+
+    let footer = Paragraph::new("↑/↓ move  type filter  Enter play  Esc cancel")
+        .style(Style::default().fg(Color::Rgb(185, 195, 210)));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// The Timeline view in place of the Library table: every entry by when it was last watched,
+/// newest first, starting `offset` rows down.
+fn draw_timeline(
+    frame: &mut Frame,
+    area: Rect,
+    items: &[SeenEntry],
+    offset: usize,
+    options: &TuiOptions,
+) {
+    let events = timeline_rows(items, &Local::now());
+    let rows = events
+        .iter()
+        .skip(offset)
+        .map(|(when, item)| {
+            Row::new(vec![
+                Cell::from(when.clone()),
+                Cell::from(display_title_and_total(item, options).0),
+                Cell::from(display_episode_label(&item.last_episode)),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Fill(1),
+            Constraint::Length(8),
+        ],
+    )
+    .header(
+        Row::new(vec!["When", "Title", "Episode"]).style(
+            Style::default()
+                .fg(Color::Rgb(110, 170, 255))
+                .add_modifier(Modifier::BOLD),
+        ),
+    )
+    .block(panel_block(format!(
+        "Timeline ({} shows, newest first)",
+        events.len()
+    )));
+    frame.render_widget(table, area);
+}
+
+/// Entries newest first by `last_seen_at`, each with when it was watched relative to `now`:
+/// `today 21:14`, `yesterday 09:02`, the weekday within the last week (`Mon 18:30`), and the
+/// full date before that. Unparseable timestamps come last, shown as stored.
+pub(crate) fn timeline_rows<'a, Tz: TimeZone>(
+    items: &'a [SeenEntry],
+    now: &DateTime<Tz>,
+) -> Vec<(String, &'a SeenEntry)>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let mut dated = items
+        .iter()
+        .map(|item| {
+            let seen = DateTime::parse_from_rfc3339(&item.last_seen_at)
+                .ok()
+                .map(|seen| seen.with_timezone(&now.timezone()));
+            (seen, item)
+        })
+        .collect::<Vec<_>>();
+    dated.sort_by(|(a, _), (b, _)| b.cmp(a));
+    let today = now.date_naive();
+    dated
+        .into_iter()
+        .map(|(seen, item)| {
+            let when = match seen {
+                Some(seen) => {
+                    let days_ago = (today - seen.date_naive()).num_days();
+                    match days_ago {
+                        0 => seen.format("today %H:%M").to_string(),
+                        1 => seen.format("yesterday %H:%M").to_string(),
+                        2..=6 => seen.format("%a %H:%M").to_string(),
+                        _ => seen.format("%Y-%m-%d %H:%M").to_string(),
+                    }
+                }
+                None => item.last_seen_at.clone(),
+            };
+            (when, item)
+        })
+        .collect()
+}
+
+/// Column widths for the Library table. The title takes whatever the other columns leave, so
+/// hiding columns gives their width to titles.
+fn library_column_widths(columns: &[LibraryColumn]) -> Vec<Constraint> {
+    // With every column shown the title keeps its usual share; hidden columns leave it the rest.
+    let title = if columns.len() == LibraryColumn::ALL.len() {
+        Constraint::Percentage(46)
+    } else {
+        Constraint::Fill(1)
+    };
+    columns
+        .iter()
+        .map(|column| match column {
+            LibraryColumn::Title => title,
+            LibraryColumn::Total | LibraryColumn::LastEp => Constraint::Length(10),
+            LibraryColumn::Rating => Constraint::Length(6),
+            LibraryColumn::LastSeen => Constraint::Length(26),
+        })
+        .collect()
+}
+
+/// Width of the Library title column for a table `inner_width` columns wide, laid out the same
+/// way `Table` does: the selection marker first, then the columns with one space between them.
+fn library_title_width(inner_width: u16, selection_width: u16, columns: &[LibraryColumn]) -> usize {
+    let [_, area] = Layout::horizontal([Constraint::Length(selection_width), Constraint::Fill(0)])
+        .areas(Rect::new(0, 0, inner_width, 1));
+    let widths = Layout::horizontal(library_column_widths(columns))
+        .spacing(1)
+        .split(area);
+    columns
+        .iter()
+        .position(|column| *column == LibraryColumn::Title)
+        .map_or(0, |idx| usize::from(widths[idx].width))
+}
+
+/// Library rows that fit on a terminal `height` rows tall: the dashboard, controls, and status
+/// bars take 9 rows, and the table's borders and header take 3 more.
+pub(super) fn visible_library_rows(height: u16) -> usize {
+    usize::from(height.saturating_sub(12))
+}
+
+fn display_title_and_total(item: &SeenEntry, options: &TuiOptions) -> (String, Option<u32>) {
+    let (display_title, total_eps) = entry_title_and_total(item);
+    if options.title_case {
+        (title_case_display(&display_title), total_eps)
+    } else {
+        (display_title, total_eps)
+    }
+}
+
+fn panel_block<'a>(title: impl Into<Line<'a>>) -> Block<'a> {
     Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
@@ -255,7 +760,44 @@ fn action_pill_style(action: TuiAction, current: TuiAction) -> Style {
     }
 }
 
-fn action_selector_line(current: TuiAction) -> Line<'static> {
+/// What the keyboard is currently driving, which decides the hints in the Controls bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HintMode {
+    Main,
+    /// Delete or Previous confirmation.
+    Confirm,
+    /// Title-match confirmation before Select plays.
+    ConfirmPlay,
+    Picker,
+    /// Intro skip or tags input.
+    TextPrompt,
+    RatingPrompt,
+    Notice,
+    Jump,
+    Find,
+    Timeline,
+}
+
+pub(crate) fn hint_text(mode: HintMode) -> &'static str {
+    match mode {
+        HintMode::Main => {
+            "↑/↓ move  ←/→/Tab action  Enter run  c continue  l replay last  v next/last ep  s search  n unwatched  0-9 jump  f find  w timeline  b background  u sync  r refetch  R restart  e history  i intro  t tags  * rate  p pin action  o sort  a id  d delete  q quit"
+        }
+        HintMode::Confirm => "y/Enter confirm  n/Esc cancel",
+        HintMode::ConfirmPlay => "y/Enter play  n/Esc cancel",
+        HintMode::Picker => "↑/↓ move  type filter  Backspace erase  Enter play  Esc cancel",
+        HintMode::TextPrompt => "type value  Backspace erase  Enter save  Esc cancel",
+        HintMode::RatingPrompt => {
+            "1-10 rating  ←/→ adjust  Backspace erase  Enter save  Esc cancel"
+        }
+        HintMode::Notice => "any key close",
+        HintMode::Jump => "0-9 row number  Backspace erase  Enter jump  Esc cancel",
+        HintMode::Find => "type title start  Backspace erase  Enter keep  Esc cancel",
+        HintMode::Timeline => "↑/↓ scroll  w/Esc back to library  q quit",
+    }
+}
+
+fn action_selector_line(current: TuiAction, mode: HintMode) -> Line<'static> {
     Line::from(vec![
         Span::styled(" NEXT ", action_pill_style(TuiAction::Next, current)),
         Span::styled(" ", Style::default()),
@@ -268,7 +810,7 @@ fn action_selector_line(current: TuiAction) -> Line<'static> {
         Span::styled(" ", Style::default()),
         Span::styled(" SELECT ", action_pill_style(TuiAction::Select, current)),
         Span::styled(
-            "   ↑/↓ move  ←/→ action  Enter run  s search  d delete  q quit",
+            format!("   {}", hint_text(mode)),
             Style::default().fg(Color::Rgb(185, 195, 210)),
         ),
     ])
@@ -309,10 +851,12 @@ fn render_popup_shadow(frame: &mut Frame, popup_area: Rect) {
     frame.render_widget(shadow_block, shadow);
 }
 
-fn popup_rect_for_text(area: Rect, text: &str) -> Rect {
+/// Sizes a popup to fit `text`, measuring lines in terminal columns so wide (e.g. CJK)
+/// characters count as two.
+pub(crate) fn popup_rect_for_text(area: Rect, text: &str) -> Rect {
     let max_line_width = text
         .lines()
-        .map(|line| line.chars().count() as u16)
+        .map(|line| Line::from(line).width() as u16)
         .max()
         .unwrap_or(0);
     let line_count = text.lines().count() as u16;
@@ -8,13 +8,22 @@ use crossterm::terminal::{
 
 pub(super) struct TuiSession {
     active: bool,
+    /// Whether the session switches to the alternate screen; plain sessions draw in the normal
+    /// screen so recordings and scrollback capture them.
+    alternate_screen: bool,
 }
 
 impl TuiSession {
-    pub(super) fn enter() -> Result<Self> {
+    pub(super) fn enter(alternate_screen: bool) -> Result<Self> {
         enable_raw_mode().context("failed to enable raw mode")?;
-        execute!(io::stdout(), EnterAlternateScreen).context("failed to enter alternate screen")?;
-        Ok(Self { active: true })
+        if alternate_screen {
+            execute!(io::stdout(), EnterAlternateScreen)
+                .context("failed to enter alternate screen")?;
+        }
+        Ok(Self {
+            active: true,
+            alternate_screen,
+        })
     }
 
     pub(super) fn suspend(&mut self) -> Result<()> {
@@ -22,7 +31,10 @@ impl TuiSession {
             return Ok(());
         }
         disable_raw_mode().context("failed to disable raw mode")?;
-        execute!(io::stdout(), LeaveAlternateScreen).context("failed to leave alternate screen")?;
+        if self.alternate_screen {
+            execute!(io::stdout(), LeaveAlternateScreen)
+                .context("failed to leave alternate screen")?;
+        }
         self.active = false;
         Ok(())
     }
@@ -31,8 +43,10 @@ impl TuiSession {
         if self.active {
             return Ok(());
         }
-        execute!(io::stdout(), EnterAlternateScreen)
-            .context("failed to re-enter alternate screen")?;
+        if self.alternate_screen {
+            execute!(io::stdout(), EnterAlternateScreen)
+                .context("failed to re-enter alternate screen")?;
+        }
         enable_raw_mode().context("failed to re-enable raw mode")?;
         self.active = true;
         Ok(())
@@ -47,7 +61,9 @@ impl Drop for TuiSession {
     fn drop(&mut self) {
         if self.active {
             let _ = disable_raw_mode();
-            let _ = execute!(io::stdout(), LeaveAlternateScreen);
+            if self.alternate_screen {
+                let _ = execute!(io::stdout(), LeaveAlternateScreen);
+            }
         }
     }
 }
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+use crate::db::{ListSort, SortTieBreak};
+
+/// UI state remembered between TUI runs. The file is a JSON object; unknown fields are ignored
+/// and kept as-is when saving, and missing ones fall back to defaults, so older and newer binaries
+/// can share it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TuiUiState {
+    pub(crate) selected_ani_id: Option<String>,
+    pub(crate) sort: Option<ListSort>,
+    pub(crate) tie_break: Option<SortTieBreak>,
+    unknown_fields: Map<String, Value>,
+}
+
+impl TuiUiState {
+    /// Reads the state file, treating a missing or unreadable one as empty state.
+    pub(super) fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .map(|raw| Self::parse(&raw))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn parse(raw: &str) -> Self {
+        let Ok(Value::Object(mut fields)) = serde_json::from_str::<Value>(raw) else {
+            return Self::default();
+        };
+        let selected_ani_id = fields
+            .remove("selected_ani_id")
+            .and_then(|value| value.as_str().map(str::to_string))
+            .filter(|id| !id.is_empty());
+        let sort = fields
+            .remove("sort")
+            .and_then(|value| value.as_str().and_then(ListSort::parse));
+        let tie_break = fields
+            .remove("tie_break")
+            .and_then(|value| value.as_str().and_then(SortTieBreak::parse));
+        Self {
+            selected_ani_id,
+            sort,
+            tie_break,
+            unknown_fields: fields,
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let mut fields = self.unknown_fields.clone();
+        if let Some(id) = &self.selected_ani_id {
+            fields.insert("selected_ani_id".to_string(), Value::from(id.as_str()));
+        }
+        if let Some(sort) = self.sort {
+            fields.insert("sort".to_string(), Value::from(sort.as_str()));
+        }
+        if let Some(tie_break) = self.tie_break {
+            fields.insert("tie_break".to_string(), Value::from(tie_break.as_str()));
+        }
+        Value::Object(fields).to_string()
+    }
+
+    pub(super) fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        fs::write(path, self.to_json())
+            .with_context(|| format!("failed to write TUI state to {}", path.display()))
+    }
+}
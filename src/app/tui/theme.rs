@@ -0,0 +1,67 @@
+use std::env;
+
+use anyhow::{Result, anyhow};
+use ratatui::style::{Color, Modifier, Style};
+
+/// Selection styling for the library table and episode picker. Each field can be overridden
+/// through an `ANI_TRACK_HIGHLIGHT_*` environment variable; unset or empty values keep the default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TuiTheme {
+    pub(crate) highlight_symbol: String,
+    pub(crate) highlight_fg: Color,
+    pub(crate) highlight_bg: Color,
+}
+
+impl Default for TuiTheme {
+    fn default() -> Self {
+        Self {
+            highlight_symbol: "▸ ".to_string(),
+            highlight_fg: Color::Black,
+            highlight_bg: Color::Rgb(110, 170, 255),
+        }
+    }
+}
+
+impl TuiTheme {
+    pub(super) fn from_env() -> Result<Self> {
+        Self::from_values(
+            env::var("ANI_TRACK_HIGHLIGHT_SYMBOL").ok(),
+            env::var("ANI_TRACK_HIGHLIGHT_FG").ok(),
+            env::var("ANI_TRACK_HIGHLIGHT_BG").ok(),
+        )
+    }
+
+    pub(crate) fn from_values(
+        symbol: Option<String>,
+        fg: Option<String>,
+        bg: Option<String>,
+    ) -> Result<Self> {
+        let mut theme = Self::default();
+        if let Some(symbol) = symbol.filter(|value| !value.is_empty()) {
+            theme.highlight_symbol = symbol;
+        }
+        if let Some(color) = parse_color("ANI_TRACK_HIGHLIGHT_FG", fg)? {
+            theme.highlight_fg = color;
+        }
+        if let Some(color) = parse_color("ANI_TRACK_HIGHLIGHT_BG", bg)? {
+            theme.highlight_bg = color;
+        }
+        Ok(theme)
+    }
+
+    pub(super) fn highlight_style(&self) -> Style {
+        Style::default()
+            .fg(self.highlight_fg)
+            .bg(self.highlight_bg)
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+fn parse_color(var: &str, value: Option<String>) -> Result<Option<Color>> {
+    let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+        return Ok(None);
+    };
+    value.trim().parse::<Color>().map(Some).map_err(|_| {
+        anyhow!("invalid {var} `{value}` (expected a color name like `blue`, an index like `4`, or `#rrggbb`)")
+    })
+}
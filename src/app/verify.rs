@@ -0,0 +1,108 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use anyhow::{Result, bail};
+
+use crate::db::{Database, SeenEntry};
+
+use super::color::warning_label;
+use super::tracking::{SelectNthResolution, resolve_select_nth_for_item_with_diagnostics};
+
+/// How a tracked entry was found in the allanime search results `Select` relies on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum VerifyOutcome {
+    /// A search result carries the entry's id.
+    ById,
+    /// Only a result with a matching title was found; holds that result's title.
+    ByTitle(String),
+    /// Nothing matched; holds the resolution summary.
+    Failed(String),
+}
+
+impl VerifyOutcome {
+    pub(crate) fn from_resolution(resolution: &SelectNthResolution) -> Self {
+        match (resolution.index, &resolution.title_match) {
+            (Some(_), None) => Self::ById,
+            (Some(_), Some(title)) => Self::ByTitle(title.clone()),
+            (None, _) => Self::Failed(resolution.summary()),
+        }
+    }
+}
+
+/// Resolves every entry with `resolve`, running up to `concurrency` lookups at a time (at least
+/// one). Results come back in the order of `items`, each with the lookup's warnings.
+pub(crate) fn verify_entries<F>(
+    items: &[SeenEntry],
+    concurrency: usize,
+    resolve: F,
+) -> Vec<(VerifyOutcome, Vec<String>)>
+where
+    F: Fn(&SeenEntry) -> SelectNthResolution + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(vec![None; items.len()]);
+    thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, items.len().max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(idx) else {
+                        return;
+                    };
+                    let resolution = resolve(item);
+                    let outcome = VerifyOutcome::from_resolution(&resolution);
+                    results.lock().expect("verify results lock poisoned")[idx] =
+                        Some((outcome, resolution.warnings));
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .expect("verify results lock poisoned")
+        .into_iter()
+        .map(|result| result.expect("every entry is resolved"))
+        .collect()
+}
+
+pub(super) fn run_verify(db: &Database, concurrency: usize) -> Result<()> {
+    let items = db.list_seen()?;
+    let results = verify_entries(
+        &items,
+        concurrency,
+        resolve_select_nth_for_item_with_diagnostics,
+    );
+
+    let (mut by_id, mut by_title, mut failed) = (0, 0, 0);
+    for (item, (outcome, warnings)) in items.iter().zip(&results) {
+        match outcome {
+            VerifyOutcome::ById => {
+                by_id += 1;
+                println!("id\t{}\t{}", item.ani_id, item.title);
+            }
+            VerifyOutcome::ByTitle(matched) => {
+                by_title += 1;
+                println!(
+                    "title\t{}\t{}\tmatched {matched:?}",
+                    item.ani_id, item.title
+                );
+            }
+            VerifyOutcome::Failed(summary) => {
+                failed += 1;
+                println!("failed\t{}\t{}\t{summary}", item.ani_id, item.title);
+                for warning in warnings {
+                    eprintln!("{} {}: {warning}", warning_label(), item.ani_id);
+                }
+            }
+        }
+    }
+    println!("{by_id} by id, {by_title} by title only, {failed} failed.");
+    if failed > 0 {
+        bail!(
+            "{failed} entr{} could not be found on allanime; fix their titles before using Select",
+            if failed == 1 { "y" } else { "ies" }
+        );
+    }
+    Ok(())
+}
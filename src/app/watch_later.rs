@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::db::SeenEntry;
+
+/// How often the watch-later directory is re-read while the TUI runs.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Files larger than this are not mpv resume files and are skipped unread.
+const MAX_FILE_BYTES: u64 = 64 * 1024;
+
+/// Resume positions read from mpv's watch-later directory, keyed by media title. Only files that
+/// record `force-media-title` (which ani-cli sets to the show and episode) can be matched to an
+/// entry. The directory is only ever read.
+#[derive(Debug)]
+pub(crate) struct WatchLater {
+    dir: PathBuf,
+    positions: HashMap<String, f64>,
+    loaded_at: Option<Instant>,
+}
+
+impl WatchLater {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            positions: HashMap::new(),
+            loaded_at: None,
+        }
+    }
+
+    /// Re-reads the directory if it has not been read in the last few seconds.
+    pub(crate) fn refresh_if_stale(&mut self) {
+        if self
+            .loaded_at
+            .is_some_and(|loaded_at| loaded_at.elapsed() < RELOAD_INTERVAL)
+        {
+            return;
+        }
+        self.positions = read_watch_later_dir(&self.dir);
+        self.loaded_at = Some(Instant::now());
+    }
+
+    /// Where mpv paused `item`'s stored episode, in seconds, if it saved a position for it.
+    pub(crate) fn resume_position(&self, item: &SeenEntry) -> Option<f64> {
+        self.positions
+            .get(&media_title_key(&ani_cli_media_title(
+                &item.title,
+                &item.last_episode,
+            )))
+            .copied()
+    }
+}
+
+/// Reads every resume file in `dir`; a missing or unreadable directory, and files that cannot be
+/// parsed, are skipped silently.
+pub(crate) fn read_watch_later_dir(dir: &Path) -> HashMap<String, f64> {
+    let mut positions = HashMap::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return positions;
+    };
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if !meta.is_file() || meta.len() > MAX_FILE_BYTES {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        if let Some((title, start)) = parse_watch_later(&contents) {
+            positions.insert(media_title_key(&title), start);
+        }
+    }
+    positions
+}
+
+/// The media title and resume position (`start`) of one watch-later file, if it has both.
+pub(crate) fn parse_watch_later(contents: &str) -> Option<(String, f64)> {
+    let mut title = None;
+    let mut start = None;
+    for line in contents.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "force-media-title" => title = Some(unquote_mpv_value(value).to_string()),
+            "start" => start = value.trim().parse::<f64>().ok(),
+            _ => {}
+        }
+    }
+    Some((
+        title?,
+        start.filter(|secs| secs.is_finite() && *secs > 0.0)?,
+    ))
+}
+
+/// Strips mpv's `%N%value` length quoting, used for values with special characters.
+fn unquote_mpv_value(value: &str) -> &str {
+    if let Some(rest) = value.strip_prefix('%')
+        && let Some((len, quoted)) = rest.split_once('%')
+        && let Ok(len) = len.parse::<usize>()
+        && let Some(exact) = quoted.get(..len)
+    {
+        return exact;
+    }
+    value
+}
+
+/// The media title ani-cli gives mpv: the show title up to its first `(`, then `Episode N`.
+pub(crate) fn ani_cli_media_title(title: &str, episode: &str) -> String {
+    let show = title.split('(').next().unwrap_or_default();
+    format!("{} Episode {}", show.trim(), episode.trim())
+}
+
+/// Compares media titles the way ani-cli builds them: punctuation dropped (ani-cli strips it from
+/// the show title), case and spacing ignored.
+fn media_title_key(title: &str) -> String {
+    title
+        .chars()
+        .filter(|ch| !ch.is_ascii_punctuation())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// `h:mm:ss`, or `m:ss` under an hour.
+pub(crate) fn format_resume_position(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, secs) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    }
+}
@@ -1,4 +1,9 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::db::ListSort;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -7,15 +12,319 @@ use clap::{Parser, Subcommand};
     about = "Launch ani-cli and track last seen show/episode"
 )]
 pub struct Cli {
+    /// Media player for ani-cli to use (e.g. mpv, vlc, iina)
+    #[arg(long, global = true, value_name = "PLAYER")]
+    pub player: Option<String>,
+
+    /// Terminate ani-cli if a launch runs longer than this many seconds
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub max_runtime: Option<u64>,
+
+    /// Extra flag passed verbatim to every ani-cli launch (repeatable, not validated)
+    #[arg(
+        long = "ani-cli-arg",
+        global = true,
+        value_name = "ARG",
+        allow_hyphen_values = true
+    )]
+    pub ani_cli_args: Vec<String>,
+
+    /// Episode lists the TUI fetches in parallel for visible rows (0 disables prefetching)
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = 3,
+        value_parser = clap::value_parser!(u8).range(0..=8)
+    )]
+    pub concurrency: u8,
+
+    /// How episode progress against a known total is displayed
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    pub episode_format: Option<EpisodeFormat>,
+
+    /// Color warnings and results in command output (auto: only on a terminal without NO_COLOR)
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorChoice::Auto
+    )]
+    pub color: ColorChoice,
+
+    /// Fail instead of upgrading a database with an older schema (see `anitrack migrate`)
+    #[arg(long, global = true)]
+    pub no_migrate: bool,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
 
+/// Presets for progress text: `5 of 12`, `5/12`, `42%`, or `5/12 (42%)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum EpisodeFormat {
+    #[default]
+    #[value(name = "x_of_y")]
+    XOfY,
+    #[value(name = "x/y")]
+    Slash,
+    #[value(name = "pct")]
+    Percent,
+    #[value(name = "x/y_pct")]
+    SlashPercent,
+}
+
+/// `json` is the restorable backup; `csv` and `markdown` are watch-list tables for sharing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ColorChoice {
+    Always,
+    #[default]
+    Auto,
+    Never,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Command {
-    Start,
+    Start {
+        /// Track the chosen show as a movie (no next episode; Replay plays it again)
+        #[arg(long)]
+        movie: bool,
+    },
     Next,
     Replay,
-    List,
-    Tui,
+    /// Run ani-cli's continue for a tracked show as if EPISODE were the last one watched
+    ContinueFrom {
+        #[arg(value_name = "ANI_ID")]
+        ani_id: String,
+        /// Episode to continue after, e.g. 11 or 12.5
+        #[arg(value_name = "EPISODE", value_parser = parse_episode_label)]
+        episode: String,
+    },
+    /// Reset a tracked show's progress so the next Next starts at its first episode
+    Restart {
+        #[arg(value_name = "ANI_ID")]
+        ani_id: String,
+    },
+    List {
+        /// Re-render the list every N seconds until interrupted
+        #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+        watch: Option<u64>,
+        /// Print entries as a JSON array
+        #[arg(long)]
+        json: bool,
+        /// Render each entry with a template, e.g. "{title} — ep {episode} ({last_seen})".
+        /// Placeholders: {ani_id}, {title}, {episode}, {total}, {last_seen}, {progress}
+        #[arg(long, value_name = "TEMPLATE", conflicts_with = "json")]
+        format: Option<String>,
+        /// Only list entries with this tag (repeatable; matches any tag)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// Require every --tag to match instead of any
+        #[arg(long, requires = "tags")]
+        all_tags: bool,
+        /// Order entries by when they were last watched, first tracked, or by rating (unrated last)
+        #[arg(long, value_enum, value_name = "ORDER", default_value_t = ListSort::Watched)]
+        sort: ListSort,
+    },
+    /// Print the last seen show on one line, for shell prompts (prints nothing if there is none)
+    Now {
+        /// Template for the line; same placeholders as `list --format` [default: "{title} · ep {episode}"]
+        #[arg(long, value_name = "TEMPLATE")]
+        format: Option<String>,
+        /// Do not end the line with a newline
+        #[arg(long)]
+        no_newline: bool,
+    },
+    /// List ani-cli history entries that are not tracked yet
+    Untracked {
+        /// Print entries as a JSON array
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write a versioned backup of all tracked entries
+    Export {
+        /// Write the backup to FILE instead of stdout
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Backup (json) or a shareable table of title, episode, progress, and last seen
+        #[arg(long, value_enum, value_name = "FORMAT", default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+    },
+    /// Write tracked progress as an ani-cli history file, to seed ani-cli elsewhere
+    ExportHistory {
+        /// File to write [default: ani-hsts next to the database]
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+        /// Replace the file if it already exists
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Restore tracked entries from a backup written by `export`
+    Import {
+        /// Backup file to restore
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Show counts of tracked entries
+    Stats {
+        /// Break counts down by watch status (none is stored yet, so every entry counts as
+        /// watching)
+        #[arg(long)]
+        by_status: bool,
+        /// Print counts as a JSON object
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete entries not watched for a while (lists them only, unless --yes is given)
+    Prune {
+        /// Prune entries last watched longer ago than this, e.g. 180d, 12w, or 36h
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        older_than: Duration,
+        /// Only prune entries with this watch status
+        #[arg(long, value_name = "STATUS", value_parser = ["watching", "completed", "dropped", "on_hold"])]
+        status: Option<String>,
+        /// List the entries that would be deleted without deleting them (the default)
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+        /// Delete the matching entries
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Advance tracked progress from the ani-cli history without launching ani-cli
+    Sync,
+    /// Track a show by hand, without ani-cli or the network
+    Add {
+        #[arg(value_name = "ANI_ID")]
+        ani_id: String,
+        /// Title as ani-cli shows it, e.g. "Frieren (28 episodes)"
+        #[arg(value_name = "TITLE")]
+        title: String,
+        /// Last watched episode, e.g. 11 or 12.5
+        #[arg(long, value_name = "EPISODE", value_parser = parse_episode_label, default_value = "0")]
+        episode: String,
+    },
+    /// Link an entry to its show on an external tracker (stored only; nothing is synced)
+    #[command(group(
+        clap::ArgGroup::new("target")
+            .required(true)
+            .args(["anilist", "mal", "clear"])
+    ))]
+    Link {
+        /// Entry to link
+        #[arg(value_name = "ANI_ID")]
+        ani_id: String,
+        /// AniList show id
+        #[arg(long, value_name = "ID")]
+        anilist: Option<u64>,
+        /// MyAnimeList show id
+        #[arg(long, value_name = "ID")]
+        mal: Option<u64>,
+        /// Remove the link
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Combine two entries for the same show, keeping the first id
+    Merge {
+        /// Entry that survives (its id, title, and kind are kept)
+        #[arg(value_name = "KEEP_ID")]
+        keep_id: String,
+        /// Entry folded into KEEP_ID and then deleted
+        #[arg(value_name = "DROP_ID")]
+        drop_id: String,
+    },
+    /// Move the `(N episodes)` suffix out of stored titles into a separate episode count
+    NormalizeTitles,
+    /// Show the paths and player AniTrack and ani-cli will use
+    Doctor {
+        /// Also check that the player command can be found on PATH
+        #[arg(long)]
+        check: bool,
+    },
+    /// Check that every tracked entry can be found in the search results Select uses
+    Verify {
+        /// Entries searched at once
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 3,
+            value_parser = clap::value_parser!(u8).range(1..=8)
+        )]
+        jobs: u8,
+    },
+    /// Upgrade the database schema to this version and report the change
+    Migrate {
+        /// Add anitrack's tables even to a file that holds tables anitrack did not create
+        #[arg(long)]
+        allow_foreign_db: bool,
+    },
+    /// Print the ani-cli history exactly as progress detection parses it, for bug reports
+    DumpHistory {
+        /// Read DIR/ani-hsts (or DIR itself if it is a file) instead of the ani-cli history
+        #[arg(long, value_name = "DIR")]
+        hist_dir: Option<PathBuf>,
+    },
+    Tui {
+        /// Render in the normal screen instead of the alternate screen (for recordings and logs)
+        #[arg(long)]
+        plain: bool,
+    },
+}
+
+/// Accepts a numeric episode label such as `12` or `12.5`, the forms ani-cli writes to history.
+pub(crate) fn parse_episode_label(raw: &str) -> Result<String, String> {
+    let raw = raw.trim();
+    let all_digits = |part: &str| !part.is_empty() && part.chars().all(|ch| ch.is_ascii_digit());
+    let valid = match raw.split_once('.') {
+        Some((whole, fraction)) => all_digits(whole) && all_digits(fraction),
+        None => all_digits(raw),
+    };
+    if valid {
+        Ok(raw.to_string())
+    } else {
+        Err(format!(
+            "invalid episode `{raw}` (expected a number such as 12 or 12.5)"
+        ))
+    }
+}
+
+/// Parses an age such as `90s`, `45m`, `36h`, `180d`, or `12w` (a bare number means days).
+pub(crate) fn parse_age(raw: &str) -> Result<Duration, String> {
+    let raw = raw.trim();
+    let split = raw
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(raw.len());
+    let (amount, unit) = raw.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid age `{raw}` (expected e.g. 180d, 12w, or 36h)"))?;
+    let unit_secs = match unit.trim().to_ascii_lowercase().as_str() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid age unit in `{raw}` (expected s, m, h, d, or w)"
+            ));
+        }
+    };
+    amount
+        .checked_mul(unit_secs)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("age `{raw}` is too large"))
 }
@@ -1,24 +1,366 @@
 use std::path::Path;
 use std::time::Duration;
 
-use anyhow::{Context, Result, anyhow};
-use chrono::Utc;
-use rusqlite::{Connection, params};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::ValueEnum;
+use rusqlite::{Connection, ErrorCode, OpenFlags, OptionalExtension, params};
 
-const SCHEMA_VERSION: i64 = 2;
+pub const SCHEMA_VERSION: i64 = 12;
 
-#[derive(Debug, Clone)]
+const SEEN_COLUMNS: &str = "ani_id, title, last_episode, last_seen_at, intro_skip_seconds, tags, kind, \
+     total_eps_override, default_action, first_seen_at, airing_status, rating, external_provider, \
+     external_id, watched_episodes";
+
+#[derive(Debug, Clone, Default)]
 pub struct SeenEntry {
     pub ani_id: String,
     pub title: String,
     pub last_episode: String,
     pub last_seen_at: String,
+    /// When the entry was first tracked. Entries from before this was recorded use their
+    /// `last_seen_at` at upgrade time.
+    pub first_seen_at: String,
+    pub intro_skip_seconds: u32,
+    pub tags: Vec<String>,
+    pub kind: EntryKind,
+    /// Episode count kept outside the title, for titles stored without their `(N episodes)` suffix.
+    pub total_eps_override: Option<u32>,
+    /// TUI action preselected when this entry is selected (`next`, `replay`, `previous`, or
+    /// `select`); `None` keeps the TUI's current action.
+    pub default_action: Option<String>,
+    /// Last airing status seen in show metadata (`airing` or `finished`); `None` when unknown.
+    pub airing_status: Option<String>,
+    /// Personal rating within [`RATING_RANGE`]; `None` when unrated.
+    pub rating: Option<u8>,
+    /// External tracker this entry is linked to (`anilist` or `mal`); set together with
+    /// `external_id`, and `None` when unlinked.
+    pub external_provider: Option<String>,
+    /// The show's id on `external_provider`.
+    pub external_id: Option<String>,
+    /// Every episode label played, for entries tracking watched episodes (see
+    /// [`Database::add_watched_episode`]); `None` when only `last_episode` is kept.
+    pub watched_episodes: Option<Vec<String>>,
+}
+
+/// Ratings accepted by [`Database::set_rating`].
+pub const RATING_RANGE: std::ops::RangeInclusive<u8> = 1..=10;
+
+/// How far the newest stored `last_seen_at` may be ahead of the clock and still be treated as
+/// clock skew. Anything further ahead is assumed to be a bad imported value and is ignored.
+const MAX_CLOCK_SKEW: TimeDelta = TimeDelta::days(1);
+
+/// What a tracked entry is. Movies have a single episode, so there is never a next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EntryKind {
+    #[default]
+    Series,
+    Movie,
+}
+
+impl EntryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Series => "series",
+            Self::Movie => "movie",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "series" => Some(Self::Series),
+            "movie" => Some(Self::Movie),
+            _ => None,
+        }
+    }
+}
+
+/// `now`, or 1ms after `newest` when the clock reads at or before it by no more than
+/// `MAX_CLOCK_SKEW`.
+pub fn monotonic_seen_at(now: DateTime<Utc>, newest: Option<DateTime<Utc>>) -> DateTime<Utc> {
+    match newest {
+        Some(newest) if now <= newest && newest - now <= MAX_CLOCK_SKEW => {
+            newest + TimeDelta::milliseconds(1)
+        }
+        _ => now,
+    }
+}
+
+/// Order of listed entries, newest first. Also the values of `list --sort`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ListSort {
+    /// By when the entry was last watched.
+    #[default]
+    Watched,
+    /// By when the entry was first tracked. Ties (e.g. entries whose first-seen time was
+    /// backfilled from their last-seen time) fall back to the last-watched order.
+    Added,
+    /// Highest rating first; unrated entries come last.
+    Rating,
+}
+
+impl ListSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "watched" => Some(Self::Watched),
+            "added" => Some(Self::Added),
+            "rating" => Some(Self::Rating),
+            _ => None,
+        }
+    }
+
+    /// The name `parse` accepts.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Watched => "watched",
+            Self::Added => "added",
+            Self::Rating => "rating",
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::Watched => "last_seen_at DESC",
+            Self::Added => "first_seen_at DESC",
+            Self::Rating => "rating IS NULL, rating DESC",
+        }
+    }
+}
+
+/// How entries that tie on the sort key are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortTieBreak {
+    /// Most recently watched first.
+    #[default]
+    LastSeen,
+    /// Alphabetically by title, ignoring case.
+    Title,
+    /// Most recently tracked first.
+    Added,
+}
+
+impl SortTieBreak {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "last_seen" => Some(Self::LastSeen),
+            "title" => Some(Self::Title),
+            "added" => Some(Self::Added),
+            _ => None,
+        }
+    }
+
+    /// The name `parse` accepts.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LastSeen => "last_seen",
+            Self::Title => "title",
+            Self::Added => "added",
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::LastSeen => "last_seen_at DESC",
+            Self::Title => "title COLLATE NOCASE ASC",
+            Self::Added => "first_seen_at DESC",
+        }
+    }
+}
+
+/// A sort with its tie-breaker. Entries still tied after both are ordered by id, so a listing
+/// never depends on storage order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ListOrder {
+    pub sort: ListSort,
+    pub tie_break: SortTieBreak,
+}
+
+impl ListOrder {
+    fn order_by(self) -> String {
+        format!(
+            "{}, {}, ani_id ASC",
+            self.sort.order_by(),
+            self.tie_break.order_by()
+        )
+    }
+}
+
+impl From<ListSort> for ListOrder {
+    fn from(sort: ListSort) -> Self {
+        Self {
+            sort,
+            tie_break: SortTieBreak::default(),
+        }
+    }
+}
+
+fn seen_entry_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SeenEntry> {
+    Ok(SeenEntry {
+        ani_id: row.get(0)?,
+        title: row.get(1)?,
+        last_episode: row.get(2)?,
+        last_seen_at: row.get(3)?,
+        intro_skip_seconds: row.get(4)?,
+        tags: normalize_tags(row.get::<_, String>(5)?.split(',')),
+        kind: EntryKind::parse(&row.get::<_, String>(6)?).unwrap_or_default(),
+        total_eps_override: row.get(7)?,
+        default_action: row.get(8)?,
+        first_seen_at: row.get(9)?,
+        airing_status: row.get(10)?,
+        rating: row.get(11)?,
+        external_provider: row.get(12)?,
+        external_id: row.get(13)?,
+        watched_episodes: row
+            .get::<_, Option<String>>(14)?
+            .map(|raw| normalize_watched_episodes(raw.split(','))),
+    })
+}
+
+/// Lowercases and trims tags, splitting on commas and dropping empty entries and duplicates
+/// (first occurrence wins). Tags are stored comma-separated, so they never contain commas.
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut out: Vec<String> = Vec::new();
+    for raw in tags {
+        for tag in raw.as_ref().split(',') {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() && !out.contains(&tag) {
+                out.push(tag);
+            }
+        }
+    }
+    out
+}
+
+/// Trims episode labels, splitting on commas and dropping empty entries and duplicates, and sorts
+/// them numerically (labels that are not numbers go last, in text order). Watched episodes are
+/// stored comma-separated, so they never contain commas.
+pub fn normalize_watched_episodes<I, S>(labels: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut out: Vec<String> = Vec::new();
+    for raw in labels {
+        for label in raw.as_ref().split(',') {
+            let label = label.trim();
+            if !label.is_empty() && !out.iter().any(|seen| seen == label) {
+                out.push(label.to_string());
+            }
+        }
+    }
+    let numeric = |label: &str| label.parse::<f64>().ok().filter(|value| value.is_finite());
+    out.sort_by(|a, b| match (numeric(a), numeric(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y).then_with(|| a.cmp(b)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.cmp(b),
+    });
+    out
 }
 
 pub struct Database {
     conn: Connection,
 }
 
+/// Column value for a set of watched episodes: `NULL` when not tracking them.
+fn joined_watched_episodes(watched: Option<&[String]>) -> Option<String> {
+    watched.map(|labels| normalize_watched_episodes(labels).join(","))
+}
+
+/// SQLite journal mode applied when opening the database. WAL is the default; `Delete` and
+/// `Truncate` exist for network or FUSE filesystems that do not support WAL's shared memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JournalMode {
+    #[default]
+    Wal,
+    Delete,
+    Truncate,
+}
+
+impl JournalMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "wal" => Some(Self::Wal),
+            "delete" => Some(Self::Delete),
+            "truncate" => Some(Self::Truncate),
+            _ => None,
+        }
+    }
+
+    fn pragma_value(self) -> &'static str {
+        match self {
+            Self::Wal => "wal",
+            Self::Delete => "delete",
+            Self::Truncate => "truncate",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbOpenIssue {
+    Locked,
+    Corrupt,
+}
+
+/// Classifies open/migrate failures that need user action rather than a raw sqlite message.
+pub fn classify_open_error(err: &anyhow::Error) -> Option<DbOpenIssue> {
+    err.chain().find_map(|cause| {
+        let code = cause
+            .downcast_ref::<rusqlite::Error>()?
+            .sqlite_error_code()?;
+        match code {
+            ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => Some(DbOpenIssue::Locked),
+            ErrorCode::NotADatabase | ErrorCode::DatabaseCorrupt => Some(DbOpenIssue::Corrupt),
+            _ => None,
+        }
+    })
+}
+
+/// Columns an unversioned `seen_progress` table must have to be anitrack's own (the v1 schema).
+const V1_SEEN_COLUMNS: [&str; 4] = ["ani_id", "title", "last_episode", "last_seen_at"];
+
+/// Fails when an unversioned database holds tables anitrack did not create, or a `seen_progress`
+/// table with a different shape, so migrating does not mix anitrack's tables into another app's
+/// file. Empty files and databases from before schema versioning pass.
+fn ensure_no_foreign_tables(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )?;
+    let tables = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let foreign = tables
+        .iter()
+        .filter(|name| name.as_str() != "seen_progress")
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if !foreign.is_empty() {
+        bail!(
+            "this doesn't look like an anitrack database: it already has tables anitrack did not create ({}). Run `anitrack migrate --allow-foreign-db` to add anitrack's tables to it anyway",
+            foreign.join(", ")
+        );
+    }
+    if tables.iter().any(|name| name == "seen_progress") {
+        let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('seen_progress')")?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if let Some(missing) = V1_SEEN_COLUMNS
+            .iter()
+            .find(|column| !columns.iter().any(|name| name == *column))
+        {
+            bail!(
+                "this doesn't look like an anitrack database: its seen_progress table has no {missing} column. Run `anitrack migrate --allow-foreign-db` to migrate it anyway"
+            );
+        }
+    }
+    Ok(())
+}
+
 impl Database {
     pub fn open(path: &Path) -> Result<Self> {
         if let Some(parent) = path.parent() {
@@ -30,11 +372,74 @@ impl Database {
             .with_context(|| format!("failed to open database at {}", path.display()))?;
         conn.busy_timeout(Duration::from_secs(5))
             .context("failed to configure sqlite busy timeout")?;
-        let _ = conn.pragma_update(None, "journal_mode", "WAL");
+        Ok(Self { conn })
+    }
+
+    /// Switches the journal mode, failing when SQLite keeps a different one (it silently stays on
+    /// the old mode when, for example, the filesystem cannot host WAL).
+    pub fn set_journal_mode(&self, mode: JournalMode) -> Result<()> {
+        let applied: String = self
+            .conn
+            .pragma_update_and_check(None, "journal_mode", mode.pragma_value(), |row| row.get(0))
+            .with_context(|| format!("failed to set journal mode to {}", mode.pragma_value()))?;
+        if !applied.eq_ignore_ascii_case(mode.pragma_value()) {
+            return Err(anyhow!(
+                "sqlite kept journal mode {applied} instead of {}",
+                mode.pragma_value()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Copies committed WAL frames into the main database file without waiting on other
+    /// connections (a passive checkpoint), so readers of the file alone see recent writes. Returns
+    /// whether every frame was copied; outside WAL mode there is nothing to copy.
+    pub fn checkpoint_wal(&self) -> Result<bool> {
+        let (busy, log_frames, checkpointed): (i64, i64, i64) = self
+            .conn
+            .query_row("PRAGMA wal_checkpoint(PASSIVE)", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .context("failed to checkpoint the write-ahead log")?;
+        Ok(busy == 0 && log_frames == checkpointed)
+    }
+
+    #[cfg(test)]
+    fn journal_mode(&self) -> Result<String> {
+        Ok(self
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))?)
+    }
+
+    /// Opens an existing database without write access or migrations.
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("failed to open database read-only at {}", path.display()))?;
+        conn.busy_timeout(Duration::from_secs(5))
+            .context("failed to configure sqlite busy timeout")?;
         Ok(Self { conn })
     }
 
     pub fn migrate(&self) -> Result<()> {
+        self.migrate_with(false)
+    }
+
+    /// Fails like `migrate` when an unversioned file holds tables anitrack did not create, without
+    /// changing anything, so a caller can check before touching the file (e.g. its journal mode).
+    pub fn ensure_anitrack_database(&self) -> Result<()> {
+        if self.schema_version()? == 0 {
+            ensure_no_foreign_tables(&self.conn)?;
+        }
+        Ok(())
+    }
+
+    /// Like `migrate`, but also adds anitrack's tables to an unversioned file that holds other
+    /// tables, which `migrate` refuses as not an anitrack database.
+    pub fn migrate_allowing_foreign_tables(&self) -> Result<()> {
+        self.migrate_with(true)
+    }
+
+    fn migrate_with(&self, allow_foreign_tables: bool) -> Result<()> {
         let tx = self
             .conn
             .unchecked_transaction()
@@ -48,6 +453,9 @@ impl Database {
                 "database schema version {user_version} is newer than supported {SCHEMA_VERSION}"
             ));
         }
+        if user_version == 0 && !allow_foreign_tables {
+            ensure_no_foreign_tables(&tx)?;
+        }
 
         while user_version < SCHEMA_VERSION {
             let next_version = user_version + 1;
@@ -74,6 +482,99 @@ impl Database {
                     )
                     .context("failed applying migration v2")?;
                 }
+                3 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN intro_skip_seconds INTEGER NOT NULL DEFAULT 0;
+                        "#,
+                    )
+                    .context("failed applying migration v3")?;
+                }
+                4 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN tags TEXT NOT NULL DEFAULT '';
+                        "#,
+                    )
+                    .context("failed applying migration v4")?;
+                }
+                5 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN kind TEXT NOT NULL DEFAULT 'series';
+                        "#,
+                    )
+                    .context("failed applying migration v5")?;
+                }
+                6 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN total_eps_override INTEGER;
+                        "#,
+                    )
+                    .context("failed applying migration v6")?;
+                }
+                7 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN default_action TEXT;
+                        "#,
+                    )
+                    .context("failed applying migration v7")?;
+                }
+                8 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN first_seen_at TEXT NOT NULL DEFAULT '';
+                        UPDATE seen_progress SET first_seen_at = last_seen_at;
+                        "#,
+                    )
+                    .context("failed applying migration v8")?;
+                }
+                9 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN airing_status TEXT;
+                        "#,
+                    )
+                    .context("failed applying migration v9")?;
+                }
+                10 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN rating INTEGER CHECK (rating BETWEEN 1 AND 10);
+                        "#,
+                    )
+                    .context("failed applying migration v10")?;
+                }
+                11 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN external_provider TEXT;
+                        ALTER TABLE seen_progress
+                        ADD COLUMN external_id TEXT;
+                        "#,
+                    )
+                    .context("failed applying migration v11")?;
+                }
+                12 => {
+                    tx.execute_batch(
+                        r#"
+                        ALTER TABLE seen_progress
+                        ADD COLUMN watched_episodes TEXT;
+                        "#,
+                    )
+                    .context("failed applying migration v12")?;
+                }
                 _ => {
                     return Err(anyhow!(
                         "missing migration for schema version {next_version}"
@@ -90,100 +591,422 @@ impl Database {
         Ok(())
     }
 
+    /// Fails unless the schema is exactly the version this build writes, for opening a database
+    /// without migrating it.
+    pub fn ensure_current_schema(&self) -> Result<()> {
+        let version = self.schema_version()?;
+        if version > SCHEMA_VERSION {
+            bail!("database schema version {version} is newer than supported {SCHEMA_VERSION}");
+        }
+        if version < SCHEMA_VERSION {
+            bail!(
+                "database schema version {version} is older than {SCHEMA_VERSION} and --no-migrate was given; run `anitrack migrate` to upgrade it"
+            );
+        }
+        Ok(())
+    }
+
+    pub fn schema_version(&self) -> Result<i64> {
+        Ok(self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    /// Writes entries verbatim (including timestamps), replacing rows with the same `ani_id`.
+    /// All entries are restored in one transaction.
+    pub fn restore_seen_entries(&self, entries: &[SeenEntry]) -> Result<()> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("failed to start restore transaction")?;
+        for entry in entries {
+            tx.execute(
+                r#"
+                INSERT INTO seen_progress
+                    (ani_id, title, last_episode, last_seen_at, intro_skip_seconds, tags, kind,
+                     total_eps_override, default_action, first_seen_at, airing_status, rating,
+                     external_provider, external_id, watched_episodes)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                ON CONFLICT(ani_id) DO UPDATE SET
+                    title = excluded.title,
+                    last_episode = excluded.last_episode,
+                    last_seen_at = excluded.last_seen_at,
+                    intro_skip_seconds = excluded.intro_skip_seconds,
+                    tags = excluded.tags,
+                    kind = excluded.kind,
+                    total_eps_override = excluded.total_eps_override,
+                    default_action = excluded.default_action,
+                    first_seen_at = excluded.first_seen_at,
+                    airing_status = excluded.airing_status,
+                    rating = excluded.rating,
+                    external_provider = excluded.external_provider,
+                    external_id = excluded.external_id,
+                    watched_episodes = excluded.watched_episodes
+                "#,
+                params![
+                    entry.ani_id,
+                    entry.title,
+                    entry.last_episode,
+                    entry.last_seen_at,
+                    entry.intro_skip_seconds,
+                    normalize_tags(&entry.tags).join(","),
+                    entry.kind.as_str(),
+                    entry.total_eps_override,
+                    entry.default_action,
+                    entry.first_seen_at,
+                    entry.airing_status,
+                    entry.rating,
+                    entry.external_provider,
+                    entry.external_id,
+                    joined_watched_episodes(entry.watched_episodes.as_deref()),
+                ],
+            )
+            .with_context(|| format!("failed restoring entry {}", entry.ani_id))?;
+        }
+        tx.commit().context("failed to commit restore")?;
+        Ok(())
+    }
+
     pub fn upsert_seen(&self, ani_id: &str, title: &str, episode: &str) -> Result<()> {
-        let now = Utc::now().to_rfc3339();
+        self.record_seen(ani_id, title, None, episode)
+    }
+
+    /// `upsert_seen` that also stores an episode count kept outside the title. A `None` count
+    /// leaves an existing override in place.
+    pub fn record_seen(
+        &self,
+        ani_id: &str,
+        title: &str,
+        total_eps_override: Option<u32>,
+        episode: &str,
+    ) -> Result<()> {
+        self.record_seen_at(ani_id, title, total_eps_override, episode, Utc::now())
+    }
+
+    /// `record_seen` at a given clock reading. If the clock has gone backward since the newest
+    /// entry was recorded, the entry is stamped 1ms after it instead, so `last_seen` and the
+    /// watched order still put it first.
+    pub fn record_seen_at(
+        &self,
+        ani_id: &str,
+        title: &str,
+        total_eps_override: Option<u32>,
+        episode: &str,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let newest: Option<String> =
+            self.conn
+                .query_row("SELECT MAX(last_seen_at) FROM seen_progress", [], |row| {
+                    row.get(0)
+                })?;
+        let newest = newest
+            .and_then(|raw| DateTime::parse_from_rfc3339(&raw).ok())
+            .map(|newest| newest.with_timezone(&Utc));
+        let now = monotonic_seen_at(now, newest).to_rfc3339();
         self.conn.execute(
             r#"
-            INSERT INTO seen_progress (ani_id, title, last_episode, last_seen_at)
-            VALUES (?1, ?2, ?3, ?4)
+            INSERT INTO seen_progress
+                (ani_id, title, last_episode, last_seen_at, first_seen_at, total_eps_override)
+            VALUES (?1, ?2, ?3, ?4, ?4, ?5)
             ON CONFLICT(ani_id) DO UPDATE SET
                 title = excluded.title,
                 last_episode = excluded.last_episode,
-                last_seen_at = excluded.last_seen_at
+                last_seen_at = excluded.last_seen_at,
+                total_eps_override = COALESCE(excluded.total_eps_override, total_eps_override)
             "#,
-            params![ani_id, title, episode, now],
+            params![ani_id, title, episode, now, total_eps_override],
         )?;
         Ok(())
     }
 
-    pub fn delete_seen(&self, ani_id: &str) -> Result<bool> {
+    /// Rewrites titles and episode-count overrides in one transaction, leaving progress and
+    /// timestamps untouched. Returns how many entries were updated.
+    pub fn set_titles_and_totals(&self, updates: &[(String, String, u32)]) -> Result<usize> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("failed to start title update transaction")?;
+        let mut updated = 0;
+        for (ani_id, title, total) in updates {
+            updated += tx
+                .execute(
+                    "UPDATE seen_progress SET title = ?1, total_eps_override = ?2 WHERE ani_id = ?3",
+                    params![title, total, ani_id],
+                )
+                .with_context(|| format!("failed updating title of {ani_id}"))?;
+        }
+        tx.commit().context("failed to commit title update")?;
+        Ok(updated)
+    }
+
+    pub fn set_intro_skip(&self, ani_id: &str, seconds: u32) -> Result<bool> {
         let changed = self.conn.execute(
-            "DELETE FROM seen_progress WHERE ani_id = ?1",
-            params![ani_id],
+            "UPDATE seen_progress SET intro_skip_seconds = ?1 WHERE ani_id = ?2",
+            params![seconds, ani_id],
         )?;
         Ok(changed > 0)
     }
 
-    pub fn last_seen(&self) -> Result<Option<SeenEntry>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT ani_id, title, last_episode, last_seen_at FROM seen_progress ORDER BY last_seen_at DESC LIMIT 1",
+    /// Replaces the tags of an entry; tags are normalized with [`normalize_tags`] before storing.
+    pub fn set_tags<S: AsRef<str>>(&self, ani_id: &str, tags: &[S]) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE seen_progress SET tags = ?1 WHERE ani_id = ?2",
+            params![normalize_tags(tags).join(","), ani_id],
         )?;
-        let mut rows = stmt.query([])?;
-        if let Some(row) = rows.next()? {
-            return Ok(Some(SeenEntry {
-                ani_id: row.get(0)?,
-                title: row.get(1)?,
-                last_episode: row.get(2)?,
-                last_seen_at: row.get(3)?,
-            }));
-        }
-        Ok(None)
+        Ok(changed > 0)
     }
 
-    pub fn list_seen(&self) -> Result<Vec<SeenEntry>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT ani_id, title, last_episode, last_seen_at FROM seen_progress ORDER BY last_seen_at DESC",
+    /// Sets or (with `None`) clears the TUI action preselected for an entry.
+    pub fn set_default_action(&self, ani_id: &str, action: Option<&str>) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE seen_progress SET default_action = ?1 WHERE ani_id = ?2",
+            params![action, ani_id],
         )?;
-        let rows = stmt.query_map([], |row| {
-            Ok(SeenEntry {
-                ani_id: row.get(0)?,
-                title: row.get(1)?,
-                last_episode: row.get(2)?,
-                last_seen_at: row.get(3)?,
-            })
-        })?;
-
-        let mut out = Vec::new();
-        for row in rows {
-            out.push(row?);
-        }
-        Ok(out)
+        Ok(changed > 0)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::{thread, time::Duration};
 
-    fn in_memory_db() -> Database {
-        Database {
-            conn: Connection::open_in_memory().expect("failed to open in-memory db"),
+    /// Sets or (with `None`) clears an entry's rating. Ratings outside [`RATING_RANGE`] are
+    /// rejected.
+    pub fn set_rating(&self, ani_id: &str, rating: Option<u8>) -> Result<bool> {
+        if let Some(rating) = rating
+            && !RATING_RANGE.contains(&rating)
+        {
+            return Err(anyhow!(
+                "rating must be between {} and {}, got {rating}",
+                RATING_RANGE.start(),
+                RATING_RANGE.end()
+            ));
         }
+        let changed = self.conn.execute(
+            "UPDATE seen_progress SET rating = ?1 WHERE ani_id = ?2",
+            params![rating, ani_id],
+        )?;
+        Ok(changed > 0)
     }
 
-    #[test]
-    fn upsert_updates_existing_row() {
-        let db = in_memory_db();
-        db.migrate().expect("migration should succeed");
+    /// Links an entry to a show on an external tracker as `(provider, id)`, or (with `None`)
+    /// removes the link.
+    pub fn set_external_link(&self, ani_id: &str, link: Option<(&str, &str)>) -> Result<bool> {
+        let (provider, id) = link.unzip();
+        let changed = self.conn.execute(
+            "UPDATE seen_progress SET external_provider = ?1, external_id = ?2 WHERE ani_id = ?3",
+            params![provider, id, ani_id],
+        )?;
+        Ok(changed > 0)
+    }
 
-        db.upsert_seen("show-1", "Show One", "1")
-            .expect("insert should succeed");
-        thread::sleep(Duration::from_millis(2));
-        db.upsert_seen("show-1", "Show One Renamed", "2")
-            .expect("update should succeed");
+    /// Adds `episode` to an entry's watched episodes, starting the set if the entry had none.
+    /// Returns whether the entry exists.
+    pub fn add_watched_episode(&self, ani_id: &str, episode: &str) -> Result<bool> {
+        let existing: Option<Option<String>> = self
+            .conn
+            .query_row(
+                "SELECT watched_episodes FROM seen_progress WHERE ani_id = ?1",
+                params![ani_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(existing) = existing else {
+            return Ok(false);
+        };
+        let watched =
+            normalize_watched_episodes(existing.iter().map(String::as_str).chain([episode]));
+        self.conn.execute(
+            "UPDATE seen_progress SET watched_episodes = ?1 WHERE ani_id = ?2",
+            params![watched.join(","), ani_id],
+        )?;
+        Ok(true)
+    }
 
-        let latest = db
-            .last_seen()
-            .expect("query should succeed")
-            .expect("row should exist");
-        assert_eq!(latest.ani_id, "show-1");
-        assert_eq!(latest.title, "Show One Renamed");
-        assert_eq!(latest.last_episode, "2");
+    /// Stores the airing status last seen in show metadata; `None` clears it.
+    pub fn set_airing_status(&self, ani_id: &str, status: Option<&str>) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE seen_progress SET airing_status = ?1 WHERE ani_id = ?2",
+            params![status, ani_id],
+        )?;
+        Ok(changed > 0)
     }
 
-    #[test]
-    fn list_seen_returns_most_recent_first() {
+    pub fn set_kind(&self, ani_id: &str, kind: EntryKind) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE seen_progress SET kind = ?1 WHERE ani_id = ?2",
+            params![kind.as_str(), ani_id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    pub fn delete_seen(&self, ani_id: &str) -> Result<bool> {
+        let changed = self.conn.execute(
+            "DELETE FROM seen_progress WHERE ani_id = ?1",
+            params![ani_id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Overwrites `merged.ani_id`'s row with `merged` and deletes `drop_id`, in one transaction.
+    /// Fails (changing nothing) if either entry does not exist.
+    pub fn merge_seen(&self, merged: &SeenEntry, drop_id: &str) -> Result<()> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("failed to start merge transaction")?;
+        let updated = tx
+            .execute(
+                r#"
+                UPDATE seen_progress SET
+                    title = ?2,
+                    last_episode = ?3,
+                    last_seen_at = ?4,
+                    intro_skip_seconds = ?5,
+                    tags = ?6,
+                    kind = ?7,
+                    total_eps_override = ?8,
+                    default_action = ?9,
+                    first_seen_at = ?10,
+                    airing_status = ?11,
+                    rating = ?12,
+                    external_provider = ?13,
+                    external_id = ?14,
+                    watched_episodes = ?15
+                WHERE ani_id = ?1
+                "#,
+                params![
+                    merged.ani_id,
+                    merged.title,
+                    merged.last_episode,
+                    merged.last_seen_at,
+                    merged.intro_skip_seconds,
+                    normalize_tags(&merged.tags).join(","),
+                    merged.kind.as_str(),
+                    merged.total_eps_override,
+                    merged.default_action,
+                    merged.first_seen_at,
+                    merged.airing_status,
+                    merged.rating,
+                    merged.external_provider,
+                    merged.external_id,
+                    joined_watched_episodes(merged.watched_episodes.as_deref()),
+                ],
+            )
+            .with_context(|| format!("failed updating entry {}", merged.ani_id))?;
+        if updated == 0 {
+            return Err(anyhow!("no tracked entry with id `{}`", merged.ani_id));
+        }
+        let deleted = tx
+            .execute(
+                "DELETE FROM seen_progress WHERE ani_id = ?1",
+                params![drop_id],
+            )
+            .with_context(|| format!("failed deleting entry {drop_id}"))?;
+        if deleted == 0 {
+            return Err(anyhow!("no tracked entry with id `{drop_id}`"));
+        }
+        tx.commit().context("failed to commit merge")?;
+        Ok(())
+    }
+
+    /// Deletes the given entries in one transaction, returning how many rows were removed.
+    pub fn delete_seen_entries(&self, ani_ids: &[String]) -> Result<usize> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("failed to start delete transaction")?;
+        let mut removed = 0;
+        for ani_id in ani_ids {
+            removed += tx
+                .execute(
+                    "DELETE FROM seen_progress WHERE ani_id = ?1",
+                    params![ani_id],
+                )
+                .with_context(|| format!("failed deleting entry {ani_id}"))?;
+        }
+        tx.commit().context("failed to commit delete")?;
+        Ok(removed)
+    }
+
+    /// Entry counts grouped by watch status. No status is stored yet, so every entry counts as
+    /// `watching`.
+    pub fn status_counts(&self) -> Result<Vec<(String, u64)>> {
+        let total: u64 = self
+            .conn
+            .query_row("SELECT COUNT(1) FROM seen_progress", [], |row| row.get(0))?;
+        Ok(vec![("watching".to_string(), total)])
+    }
+
+    pub fn get_seen(&self, ani_id: &str) -> Result<Option<SeenEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {SEEN_COLUMNS} FROM seen_progress WHERE ani_id = ?1"
+        ))?;
+        let mut rows = stmt.query(params![ani_id])?;
+        if let Some(row) = rows.next()? {
+            return Ok(Some(seen_entry_from_row(row)?));
+        }
+        Ok(None)
+    }
+
+    pub fn last_seen(&self) -> Result<Option<SeenEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {SEEN_COLUMNS} FROM seen_progress ORDER BY last_seen_at DESC LIMIT 1"
+        ))?;
+        let mut rows = stmt.query([])?;
+        if let Some(row) = rows.next()? {
+            return Ok(Some(seen_entry_from_row(row)?));
+        }
+        Ok(None)
+    }
+
+    pub fn list_seen(&self) -> Result<Vec<SeenEntry>> {
+        self.list_seen_sorted(ListSort::Watched.into())
+    }
+
+    pub fn list_seen_sorted(&self, order: ListOrder) -> Result<Vec<SeenEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {SEEN_COLUMNS} FROM seen_progress ORDER BY {}",
+            order.order_by()
+        ))?;
+        let rows = stmt.query_map([], seen_entry_from_row)?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row?);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{thread, time::Duration};
+
+    fn in_memory_db() -> Database {
+        Database {
+            conn: Connection::open_in_memory().expect("failed to open in-memory db"),
+        }
+    }
+
+    #[test]
+    fn upsert_updates_existing_row() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+
+        db.upsert_seen("show-1", "Show One", "1")
+            .expect("insert should succeed");
+        thread::sleep(Duration::from_millis(2));
+        db.upsert_seen("show-1", "Show One Renamed", "2")
+            .expect("update should succeed");
+
+        let latest = db
+            .last_seen()
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(latest.ani_id, "show-1");
+        assert_eq!(latest.title, "Show One Renamed");
+        assert_eq!(latest.last_episode, "2");
+    }
+
+    #[test]
+    fn list_seen_returns_most_recent_first() {
         let db = in_memory_db();
         db.migrate().expect("migration should succeed");
 
@@ -316,6 +1139,737 @@ mod tests {
         assert_eq!(existing_row, 1);
     }
 
+    #[test]
+    fn migrate_backfills_first_seen_from_last_seen() {
+        let db = in_memory_db();
+        db.conn
+            .execute_batch(
+                r#"
+                CREATE TABLE seen_progress (
+                    ani_id TEXT PRIMARY KEY,
+                    title TEXT NOT NULL,
+                    last_episode TEXT NOT NULL,
+                    last_seen_at TEXT NOT NULL
+                );
+                INSERT INTO seen_progress (ani_id, title, last_episode, last_seen_at)
+                VALUES ('show-1', 'Show One', '3', '2026-03-01T00:00:00+00:00');
+                "#,
+            )
+            .expect("v1 schema should be created");
+        db.conn
+            .pragma_update(None, "user_version", 1)
+            .expect("v1 user_version should be set");
+        db.migrate().expect("v1 schema should migrate to latest");
+
+        let row = db
+            .get_seen("show-1")
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.first_seen_at, "2026-03-01T00:00:00+00:00");
+
+        thread::sleep(Duration::from_millis(2));
+        db.upsert_seen("show-1", "Show One", "4")
+            .expect("upsert should succeed");
+        let row = db
+            .get_seen("show-1")
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.first_seen_at, "2026-03-01T00:00:00+00:00");
+        assert_ne!(row.last_seen_at, row.first_seen_at);
+    }
+
+    #[test]
+    fn list_seen_sorted_by_added_breaks_ties_by_last_watched() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        let entry = |ani_id: &str, first_seen_at: &str, last_seen_at: &str| SeenEntry {
+            ani_id: ani_id.to_string(),
+            title: ani_id.to_string(),
+            last_episode: "1".to_string(),
+            last_seen_at: last_seen_at.to_string(),
+            first_seen_at: first_seen_at.to_string(),
+            ..SeenEntry::default()
+        };
+        db.restore_seen_entries(&[
+            entry(
+                "old-rewatched",
+                "2025-01-01T00:00:00+00:00",
+                "2026-05-01T00:00:00+00:00",
+            ),
+            entry(
+                "new",
+                "2026-04-01T00:00:00+00:00",
+                "2026-04-02T00:00:00+00:00",
+            ),
+            // Backfilled rows share a first-seen time; the later-watched one comes first.
+            entry(
+                "backfilled-a",
+                "2025-06-01T00:00:00+00:00",
+                "2025-06-01T00:00:00+00:00",
+            ),
+            entry(
+                "backfilled-b",
+                "2025-06-01T00:00:00+00:00",
+                "2025-07-01T00:00:00+00:00",
+            ),
+        ])
+        .expect("restore should succeed");
+
+        let ids = |sort: ListSort| {
+            db.list_seen_sorted(sort.into())
+                .expect("list should succeed")
+                .into_iter()
+                .map(|row| row.ani_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(ListSort::Added),
+            ["new", "backfilled-b", "backfilled-a", "old-rewatched"]
+        );
+        assert_eq!(
+            ids(ListSort::Watched),
+            ["old-rewatched", "new", "backfilled-b", "backfilled-a"]
+        );
+    }
+
+    #[test]
+    fn list_seen_sorted_breaks_ties_by_the_configured_order_then_id() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        let entry = |ani_id: &str, title: &str, first_seen_at: &str| SeenEntry {
+            ani_id: ani_id.to_string(),
+            title: title.to_string(),
+            last_episode: "1".to_string(),
+            last_seen_at: "2026-05-01T00:00:00+00:00".to_string(),
+            first_seen_at: first_seen_at.to_string(),
+            ..SeenEntry::default()
+        };
+        // All three tie on last watched (and rating), the primary key of both sorts below.
+        db.restore_seen_entries(&[
+            entry("b", "banana", "2026-01-01T00:00:00+00:00"),
+            entry("c", "Apple", "2026-02-01T00:00:00+00:00"),
+            entry("a", "apple", "2026-02-01T00:00:00+00:00"),
+        ])
+        .expect("restore should succeed");
+
+        let ids = |sort, tie_break| {
+            db.list_seen_sorted(ListOrder { sort, tie_break })
+                .expect("list should succeed")
+                .into_iter()
+                .map(|row| row.ani_id)
+                .collect::<Vec<_>>()
+        };
+        for sort in [ListSort::Watched, ListSort::Rating] {
+            assert_eq!(ids(sort, SortTieBreak::Title), ["a", "c", "b"]);
+            assert_eq!(ids(sort, SortTieBreak::Added), ["a", "c", "b"]);
+            assert_eq!(ids(sort, SortTieBreak::LastSeen), ["a", "b", "c"]);
+        }
+        assert_eq!(SortTieBreak::parse(" Title "), Some(SortTieBreak::Title));
+        assert_eq!(
+            SortTieBreak::parse("last_seen"),
+            Some(SortTieBreak::LastSeen)
+        );
+        assert_eq!(SortTieBreak::parse("rating"), None);
+    }
+
+    #[test]
+    fn record_seen_stays_newest_when_the_clock_goes_backward() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        let at = |raw: &str| {
+            DateTime::parse_from_rfc3339(raw)
+                .expect("timestamp should parse")
+                .with_timezone(&Utc)
+        };
+        db.record_seen_at("show-1", "Show One", None, "1", at("2026-03-01T12:00:00Z"))
+            .expect("record should succeed");
+        // The clock jumps back an hour before the next watch.
+        db.record_seen_at("show-2", "Show Two", None, "1", at("2026-03-01T11:00:00Z"))
+            .expect("record should succeed");
+
+        let newest = db
+            .last_seen()
+            .expect("query should succeed")
+            .expect("entry should exist");
+        assert_eq!(newest.ani_id, "show-2");
+        assert_eq!(
+            at(&newest.last_seen_at),
+            at("2026-03-01T12:00:00.001Z"),
+            "nudged 1ms past the previous newest entry"
+        );
+
+        // A stored time far in the future is not skew, so the clock is used as-is.
+        let future = at("2030-01-01T00:00:00Z");
+        assert_eq!(
+            monotonic_seen_at(at("2026-03-01T11:00:00Z"), Some(future)),
+            at("2026-03-01T11:00:00Z")
+        );
+        assert_eq!(
+            monotonic_seen_at(at("2026-03-01T13:00:00Z"), Some(at("2026-03-01T12:00:00Z"))),
+            at("2026-03-01T13:00:00Z")
+        );
+    }
+
+    #[test]
+    fn set_rating_validates_range_and_rating_sort_puts_unrated_last() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("a", "A", "1")
+            .expect("upsert should succeed");
+        db.upsert_seen("b", "B", "1")
+            .expect("upsert should succeed");
+        db.upsert_seen("c", "C", "1")
+            .expect("upsert should succeed");
+
+        assert!(db.set_rating("a", Some(9)).expect("rating should save"));
+        assert!(db.set_rating("b", Some(4)).expect("rating should save"));
+        assert!(
+            !db.set_rating("missing", Some(5))
+                .expect("rating should run")
+        );
+        let err = db
+            .set_rating("c", Some(11))
+            .expect_err("out-of-range rating should fail");
+        assert!(err.to_string().contains("between 1 and 10"));
+
+        let rows = db
+            .list_seen_sorted(ListSort::Rating.into())
+            .expect("list should succeed");
+        let ids = rows
+            .iter()
+            .map(|row| row.ani_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert_eq!(rows[0].rating, Some(9));
+        assert_eq!(rows[2].rating, None);
+
+        assert!(db.set_rating("a", None).expect("clearing should succeed"));
+        let cleared = db.get_seen("a").expect("get should succeed").expect("row");
+        assert_eq!(cleared.rating, None);
+    }
+
+    #[test]
+    fn normalize_watched_episodes_dedupes_and_sorts_numerically() {
+        assert_eq!(
+            normalize_watched_episodes(["5, 3", "10", " 3 ", "", "2.5", "Special", "0"]),
+            vec!["0", "2.5", "3", "5", "10", "Special"]
+        );
+        assert!(normalize_watched_episodes([""]).is_empty());
+    }
+
+    #[test]
+    fn add_watched_episode_starts_and_extends_the_set_out_of_order() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("a", "A", "5")
+            .expect("upsert should succeed");
+        let row = db.get_seen("a").expect("get should succeed").expect("row");
+        assert_eq!(row.watched_episodes, None);
+
+        for episode in ["5", "3", "5"] {
+            assert!(
+                db.add_watched_episode("a", episode)
+                    .expect("add should succeed")
+            );
+        }
+        assert!(
+            !db.add_watched_episode("missing", "1")
+                .expect("add should run")
+        );
+        let row = db.get_seen("a").expect("get should succeed").expect("row");
+        assert_eq!(
+            row.watched_episodes,
+            Some(vec!["3".to_string(), "5".to_string()])
+        );
+        // The high-water mark is untouched by the set.
+        assert_eq!(row.last_episode, "5");
+    }
+
+    #[test]
+    fn set_external_link_stores_and_clears_both_columns() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("a", "A", "1")
+            .expect("upsert should succeed");
+        let unlinked = db.get_seen("a").expect("get should succeed").expect("row");
+        assert_eq!(unlinked.external_provider, None);
+        assert_eq!(unlinked.external_id, None);
+
+        assert!(
+            db.set_external_link("a", Some(("anilist", "154587")))
+                .expect("link should save")
+        );
+        assert!(
+            !db.set_external_link("missing", Some(("mal", "1")))
+                .expect("link should run")
+        );
+        let linked = db.get_seen("a").expect("get should succeed").expect("row");
+        assert_eq!(linked.external_provider.as_deref(), Some("anilist"));
+        assert_eq!(linked.external_id.as_deref(), Some("154587"));
+
+        // Progress updates leave the link alone.
+        db.upsert_seen("a", "A", "2")
+            .expect("upsert should succeed");
+        let advanced = db.get_seen("a").expect("get should succeed").expect("row");
+        assert_eq!(advanced.external_id.as_deref(), Some("154587"));
+
+        assert!(
+            db.set_external_link("a", None)
+                .expect("clearing should succeed")
+        );
+        let cleared = db.get_seen("a").expect("get should succeed").expect("row");
+        assert_eq!(cleared.external_provider, None);
+        assert_eq!(cleared.external_id, None);
+    }
+
+    #[test]
+    fn migrate_upgrades_from_v2_with_default_intro_skip() {
+        let db = in_memory_db();
+        db.conn
+            .execute_batch(
+                r#"
+                CREATE TABLE seen_progress (
+                    ani_id TEXT PRIMARY KEY,
+                    title TEXT NOT NULL,
+                    last_episode TEXT NOT NULL,
+                    last_seen_at TEXT NOT NULL
+                );
+                CREATE INDEX idx_seen_progress_seen_at ON seen_progress(last_seen_at DESC);
+                INSERT INTO seen_progress (ani_id, title, last_episode, last_seen_at)
+                VALUES ('show-3', 'Show Three', '5', '2026-03-01T00:00:00+00:00');
+                "#,
+            )
+            .expect("v2 schema should be created");
+        db.conn
+            .pragma_update(None, "user_version", 2)
+            .expect("v2 user_version should be set");
+
+        db.migrate().expect("v2 schema should migrate to latest");
+
+        let row = db
+            .last_seen()
+            .expect("query should succeed")
+            .expect("v2 row should survive migration");
+        assert_eq!(row.ani_id, "show-3");
+        assert_eq!(row.intro_skip_seconds, 0);
+        assert!(row.tags.is_empty());
+    }
+
+    #[test]
+    fn merge_seen_updates_keep_and_deletes_drop_atomically() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("keep", "Keep", "3")
+            .expect("insert should succeed");
+        db.upsert_seen("drop", "Drop", "7")
+            .expect("insert should succeed");
+        let mut merged = db
+            .get_seen("keep")
+            .expect("query should succeed")
+            .expect("row should exist");
+        merged.last_episode = "7".to_string();
+
+        let err = db
+            .merge_seen(&merged, "missing")
+            .expect_err("unknown drop id should fail");
+        assert!(err.to_string().contains("missing"));
+        let unchanged = db
+            .get_seen("keep")
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(unchanged.last_episode, "3");
+
+        db.merge_seen(&merged, "drop")
+            .expect("merge should succeed");
+        let rows = db.list_seen().expect("list should succeed");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].ani_id, "keep");
+        assert_eq!(rows[0].last_episode, "7");
+        assert!(db.get_seen("drop").expect("query should succeed").is_none());
+    }
+
+    #[test]
+    fn record_seen_keeps_total_override_when_not_given() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.record_seen("show-1", "Naruto", Some(220), "3")
+            .expect("insert should succeed");
+        db.upsert_seen("show-1", "Naruto", "4")
+            .expect("upsert should succeed");
+        let row = db
+            .get_seen("show-1")
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.total_eps_override, Some(220));
+        assert_eq!(row.last_episode, "4");
+
+        db.upsert_seen("show-2", "Bleach (366 episodes)", "1")
+            .expect("insert should succeed");
+        let updated = db
+            .set_titles_and_totals(&[("show-2".to_string(), "Bleach".to_string(), 366)])
+            .expect("update should succeed");
+        assert_eq!(updated, 1);
+        let row = db
+            .get_seen("show-2")
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.title, "Bleach");
+        assert_eq!(row.total_eps_override, Some(366));
+        assert_eq!(row.last_episode, "1");
+    }
+
+    #[test]
+    fn airing_status_survives_progress_updates() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("show-1", "Show One", "1")
+            .expect("insert should succeed");
+        assert!(
+            db.set_airing_status("show-1", Some("airing"))
+                .expect("update should succeed")
+        );
+        db.upsert_seen("show-1", "Show One", "2")
+            .expect("upsert should succeed");
+        let row = db
+            .get_seen("show-1")
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.airing_status.as_deref(), Some("airing"));
+        assert!(
+            !db.set_airing_status("missing", Some("finished"))
+                .expect("update should succeed")
+        );
+    }
+
+    #[test]
+    fn default_action_can_be_set_and_cleared() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("show-1", "Show One", "1")
+            .expect("insert should succeed");
+        let default_action = |db: &Database| {
+            db.get_seen("show-1")
+                .expect("query should succeed")
+                .expect("row should exist")
+                .default_action
+        };
+        assert_eq!(default_action(&db), None);
+
+        assert!(
+            db.set_default_action("show-1", Some("select"))
+                .expect("update should succeed")
+        );
+        db.upsert_seen("show-1", "Show One", "2")
+            .expect("upsert should succeed");
+        assert_eq!(default_action(&db).as_deref(), Some("select"));
+
+        assert!(
+            db.set_default_action("show-1", None)
+                .expect("update should succeed")
+        );
+        assert_eq!(default_action(&db), None);
+        assert!(
+            !db.set_default_action("missing", Some("next"))
+                .expect("update should succeed")
+        );
+    }
+
+    #[test]
+    fn kind_defaults_to_series_and_survives_upsert() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("movie-1", "Perfect Blue", "1")
+            .expect("insert should succeed");
+        let row = db
+            .last_seen()
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.kind, EntryKind::Series);
+
+        assert!(
+            db.set_kind("movie-1", EntryKind::Movie)
+                .expect("update should succeed")
+        );
+        db.upsert_seen("movie-1", "Perfect Blue", "1")
+            .expect("upsert should succeed");
+        let row = db
+            .last_seen()
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.kind, EntryKind::Movie);
+    }
+
+    #[test]
+    fn set_tags_normalizes_and_survives_upsert() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("show-1", "Show One", "1")
+            .expect("insert should succeed");
+
+        assert!(
+            db.set_tags("show-1", &[" Rewatch , With Partner", "rewatch", ""])
+                .expect("update should succeed")
+        );
+        assert!(
+            !db.set_tags("missing", &["drama"])
+                .expect("update should succeed")
+        );
+        db.upsert_seen("show-1", "Show One", "2")
+            .expect("upsert should succeed");
+
+        let row = db
+            .last_seen()
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.tags, vec!["rewatch", "with partner"]);
+
+        db.set_tags::<&str>("show-1", &[])
+            .expect("clearing tags should succeed");
+        let row = db
+            .last_seen()
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert!(row.tags.is_empty());
+    }
+
+    #[test]
+    fn set_intro_skip_persists_and_survives_upsert() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("show-1", "Show One", "1")
+            .expect("insert should succeed");
+
+        assert!(
+            db.set_intro_skip("show-1", 85)
+                .expect("update should succeed")
+        );
+        assert!(
+            !db.set_intro_skip("missing", 10)
+                .expect("update should succeed")
+        );
+        db.upsert_seen("show-1", "Show One", "2")
+            .expect("progress update should succeed");
+
+        let row = db
+            .last_seen()
+            .expect("query should succeed")
+            .expect("row should exist");
+        assert_eq!(row.last_episode, "2");
+        assert_eq!(row.intro_skip_seconds, 85);
+    }
+
+    #[test]
+    fn ensure_current_schema_rejects_unmigrated_databases() {
+        let db = in_memory_db();
+        let err = db
+            .ensure_current_schema()
+            .expect_err("an unmigrated database should be refused");
+        assert!(
+            err.to_string()
+                .contains(&format!("schema version 0 is older than {SCHEMA_VERSION}")),
+            "{err}"
+        );
+        assert_eq!(db.schema_version().expect("version should be readable"), 0);
+
+        db.migrate().expect("migration should succeed");
+        db.ensure_current_schema()
+            .expect("a migrated database should be accepted");
+
+        db.conn
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .expect("user_version should be settable");
+        let err = db
+            .ensure_current_schema()
+            .expect_err("a newer schema should be refused");
+        assert!(err.to_string().contains("is newer than supported"), "{err}");
+    }
+
+    #[test]
+    fn migrate_refuses_foreign_schema_unless_forced() {
+        let db = in_memory_db();
+        db.conn
+            .execute_batch("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT);")
+            .expect("foreign table should be created");
+
+        let err = db.migrate().expect_err("foreign schema should be refused");
+        let message = err.to_string();
+        assert!(
+            message.contains("doesn't look like an anitrack database"),
+            "{message}"
+        );
+        assert!(message.contains("notes"), "{message}");
+        assert!(
+            !db.conn
+                .query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE name = 'seen_progress'",
+                    [],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map(|count| count > 0)
+                .expect("sqlite_master should be readable"),
+            "nothing should be created"
+        );
+
+        let err = db
+            .ensure_anitrack_database()
+            .expect_err("the check should refuse the same file");
+        assert!(err.to_string().contains("notes"), "{err}");
+
+        db.migrate_allowing_foreign_tables()
+            .expect("forced migration should succeed");
+        db.ensure_anitrack_database()
+            .expect("a versioned database passes the check");
+        db.upsert_seen("show-1", "Show One", "1")
+            .expect("anitrack tables should work");
+        // Once versioned, the file is ours and later opens migrate normally.
+        db.migrate().expect("versioned database should migrate");
+
+        let conflicting = in_memory_db();
+        conflicting
+            .conn
+            .execute_batch("CREATE TABLE seen_progress (id INTEGER PRIMARY KEY);")
+            .expect("conflicting table should be created");
+        let err = conflicting
+            .migrate()
+            .expect_err("a different seen_progress should be refused");
+        assert!(err.to_string().contains("no ani_id column"), "{err}");
+
+        // A database from before schema versioning has only a v1 seen_progress table.
+        let legacy = in_memory_db();
+        legacy
+            .conn
+            .execute_batch(
+                "CREATE TABLE seen_progress (ani_id TEXT PRIMARY KEY, title TEXT NOT NULL, last_episode TEXT NOT NULL, last_seen_at TEXT NOT NULL);",
+            )
+            .expect("legacy table should be created");
+        legacy.migrate().expect("legacy database should migrate");
+    }
+
+    #[test]
+    fn classify_open_error_detects_non_database_file() {
+        let path = std::env::temp_dir().join(format!(
+            "anitrack-not-a-db-{}-{}.db",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::write(
+            &path,
+            "this is definitely not a sqlite database file\n".repeat(20),
+        )
+        .expect("garbage file should be written");
+
+        let err = Database::open(&path)
+            .and_then(|db| db.migrate())
+            .expect_err("garbage file should fail to migrate");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(classify_open_error(&err), Some(DbOpenIssue::Corrupt));
+    }
+
+    #[test]
+    fn set_journal_mode_applies_requested_mode() {
+        let dir = std::env::temp_dir().join(format!(
+            "anitrack-journal-{}-{}",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let path = dir.join("anitrack.db");
+        let db = Database::open(&path).expect("db should open");
+
+        db.set_journal_mode(JournalMode::Truncate)
+            .expect("truncate mode should apply");
+        assert_eq!(db.journal_mode().expect("mode should read"), "truncate");
+        db.set_journal_mode(JournalMode::Wal)
+            .expect("wal mode should apply");
+        assert_eq!(db.journal_mode().expect("mode should read"), "wal");
+        db.set_journal_mode(JournalMode::Delete)
+            .expect("delete mode should apply");
+        assert_eq!(db.journal_mode().expect("mode should read"), "delete");
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn checkpoint_wal_brings_the_main_file_up_to_date() {
+        let dir = std::env::temp_dir().join(format!(
+            "anitrack-checkpoint-{}-{}",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let path = dir.join("anitrack.db");
+        let db = Database::open(&path).expect("db should open");
+        db.set_journal_mode(JournalMode::Wal)
+            .expect("wal mode should apply");
+        db.migrate().expect("migration should succeed");
+        db.checkpoint_wal().expect("checkpoint should succeed");
+
+        db.upsert_seen("show-1", "Show One", "3")
+            .expect("upsert should succeed");
+        // The main file alone, as a reader that ignores the WAL would see it.
+        let main_only = |name: &str| {
+            let copy = dir.join(name);
+            std::fs::copy(&path, &copy).expect("main file should be copied");
+            Database::open(&copy)
+                .expect("copy should open")
+                .list_seen()
+                .expect("copy should list")
+                .len()
+        };
+        assert_eq!(main_only("before.db"), 0);
+
+        assert!(db.checkpoint_wal().expect("checkpoint should succeed"));
+        assert_eq!(main_only("after.db"), 1);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn journal_mode_parse_accepts_known_modes_case_insensitively() {
+        assert_eq!(JournalMode::parse("WAL"), Some(JournalMode::Wal));
+        assert_eq!(JournalMode::parse(" delete "), Some(JournalMode::Delete));
+        assert_eq!(JournalMode::parse("truncate"), Some(JournalMode::Truncate));
+        assert_eq!(JournalMode::parse("memory"), None);
+    }
+
+    #[test]
+    fn classify_open_error_ignores_unrelated_errors() {
+        let err = anyhow!("something else went wrong");
+        assert_eq!(classify_open_error(&err), None);
+    }
+
+    #[test]
+    fn status_counts_reports_every_entry_as_watching() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("show-1", "Show One", "1")
+            .expect("insert should succeed");
+        db.upsert_seen("show-2", "Show Two", "4")
+            .expect("insert should succeed");
+
+        let counts = db.status_counts().expect("counts should succeed");
+        assert_eq!(counts, vec![("watching".to_string(), 2)]);
+    }
+
+    #[test]
+    fn delete_seen_entries_removes_only_existing_ids() {
+        let db = in_memory_db();
+        db.migrate().expect("migration should succeed");
+        db.upsert_seen("show-1", "Show One", "1")
+            .expect("insert should succeed");
+        db.upsert_seen("show-2", "Show Two", "4")
+            .expect("insert should succeed");
+
+        let removed = db
+            .delete_seen_entries(&["show-1".to_string(), "missing".to_string()])
+            .expect("delete should succeed");
+        assert_eq!(removed, 1);
+        let rows = db.list_seen().expect("list should succeed");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].ani_id, "show-2");
+    }
+
     #[test]
     fn migrate_rejects_future_schema_versions() {
         let db = in_memory_db();
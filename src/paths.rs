@@ -6,3 +6,8 @@ pub fn database_file_path() -> Result<PathBuf> {
     let base = dirs::data_dir().context("unable to resolve data directory")?;
     Ok(base.join("anitrack").join("anitrack.db"))
 }
+
+pub fn tui_state_file_path() -> Result<PathBuf> {
+    let base = dirs::cache_dir().context("unable to resolve cache directory")?;
+    Ok(base.join("anitrack").join("tui-state.json"))
+}